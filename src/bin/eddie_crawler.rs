@@ -3,15 +3,22 @@ extern crate eddie_crawler;
 use crate::eddie_crawler::crawler_modules::user_interface;
 
 use std::env;
-use tokio;
+use std::process;
 
 #[tokio::main]
 async fn main() {
     let args = env::args();
-    if let Err(error) = user_interface::run(args).await {
-        eprintln!("Fatal error: {}", error);
-        eprintln!("Exiting program...")
-    } else {
-        println!("Thank you for using EddieWikiCrawler.");
+    match user_interface::run(args).await {
+        Err(error) => {
+            eprintln!("Fatal error: {}", error);
+            eprintln!("Exiting program...");
+            process::exit(1);
+        },
+        Ok(code) => {
+            println!("Thank you for using EddieWikiCrawler.");
+            if code != user_interface::EXIT_FOUND {
+                process::exit(code);
+            }
+        },
     }
 }
\ No newline at end of file
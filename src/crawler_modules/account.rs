@@ -0,0 +1,123 @@
+use std::fmt;
+
+use serde_json;
+
+use super::wiki_client::WikiClient;
+
+// The actions whose rate limits the requests of the crawler count against, if the wiki sets any. MediaWiki itself
+// only limits writes, but wikis can limit these reading actions as well
+const READ_ACTIONS: [&str; 2] = ["query", "parse"];
+
+/// A struct representing the rate limit of an action, as the most hits allowed within an amount of seconds
+#[derive(Clone, PartialEq, Debug)]
+pub struct RateLimit {
+    /// The action the limit applies to, such as edit or move
+    pub action: String,
+    /// The amount of times the action can be done within the seconds
+    pub hits: u64,
+    /// The length of the period of the limit in seconds
+    pub seconds: u64,
+}
+
+impl RateLimit {
+    /// Returns the limit as the amount of hits allowed per second on average
+    pub fn per_second(&self) -> f64 {
+        self.hits as f64 / self.seconds.max(1) as f64
+    }
+}
+
+impl fmt::Display for RateLimit {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{} {} per {}s", self.action, self.hits, self.seconds)
+    }
+}
+
+/// A struct describing the rights of the logged in account that affect the crawler
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct AccountInfo {
+    /// Whether the account has the apihighlimits right, raising the amount of titles allowed per query
+    pub high_limits: bool,
+    /// Whether the account has the noratelimit right, which exempts it from the rate limits
+    pub no_rate_limit: bool,
+    /// The strictest rate limit of each action that applies to the account, sorted by the action
+    pub rate_limits: Vec<RateLimit>,
+}
+
+impl AccountInfo {
+    /// A function for finding the strictest rate limit the requests of the crawler count against
+    ///
+    /// # Returns
+    ///
+    /// * Option<&RateLimit> - The strictest limit of the reading actions, or None if the account has none
+    pub fn read_limit(&self) -> Option<&RateLimit> {
+        if self.no_rate_limit {
+            return None;
+        }
+        self.rate_limits.iter()
+            .filter(|limit| READ_ACTIONS.contains(&limit.action.as_str()))
+            .min_by(|first, second| first.per_second().total_cmp(&second.per_second()))
+    }
+}
+
+/// An async function for querying the rights and the rate limits of the logged in account with a single userinfo
+/// query. Falls back to the rights loaded when logging in if the query fails, without any rate limits
+///
+/// # Arguments
+///
+/// * 'client' - A reference to a WikiClient with a logged in api session
+///
+/// # Returns
+///
+/// * AccountInfo - The rights and the rate limits of the account
+pub async fn detect(client: &WikiClient) -> AccountInfo {
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("meta", "userinfo"),
+        ("uiprop", "rights|ratelimits"),
+    ]);
+
+    let result = match client.get(&query_map).await {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("Error querying the rights of the account, assuming no rate limits:\n{}", error);
+            let user = client.api().user();
+            return AccountInfo {
+                high_limits: user.has_right("apihighlimits"),
+                no_rate_limit: user.has_right("noratelimit"),
+                rate_limits: vec!(),
+            };
+        },
+    };
+
+    let user_info = &result["query"]["userinfo"];
+    let has_right = |right: &str| user_info["rights"].as_array()
+        .is_some_and(|rights| rights.iter().any(|value| value.as_str() == Some(right)));
+    let mut rate_limits: Vec<RateLimit> = user_info["ratelimits"].as_object()
+        .map(|limits| limits.iter().filter_map(|(action, groups)| strictest_limit(action, groups)).collect())
+        .unwrap_or_default();
+    rate_limits.sort_by(|first, second| first.action.cmp(&second.action));
+
+    AccountInfo { high_limits: has_right("apihighlimits"), no_rate_limit: has_right("noratelimit"), rate_limits }
+}
+
+/// A function for finding the strictest of the limits an action has for the different kinds of users the account
+/// counts as, such as user and newbie
+///
+/// # Arguments
+///
+/// * 'action' - A string slice with the name of the action
+/// * 'groups' - A reference to the object of the limits of the action, keyed by the kind of user
+///
+/// # Returns
+///
+/// * Option<RateLimit> - The strictest limit, or None if the action has no valid limits
+fn strictest_limit(action: &str, groups: &serde_json::Value) -> Option<RateLimit> {
+    groups.as_object()?.values()
+        .filter_map(|limit| Some(RateLimit {
+            action: action.to_string(),
+            hits: limit["hits"].as_u64()?,
+            seconds: limit["seconds"].as_u64()?,
+        }))
+        .min_by(|first, second| first.per_second().total_cmp(&second.per_second()))
+}
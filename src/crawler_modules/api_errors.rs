@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::fmt;
+
+use serde_json;
+use mediawiki::media_wiki_error::MediaWikiError;
+
+/// An enum representing the kind of an error returned by the API, deciding what the user is told to do about it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ApiErrorKind {
+    /// The client sent more requests than the account is allowed to
+    RateLimited,
+    /// The database servers of the wiki are lagging behind more than the client allowed with the maxlag parameter
+    MaxLag,
+    /// The session token sent with the request was missing or has expired
+    BadToken,
+    /// The wiki is in read-only mode, usually during maintenance
+    ReadOnly,
+    /// The account or the address of the client is blocked on the wiki
+    Blocked,
+    /// The client isn't allowed to read the wiki, such as on a private wiki or once its authorization is revoked
+    PermissionDenied,
+    /// Any other error
+    Other,
+}
+
+impl ApiErrorKind {
+    /// Returns the kind of the error with the given code, see https://www.mediawiki.org/wiki/API:Errors_and_warnings
+    pub fn from_code(code: &str) -> ApiErrorKind {
+        match code {
+            "ratelimited" => ApiErrorKind::RateLimited,
+            "maxlag" => ApiErrorKind::MaxLag,
+            "badtoken" | "notoken" => ApiErrorKind::BadToken,
+            "readonly" => ApiErrorKind::ReadOnly,
+            "blocked" | "autoblocked" => ApiErrorKind::Blocked,
+            code if code.starts_with("blocked-") => ApiErrorKind::Blocked,
+            "readapidenied" | "permissiondenied" => ApiErrorKind::PermissionDenied,
+            code if code.starts_with("mwoauth-") => ApiErrorKind::PermissionDenied,
+            _ => ApiErrorKind::Other,
+        }
+    }
+
+    /// Returns the name the kind is shown with in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiErrorKind::RateLimited => "rate_limited",
+            ApiErrorKind::MaxLag => "max_lag",
+            ApiErrorKind::BadToken => "bad_token",
+            ApiErrorKind::ReadOnly => "read_only",
+            ApiErrorKind::Blocked => "blocked",
+            ApiErrorKind::PermissionDenied => "permission_denied",
+            ApiErrorKind::Other => "other",
+        }
+    }
+
+    /// Returns what the user can do about an error of the kind, or None if there is nothing known to suggest
+    pub fn remediation(&self) -> Option<&'static str> {
+        match self {
+            ApiErrorKind::RateLimited => Some("the wiki is limiting the requests of your account — lower the rate \
+                                               with --max-rate or wait a while before crawling again"),
+            ApiErrorKind::MaxLag => Some("the servers of the wiki are lagging behind — wait a few minutes and try \
+                                          again"),
+            ApiErrorKind::BadToken => Some("your login session has expired — run the crawler again to log in anew"),
+            ApiErrorKind::ReadOnly => Some("the wiki is in read-only mode, usually for maintenance — try again \
+                                            later"),
+            ApiErrorKind::Blocked => Some("your bot account is blocked — ask an administrator of the wiki about the \
+                                           block, or crawl without logging in"),
+            ApiErrorKind::PermissionDenied => Some("your account isn't allowed to read the wiki — check the grants \
+                                                    of the bot password in secrets.txt"),
+            ApiErrorKind::Other => None,
+        }
+    }
+}
+
+/// A struct representing an error returned by the API instead of a result
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ApiError {
+    pub kind: ApiErrorKind,
+    /// The error code the API answered with, such as 'ratelimited'
+    pub code: String,
+    /// The explanation the API gave, empty if there was none
+    pub info: String,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "The API answered with the error '{}'", self.code)?;
+        if !self.info.is_empty() {
+            write!(formatter, ": {}", self.info)?;
+        }
+        match self.kind.remediation() {
+            Some(remediation) => write!(formatter, "\nHint: {}", remediation),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for ApiError {}
+
+impl From<ApiError> for MediaWikiError {
+    fn from(error: ApiError) -> MediaWikiError {
+        MediaWikiError::String(error.to_string())
+    }
+}
+
+/// A function for reading the error out of an API response. Both the old and the new response formats are supported
+///
+/// # Arguments
+///
+/// * 'response' - A reference to the API response
+///
+/// # Returns
+///
+/// * Option<ApiError> - The error of the response, or None if the response had no error
+pub fn parse_error(response: &serde_json::Value) -> Option<ApiError> {
+    // The new format lists the errors in an array under 'errors' instead of a single one under 'error'
+    let error = match response["error"].is_object() {
+        true => &response["error"],
+        false => &response["errors"][0],
+    };
+    let code = error["code"].as_str()?;
+    let info = error["info"].as_str()
+        .or_else(|| error["text"].as_str())
+        .or_else(|| error["*"].as_str())
+        .unwrap_or_default();
+    Some(ApiError { kind: ApiErrorKind::from_code(code), code: code.to_string(), info: info.trim().to_string() })
+}
@@ -0,0 +1,88 @@
+use serde_json;
+
+/// An enum representing the kind of a warning returned by the API, deciding how the warning should be acted on
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WarningKind {
+    /// Part of the query or its result was dropped, so the query should be repeated in smaller pieces
+    Truncation,
+    /// A parameter used by the query is deprecated and may stop working in the future
+    Deprecation,
+    /// The API asks the client to slow down
+    RateLimit,
+    /// Any other warning
+    Other,
+}
+
+impl WarningKind {
+    /// Returns the name the kind is shown with in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningKind::Truncation => "truncation",
+            WarningKind::Deprecation => "deprecation",
+            WarningKind::RateLimit => "rate_limit",
+            WarningKind::Other => "other",
+        }
+    }
+}
+
+/// A struct representing a single warning returned by the API
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct ApiWarning {
+    pub module: String,
+    pub message: String,
+}
+
+impl ApiWarning {
+    /// Returns the kind of the warning, deduced from its message
+    pub fn kind(&self) -> WarningKind {
+        let message = self.message.to_lowercase();
+        if message.contains("truncated") || message.contains("too many values supplied") {
+            WarningKind::Truncation
+        } else if message.contains("deprecated") {
+            WarningKind::Deprecation
+        } else if message.contains("rate limit") || message.contains("ratelimit") || message.contains("lagged") {
+            WarningKind::RateLimit
+        } else {
+            WarningKind::Other
+        }
+    }
+}
+
+/// A function for reading the warnings out of an API response. Both the old and the new response formats are
+/// supported, and a module reporting several warnings at once is split into separate warnings
+///
+/// # Arguments
+///
+/// * 'response' - A reference to the API response
+///
+/// # Returns
+///
+/// * Vec<ApiWarning> - The warnings of the response, empty if there were none
+pub fn parse_warnings(response: &serde_json::Value) -> Vec<ApiWarning> {
+    let modules = match response["warnings"].as_object() {
+        Some(modules) => modules,
+        None => return vec!(),
+    };
+
+    let mut warnings = vec!();
+    for (module, content) in modules.iter() {
+        let text = match content["*"].as_str().or_else(|| content["warnings"].as_str()) {
+            Some(text) => text,
+            None => continue,
+        };
+        for message in text.lines().map(str::trim).filter(|message| !message.is_empty()) {
+            warnings.push(ApiWarning { module: module.to_string(), message: message.to_string() });
+        }
+    }
+    warnings
+}
+
+/// Returns true if the response has a warning of the given kind
+///
+/// # Arguments
+///
+/// * 'response' - A reference to the API response
+/// * 'kind' - The WarningKind to look for
+pub fn has_warning(response: &serde_json::Value, kind: WarningKind) -> bool {
+    parse_warnings(response).iter().any(|warning| warning.kind() == kind)
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+/// An enum representing the capitalization rules a wiki has for the titles of its articles, read from the case
+/// setting of the main namespace in the site info
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TitleCase {
+    /// The first letter of a title is always capitalized, as on wikipedia
+    FirstLetter,
+    /// Titles are kept as they are written, as on wiktionary
+    CaseSensitive,
+}
+
+impl TitleCase {
+    /// A function for reading the capitalization rules from the value of a case setting of the site info
+    ///
+    /// # Arguments
+    ///
+    /// * 'setting' - A string slice with the setting, such as first-letter or case-sensitive
+    ///
+    /// # Returns
+    ///
+    /// * Option<TitleCase> - The rules, or None if the setting isn't a known one
+    pub fn from_setting(setting: &str) -> Option<TitleCase> {
+        match setting {
+            "first-letter" => Some(TitleCase::FirstLetter),
+            "case-sensitive" => Some(TitleCase::CaseSensitive),
+            _ => None,
+        }
+    }
+}
+
+/// A newtype for article titles. All titles are normalized on construction, so two ArticleTitle instances
+/// representing the same article always compare equal regardless of whether they came from the user or the API
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct ArticleTitle(String);
+
+impl ArticleTitle {
+    /// A constructor for ArticleTitle that normalizes the given string the same way mediawiki does, keeping the case
+    /// it's written in. Meant for the titles read from the API, which the wiki has capitalized already, see with_case
+    /// for the titles given by the user
+    ///
+    /// # Arguments
+    ///
+    /// * 'raw' - A string slice with the title as given by the API
+    ///
+    /// # Returns
+    ///
+    /// * ArticleTitle - A new normalized ArticleTitle instance
+    pub fn new(raw: &str) -> ArticleTitle {
+        ArticleTitle::with_case(raw, TitleCase::CaseSensitive)
+    }
+
+    /// A constructor for ArticleTitle that normalizes the given string the same way mediawiki does:
+    /// the section fragment is stripped, underscores are turned into spaces, surrounding and repeated
+    /// whitespace is removed and the first letter is capitalized, unless the wiki is case sensitive
+    ///
+    /// # Arguments
+    ///
+    /// * 'raw' - A string slice with the title as given by the user or the API
+    /// * 'case' - The TitleCase of the wiki the title is on
+    ///
+    /// # Returns
+    ///
+    /// * ArticleTitle - A new normalized ArticleTitle instance
+    pub fn with_case(raw: &str, case: TitleCase) -> ArticleTitle {
+        let without_fragment = match raw.find('#') {
+            Some(index) => &raw[..index],
+            None => raw,
+        };
+
+        let spaced = without_fragment.replace('_', " ");
+        let collapsed = spaced.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+        if case == TitleCase::CaseSensitive {
+            return ArticleTitle(collapsed);
+        }
+        let mut chars = collapsed.chars();
+        let normalized = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        };
+
+        ArticleTitle(normalized)
+    }
+
+    /// Returns the normalized title as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the title without the disambiguator in parentheses at its end, such as Mercury for Mercury (planet), or
+    /// the whole title if it has none
+    pub fn without_disambiguator(&self) -> &str {
+        match self.0.strip_suffix(')').and_then(|rest| rest.rfind(" (")) {
+            Some(index) if index > 0 => &self.0[..index],
+            _ => &self.0,
+        }
+    }
+
+    /// Returns true if the normalized title is empty (the raw title contained only whitespace or a fragment)
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for ArticleTitle {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for ArticleTitle {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
@@ -0,0 +1,199 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use tokio::runtime::Handle;
+
+use super::article_title::ArticleTitle;
+use super::wiki_api;
+use super::wiki_client::WikiClient;
+
+// Waiting this long after a keystroke before querying the API keeps a fast typist from firing a request per char
+const DEBOUNCE: Duration = Duration::from_millis(300);
+const MIN_PREFIX_LENGTH: usize = 2;
+const COMPLETION_COUNT: u8 = 10;
+
+/// A rustyline helper that completes article titles with the opensearch API. Tab fetches completions right away,
+/// while hints for the current input are fetched in the background once the user stops typing
+pub struct TitleHelper {
+    client: WikiClient,
+    namespace: i64,
+    runtime: Handle,
+    cache: Arc<Mutex<HashMap<String, Vec<ArticleTitle>>>>,
+    latest_input: Arc<Mutex<String>>,
+}
+
+impl TitleHelper {
+    /// A constructor for TitleHelper. Must be called from inside the tokio runtime
+    ///
+    /// # Arguments
+    ///
+    /// * 'client' - A reference to a WikiClient, cloned for the background queries
+    /// * 'namespace' - The id of the namespace to complete titles from
+    ///
+    /// # Returns
+    ///
+    /// * TitleHelper - A new TitleHelper instance with an empty completion cache
+    pub fn new(client: &WikiClient, namespace: i64) -> TitleHelper {
+        TitleHelper {
+            client: client.clone(),
+            namespace,
+            runtime: Handle::current(),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+            latest_input: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    /// A function for getting the cached completions of a prefix
+    ///
+    /// # Arguments
+    ///
+    /// * 'prefix' - A string slice with the typed prefix
+    ///
+    /// # Returns
+    ///
+    /// * Option<Vec<ArticleTitle>> - The cached completions, or None if the prefix hasn't been queried yet
+    fn cached(&self, prefix: &str) -> Option<Vec<ArticleTitle>> {
+        match self.cache.lock() {
+            Ok(cache) => cache.get(prefix).cloned(),
+            Err(_) => None,
+        }
+    }
+
+    /// A function that schedules a debounced background query for the given prefix. The query is dropped if the
+    /// user has typed something else before the debounce period ends
+    ///
+    /// # Arguments
+    ///
+    /// * 'prefix' - A string slice with the typed prefix
+    fn schedule_fetch(&self, prefix: &str) {
+        match self.latest_input.lock() {
+            Ok(mut latest) => *latest = prefix.to_string(),
+            Err(_) => return,
+        }
+
+        let client = self.client.clone();
+        let namespace = self.namespace;
+        let cache = Arc::clone(&self.cache);
+        let latest_input = Arc::clone(&self.latest_input);
+        let prefix = prefix.to_string();
+
+        self.runtime.spawn(async move {
+            tokio::time::sleep(DEBOUNCE).await;
+            match latest_input.lock() {
+                Ok(latest) if *latest == prefix => (),
+                _ => return,
+            }
+            if let Ok(titles) = wiki_api::opensearch(&prefix, COMPLETION_COUNT, namespace, &client).await {
+                if let Ok(mut cache) = cache.lock() {
+                    cache.insert(prefix, titles);
+                }
+            }
+        });
+    }
+}
+
+impl Completer for TitleHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        let prefix = &line[..pos];
+        if prefix.chars().count() < MIN_PREFIX_LENGTH {
+            return Ok((0, vec!()));
+        }
+
+        let titles = match self.cached(prefix) {
+            Some(titles) => titles,
+            None => {
+                let fetched = self.runtime.block_on(
+                    wiki_api::opensearch(prefix, COMPLETION_COUNT, self.namespace, &self.client));
+                let titles = fetched.unwrap_or_default();
+                if let Ok(mut cache) = self.cache.lock() {
+                    cache.insert(prefix.to_string(), titles.clone());
+                }
+                titles
+            },
+        };
+
+        Ok((0, titles.iter().map(|title| title.to_string()).collect()))
+    }
+}
+
+impl Hinter for TitleHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+        if pos < line.len() || line.chars().count() < MIN_PREFIX_LENGTH {
+            return None;
+        }
+
+        let titles = match self.cached(line) {
+            Some(titles) => titles,
+            None => {
+                self.schedule_fetch(line);
+                return None;
+            },
+        };
+
+        // Only hint titles that continue the typed text verbatim, others are still offered by tab completion
+        titles.iter()
+            .find(|title| title.as_str().starts_with(line) && title.as_str().len() > line.len())
+            .map(|title| title.as_str()[line.len()..].to_string())
+    }
+}
+
+impl Highlighter for TitleHelper {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(format!("\x1b[2m{}\x1b[0m", hint))
+    }
+}
+
+impl Validator for TitleHelper {}
+
+impl Helper for TitleHelper {}
+
+/// A function for reading an article title from the user with autocompletion
+///
+/// # Arguments
+///
+/// * 'editor' - A mutable reference to the line editor, shared between prompts to keep the history
+/// * 'prompt' - A string slice to prompt the user with while querying input
+///
+/// # Returns
+///
+/// * Option<String> - An Option containing the recieved String or None in the case of error
+pub fn read_title(editor: &mut Editor<TitleHelper, DefaultHistory>, prompt: &str) -> Option<String> {
+    // The editor blocks on stdin, so let the runtime move its other work elsewhere while waiting
+    let line = match tokio::task::block_in_place(|| editor.readline(prompt)) {
+        Ok(line) => line,
+        Err(_) => return None,
+    };
+    let _ = editor.add_history_entry(line.as_str());
+    Some(line.trim().to_string())
+}
+
+/// A function for constructing the line editor used for reading article titles
+///
+/// # Arguments
+///
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'namespace' - The id of the namespace to complete titles from
+///
+/// # Returns
+///
+/// * Option<Editor<TitleHelper, DefaultHistory>> - The editor, or None if the terminal doesn't support one
+pub fn title_editor(client: &WikiClient, namespace: i64) -> Option<Editor<TitleHelper, DefaultHistory>> {
+    let mut editor = match Editor::new() {
+        Ok(editor) => editor,
+        Err(_) => return None,
+    };
+    editor.set_helper(Some(TitleHelper::new(client, namespace)));
+    Some(editor)
+}
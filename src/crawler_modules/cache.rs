@@ -0,0 +1,728 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{self, json};
+
+use super::article_title::ArticleTitle;
+use super::crawler::ErrorReporter;
+use super::link_lru::{LinkLru, DEFAULT_MEMORY_CACHE_SIZE};
+use super::wiki_api::LinkDirection;
+
+pub const DEFAULT_CACHE_FILE: &str = "./crawler_cache.sqlite3";
+
+/// A persistent cache of the links, shortest paths and resolved redirects fetched from a single wiki. Several wikis
+/// can share the same database file, as every entry is keyed by the id of the wiki it was fetched from
+///
+/// The links read or written during the session are also kept in memory, up to a set amount of articles, so that
+/// reading them again doesn't need the database
+///
+/// Clones share the same database. Errors reading or writing it are reported and treated as the entries missing, see
+/// with_errors
+#[derive(Clone)]
+pub struct WikiCache {
+    database: Arc<CacheDatabase>,
+    errors: ErrorReporter,
+}
+
+/// The database connection and the links kept in memory, shared by the clones of a WikiCache
+struct CacheDatabase {
+    connection: Mutex<Connection>,
+    memory: Mutex<LinkLru>,
+    wiki: String,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// A struct holding the statistics of the entries cached for a single wiki
+pub struct CacheStats {
+    pub wiki: String,
+    pub links: usize,
+    pub paths: usize,
+    pub redirects: usize,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// A struct representing a path read from the cache
+pub struct CachedPath {
+    pub path: Vec<ArticleTitle>,
+    /// The time the path was found at, as seconds since the unix epoch
+    pub found_at: i64,
+}
+
+/// A struct holding the statistics of the redirects cached for a single wiki
+pub struct RedirectStats {
+    pub wiki: String,
+    pub aliases: usize,
+    pub targets: usize,
+    /// The articles with the most cached redirects pointing to them, paired up with the amount of redirects
+    pub most_aliased: Vec<(String, usize)>,
+}
+
+impl WikiCache {
+    /// A constructor for WikiCache that opens the database file, creating it and its tables if needed
+    ///
+    /// # Arguments
+    ///
+    /// * 'file' - A string slice with the path of the database file
+    /// * 'wiki' - A string slice with the id of the wiki the cache is used with, see WikiClient::wiki_id
+    ///
+    /// # Returns
+    ///
+    /// * Result<WikiCache, Box<dyn Error>> - A new WikiCache instance, or an error if the database couldn't be opened
+    pub fn open(file: &str, wiki: &str) -> Result<WikiCache, Box<dyn Error>> {
+        let connection = open_connection(file)?;
+        let database = CacheDatabase {
+            connection: Mutex::new(connection),
+            memory: Mutex::new(LinkLru::new(DEFAULT_MEMORY_CACHE_SIZE)),
+            wiki: wiki.to_string(),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        };
+        Ok(WikiCache { database: Arc::new(database), errors: ErrorReporter::default() })
+    }
+
+    /// Returns a clone of the cache reporting its errors with the given ErrorReporter, such as into the error channel
+    /// of a crawl
+    pub fn with_errors(&self, errors: ErrorReporter) -> WikiCache {
+        WikiCache { database: Arc::clone(&self.database), errors }
+    }
+
+    /// Sets the amount of articles whose links are kept in memory, 0 for reading every link from the database.
+    /// Drops the links kept so far
+    pub fn set_memory_size(&self, articles: usize) {
+        if let Some(mut memory) = self.memory() {
+            *memory = LinkLru::new(articles);
+        }
+    }
+
+    /// Returns the id of the wiki the cache entries are read and written for
+    pub fn wiki(&self) -> &str {
+        &self.database.wiki
+    }
+
+    /// A function for getting the cached links of an article
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    /// * 'direction' - The LinkDirection of the links
+    ///
+    /// # Returns
+    ///
+    /// * Option<Vec<ArticleTitle>> - The cached links, or None if the article's links haven't been cached
+    pub fn get_links(&self, title: &ArticleTitle, direction: LinkDirection) -> Option<Vec<ArticleTitle>> {
+        if let Some(links) = self.memory().and_then(|mut memory| memory.get(title, direction)) {
+            self.database.hits.fetch_add(1, Ordering::Relaxed);
+            return Some(links);
+        }
+
+        let connection = self.lock()?;
+        let links = connection.query_row(
+            "SELECT links FROM links WHERE wiki = ?1 AND direction = ?2 AND title = ?3",
+            params![self.database.wiki, direction.as_str(), title.as_str()],
+            |row| row.get::<_, String>(0)).optional();
+
+        match links {
+            Ok(Some(links)) => {
+                self.database.hits.fetch_add(1, Ordering::Relaxed);
+                let links = decode_titles(&links)?;
+                if let Some(mut memory) = self.memory() {
+                    memory.insert(title, direction, &links);
+                }
+                Some(links)
+            },
+            Ok(None) => {
+                self.database.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            },
+            Err(error) => {
+                self.errors.report(format!("Error reading links of '{}' from cache:\n{:?}", title, error));
+                None
+            },
+        }
+    }
+
+    /// A function for storing the fetched links of articles
+    ///
+    /// # Arguments
+    ///
+    /// * 'links' - A reference to a HashMap with articles paired up with their links
+    /// * 'direction' - The LinkDirection of the links
+    pub fn store_links(&self, links: &HashMap<ArticleTitle, Vec<ArticleTitle>>, direction: LinkDirection) {
+        if let Some(mut memory) = self.memory() {
+            for (title, article_links) in links.iter() {
+                memory.insert(title, direction, article_links);
+            }
+        }
+
+        let mut connection = match self.lock() {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        let result = (|| -> rusqlite::Result<()> {
+            let transaction = connection.transaction()?;
+            {
+                let mut statement = transaction.prepare(
+                    "INSERT OR REPLACE INTO links (wiki, direction, title, links, fetched_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)")?;
+                for (title, article_links) in links.iter() {
+                    statement.execute(params![self.database.wiki, direction.as_str(), title.as_str(),
+                                              encode_titles(article_links), now()])?;
+                }
+            }
+            transaction.commit()
+        })();
+
+        if let Err(error) = result {
+            self.errors.report(format!("Error writing links into cache:\n{:?}", error));
+        }
+    }
+
+    /// A function for getting the time the links of an article were cached at
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    /// * 'direction' - The LinkDirection of the links
+    ///
+    /// # Returns
+    ///
+    /// * Option<i64> - The time the links were fetched at as seconds since the unix epoch, or None if they haven't
+    ///   been cached
+    pub fn links_fetched_at(&self, title: &ArticleTitle, direction: LinkDirection) -> Option<i64> {
+        let connection = self.lock()?;
+        let fetched_at = connection.query_row(
+            "SELECT fetched_at FROM links WHERE wiki = ?1 AND direction = ?2 AND title = ?3",
+            params![self.database.wiki, direction.as_str(), title.as_str()],
+            |row| row.get::<_, i64>(0)).optional();
+
+        match fetched_at {
+            Ok(fetched_at) => fetched_at,
+            Err(error) => {
+                self.errors.report(format!("Error reading links of '{}' from cache:\n{:?}", title, error));
+                None
+            },
+        }
+    }
+
+    /// A function for removing the cached links of articles, so that they are fetched again the next time
+    ///
+    /// # Arguments
+    ///
+    /// * 'titles' - A slice of the titles of the articles
+    /// * 'direction' - The LinkDirection of the links
+    pub fn remove_links(&self, titles: &[ArticleTitle], direction: LinkDirection) {
+        if let Some(mut memory) = self.memory() {
+            for title in titles.iter() {
+                memory.remove(title, direction);
+            }
+        }
+        let connection = match self.lock() {
+            Some(connection) => connection,
+            None => return,
+        };
+        for title in titles.iter() {
+            if let Err(error) = connection.execute(
+                "DELETE FROM links WHERE wiki = ?1 AND direction = ?2 AND title = ?3",
+                params![self.database.wiki, direction.as_str(), title.as_str()]) {
+                self.errors.report(format!("Error removing links of '{}' from cache:\n{:?}", title, error));
+            }
+        }
+    }
+
+    /// A function for getting a previously found path between two articles
+    ///
+    /// # Arguments
+    ///
+    /// * 'origin' - A reference to the title of the origin article
+    /// * 'goal' - A reference to the title of the goal article
+    /// * 'direction' - The LinkDirection of the crawl that found the path
+    ///
+    /// # Returns
+    ///
+    /// * Option<CachedPath> - The cached path, or None if no path between the articles has been cached
+    pub fn get_path(&self, origin: &ArticleTitle, goal: &ArticleTitle, direction: LinkDirection)
+        -> Option<CachedPath> {
+        let connection = self.lock()?;
+        let path = connection.query_row(
+            "SELECT path, found_at FROM paths WHERE wiki = ?1 AND direction = ?2 AND origin = ?3 AND goal = ?4",
+            params![self.database.wiki, direction.as_str(), origin.as_str(), goal.as_str()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))).optional();
+
+        match path {
+            Ok(path) => path.and_then(|(path, found_at)| Some(CachedPath { path: decode_titles(&path)?, found_at })),
+            Err(error) => {
+                self.errors.report(format!("Error reading path from '{}' to '{}' from cache:\n{:?}", origin, goal,
+                                           error));
+                None
+            },
+        }
+    }
+
+    /// A function for storing a found path
+    ///
+    /// # Arguments
+    ///
+    /// * 'path' - A slice of the titles on the path from origin to goal
+    /// * 'direction' - The LinkDirection of the crawl that found the path
+    pub fn store_path(&self, path: &[ArticleTitle], direction: LinkDirection) {
+        let (origin, goal) = match (path.first(), path.last()) {
+            (Some(origin), Some(goal)) => (origin, goal),
+            _ => return,
+        };
+        let connection = match self.lock() {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        if let Err(error) = connection.execute(
+            "INSERT OR REPLACE INTO paths (wiki, direction, origin, goal, path, found_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![self.database.wiki, direction.as_str(), origin.as_str(), goal.as_str(), encode_titles(path),
+                    now()]) {
+            self.errors.report(format!("Error writing path into cache:\n{:?}", error));
+        }
+    }
+
+    /// A function for getting the canonical title a redirect was previously resolved to
+    ///
+    /// # Arguments
+    ///
+    /// * 'alias' - A reference to the title of the redirect
+    ///
+    /// # Returns
+    ///
+    /// * Option<ArticleTitle> - The title of the article the redirect points to, or None if it hasn't been cached
+    pub fn get_redirect(&self, alias: &ArticleTitle) -> Option<ArticleTitle> {
+        let connection = self.lock()?;
+        let target = connection.query_row(
+            "SELECT target FROM redirects WHERE wiki = ?1 AND alias = ?2",
+            params![self.database.wiki, alias.as_str()],
+            |row| row.get::<_, String>(0)).optional();
+
+        match target {
+            Ok(target) => target.map(|target| ArticleTitle::new(&target)),
+            Err(error) => {
+                self.errors.report(format!("Error reading redirect '{}' from cache:\n{:?}", alias, error));
+                None
+            },
+        }
+    }
+
+    /// A function for storing resolved redirects
+    ///
+    /// # Arguments
+    ///
+    /// * 'redirects' - A slice of redirect titles paired up with the titles of the articles they point to
+    pub fn store_redirects(&self, redirects: &[(ArticleTitle, ArticleTitle)]) {
+        if redirects.is_empty() {
+            return;
+        }
+        let mut connection = match self.lock() {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        let result = (|| -> rusqlite::Result<()> {
+            let transaction = connection.transaction()?;
+            {
+                let mut statement = transaction.prepare(
+                    "INSERT OR REPLACE INTO redirects (wiki, alias, target, resolved_at) VALUES (?1, ?2, ?3, ?4)")?;
+                for (alias, target) in redirects.iter() {
+                    statement.execute(params![self.database.wiki, alias.as_str(), target.as_str(), now()])?;
+                }
+            }
+            transaction.commit()
+        })();
+
+        if let Err(error) = result {
+            self.errors.report(format!("Error writing redirects into cache:\n{:?}", error));
+        }
+    }
+
+    /// Adds the hits and misses counted so far to the usage counters of the wiki, starting the counts over. Called at
+    /// the end of every crawl, so that the errors writing the counters are reported into the error channel of the crawl
+    pub fn save_usage(&self) {
+        let connection = match self.lock() {
+            Some(connection) => connection,
+            None => return,
+        };
+        let hits = self.database.hits.swap(0, Ordering::Relaxed);
+        let misses = self.database.misses.swap(0, Ordering::Relaxed);
+        if let Err(error) = write_usage(&connection, &self.database.wiki, hits, misses) {
+            self.errors.report(format!("Error writing cache usage counters:\n{:?}", error));
+        }
+    }
+
+    /// Acquires the database connection, reporting a poisoned lock
+    fn lock(&self) -> Option<MutexGuard<'_, Connection>> {
+        match self.database.connection.lock() {
+            Ok(connection) => Some(connection),
+            Err(error) => {
+                self.errors.report(format!("Error acquiring lock for cache database:\n{:?}", error));
+                None
+            },
+        }
+    }
+
+    /// Acquires the links kept in memory, reporting a poisoned lock
+    fn memory(&self) -> Option<MutexGuard<'_, LinkLru>> {
+        match self.database.memory.lock() {
+            Ok(memory) => Some(memory),
+            Err(error) => {
+                self.errors.report(format!("Error acquiring lock for the links kept in memory:\n{:?}", error));
+                None
+            },
+        }
+    }
+}
+
+impl Drop for CacheDatabase {
+    /// Adds the hits and misses not yet saved by a crawl to the usage counters of the wiki
+    fn drop(&mut self) {
+        let (hits, misses) = (*self.hits.get_mut(), *self.misses.get_mut());
+        let connection = match self.connection.get_mut() {
+            Ok(connection) => connection,
+            Err(_) => return,
+        };
+        if let Err(error) = write_usage(connection, &self.wiki, hits, misses) {
+            // Only the lookups made outside of crawls are left here, and those report their errors without a channel
+            ErrorReporter::default().report(format!("Error writing cache usage counters:\n{:?}", error));
+        }
+    }
+}
+
+/// A function for adding hits and misses to the usage counters of a wiki
+///
+/// # Arguments
+///
+/// * 'connection' - A reference to the connection of the cache database
+/// * 'wiki' - A string slice with the id of the wiki
+/// * 'hits' - The amount of hits to add
+/// * 'misses' - The amount of misses to add
+///
+/// # Returns
+///
+/// * rusqlite::Result<()> - An error if the counters couldn't be written
+fn write_usage(connection: &Connection, wiki: &str, hits: usize, misses: usize) -> rusqlite::Result<()> {
+    if hits == 0 && misses == 0 {
+        return Ok(());
+    }
+    connection.execute(
+        "INSERT INTO usage (wiki, hits, misses) VALUES (?1, ?2, ?3)
+         ON CONFLICT (wiki) DO UPDATE SET hits = hits + excluded.hits, misses = misses + excluded.misses",
+        params![wiki, hits as i64, misses as i64])?;
+    Ok(())
+}
+
+/// A set of titles kept in a temporary table next to the cache database, used for telling titles apart when their
+/// hashes collide in the low memory mode of a crawl. The table is private to the store and removed when it's dropped
+pub struct VisitedStore {
+    connection: Mutex<Connection>,
+    errors: ErrorReporter,
+}
+
+impl VisitedStore {
+    /// A constructor for VisitedStore that creates the temporary table
+    ///
+    /// # Arguments
+    ///
+    /// * 'file' - A string slice with the path of the cache database file
+    ///
+    /// # Returns
+    ///
+    /// * Result<VisitedStore, Box<dyn Error>> - A new VisitedStore, or an error if the database couldn't be opened
+    pub fn open(file: &str) -> Result<VisitedStore, Box<dyn Error>> {
+        let connection = Connection::open(file)?;
+        // Keeping the table on disk is the whole point, so it mustn't be moved into memory
+        connection.execute_batch(
+            "PRAGMA temp_store = FILE;
+            CREATE TEMP TABLE visited (title TEXT PRIMARY KEY);")?;
+        Ok(VisitedStore { connection: Mutex::new(connection), errors: ErrorReporter::default() })
+    }
+
+    /// Sets the ErrorReporter the errors of the store are reported with
+    pub fn set_error_reporter(&mut self, errors: ErrorReporter) {
+        self.errors = errors;
+    }
+
+    /// Adds a title into the store, returning true if it wasn't there yet. Errors are reported and treated as the
+    /// title already being there, so that a broken database can't make the crawl loop
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title to add
+    pub fn insert(&self, title: &ArticleTitle) -> bool {
+        let connection = match self.connection.lock() {
+            Ok(connection) => connection,
+            Err(error) => {
+                self.errors.report(format!("Error acquiring lock for visited titles database:\n{:?}", error));
+                return false;
+            },
+        };
+        match connection.execute("INSERT OR IGNORE INTO temp.visited (title) VALUES (?1)", params![title.as_str()]) {
+            Ok(inserted) => inserted > 0,
+            Err(error) => {
+                self.errors.report(format!("Error writing visited title into database:\n{:?}", error));
+                false
+            },
+        }
+    }
+}
+
+/// A function for opening the cache database, creating the tables if they don't exist yet
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the database file
+///
+/// # Returns
+///
+/// * rusqlite::Result<Connection> - The opened connection, or an error if the database couldn't be opened
+fn open_connection(file: &str) -> rusqlite::Result<Connection> {
+    let connection = Connection::open(file)?;
+    connection.execute_batch(
+        "CREATE TABLE IF NOT EXISTS links (
+            wiki TEXT NOT NULL,
+            direction TEXT NOT NULL,
+            title TEXT NOT NULL,
+            links TEXT NOT NULL,
+            fetched_at INTEGER NOT NULL,
+            PRIMARY KEY (wiki, direction, title)
+        );
+        CREATE TABLE IF NOT EXISTS paths (
+            wiki TEXT NOT NULL,
+            direction TEXT NOT NULL,
+            origin TEXT NOT NULL,
+            goal TEXT NOT NULL,
+            path TEXT NOT NULL,
+            found_at INTEGER NOT NULL,
+            PRIMARY KEY (wiki, direction, origin, goal)
+        );
+        CREATE TABLE IF NOT EXISTS redirects (
+            wiki TEXT NOT NULL,
+            alias TEXT NOT NULL,
+            target TEXT NOT NULL,
+            resolved_at INTEGER NOT NULL,
+            PRIMARY KEY (wiki, alias)
+        );
+        CREATE TABLE IF NOT EXISTS usage (
+            wiki TEXT PRIMARY KEY,
+            hits INTEGER NOT NULL,
+            misses INTEGER NOT NULL
+        );")?;
+    Ok(connection)
+}
+
+/// A function for collecting the statistics of every wiki in the cache database
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the database file
+///
+/// # Returns
+///
+/// * Result<Vec<CacheStats>, Box<dyn Error>> - The statistics of each wiki, sorted by the wiki id
+pub fn statistics(file: &str) -> Result<Vec<CacheStats>, Box<dyn Error>> {
+    let connection = open_connection(file)?;
+    let mut statement = connection.prepare(
+        "SELECT wikis.wiki,
+            (SELECT COUNT(*) FROM links WHERE links.wiki = wikis.wiki),
+            (SELECT COUNT(*) FROM paths WHERE paths.wiki = wikis.wiki),
+            (SELECT COUNT(*) FROM redirects WHERE redirects.wiki = wikis.wiki),
+            COALESCE((SELECT hits FROM usage WHERE usage.wiki = wikis.wiki), 0),
+            COALESCE((SELECT misses FROM usage WHERE usage.wiki = wikis.wiki), 0)
+         FROM (SELECT wiki FROM links UNION SELECT wiki FROM paths UNION SELECT wiki FROM redirects
+               UNION SELECT wiki FROM usage) AS wikis
+         ORDER BY wikis.wiki")?;
+
+    let rows = statement.query_map([], |row| Ok(CacheStats {
+        wiki: row.get(0)?,
+        links: row.get::<_, i64>(1)? as usize,
+        paths: row.get::<_, i64>(2)? as usize,
+        redirects: row.get::<_, i64>(3)? as usize,
+        hits: row.get::<_, i64>(4)? as usize,
+        misses: row.get::<_, i64>(5)? as usize,
+    }))?;
+
+    let mut stats = vec!();
+    for row in rows {
+        stats.push(row?);
+    }
+    Ok(stats)
+}
+
+/// A function for collecting the statistics of the cached redirects of every wiki in the cache database
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the database file
+/// * 'top' - The amount of most redirected to articles to include for each wiki
+///
+/// # Returns
+///
+/// * Result<Vec<RedirectStats>, Box<dyn Error>> - The redirect statistics of each wiki, sorted by the wiki id
+pub fn redirect_statistics(file: &str, top: usize) -> Result<Vec<RedirectStats>, Box<dyn Error>> {
+    let connection = open_connection(file)?;
+    let mut statement = connection.prepare(
+        "SELECT wiki, COUNT(*), COUNT(DISTINCT target) FROM redirects GROUP BY wiki ORDER BY wiki")?;
+    let rows = statement.query_map([], |row| Ok(RedirectStats {
+        wiki: row.get(0)?,
+        aliases: row.get::<_, i64>(1)? as usize,
+        targets: row.get::<_, i64>(2)? as usize,
+        most_aliased: vec!(),
+    }))?;
+
+    let mut stats = vec!();
+    for row in rows {
+        stats.push(row?);
+    }
+
+    let mut statement = connection.prepare(
+        "SELECT target, COUNT(*) AS aliases FROM redirects WHERE wiki = ?1
+         GROUP BY target ORDER BY aliases DESC, target LIMIT ?2")?;
+    for wiki in stats.iter_mut() {
+        let rows = statement.query_map(params![wiki.wiki, top as i64],
+                                       |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?;
+        for row in rows {
+            wiki.most_aliased.push(row?);
+        }
+    }
+    Ok(stats)
+}
+
+/// Returns the size of the cache database file in bytes, or zero if the file doesn't exist
+pub fn file_size(file: &str) -> u64 {
+    fs::metadata(file).map_or(0, |metadata| metadata.len())
+}
+
+/// A function for removing every entry from the cache database
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the database file
+///
+/// # Returns
+///
+/// * Result<usize, Box<dyn Error>> - The amount of removed link, path and redirect entries
+pub fn clear(file: &str) -> Result<usize, Box<dyn Error>> {
+    let connection = open_connection(file)?;
+    let removed = connection.execute("DELETE FROM links", [])? + connection.execute("DELETE FROM paths", [])?
+        + connection.execute("DELETE FROM redirects", [])?;
+    connection.execute("DELETE FROM usage", [])?;
+    connection.execute("VACUUM", [])?;
+    Ok(removed)
+}
+
+/// A function for removing the entries older than the given age from the cache database
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the database file
+/// * 'older_than' - The age after which entries are removed
+///
+/// # Returns
+///
+/// * Result<usize, Box<dyn Error>> - The amount of removed link, path and redirect entries
+pub fn prune(file: &str, older_than: Duration) -> Result<usize, Box<dyn Error>> {
+    let connection = open_connection(file)?;
+    let cutoff = now() - older_than.as_secs() as i64;
+    let removed = connection.execute("DELETE FROM links WHERE fetched_at < ?1", params![cutoff])?
+        + connection.execute("DELETE FROM paths WHERE found_at < ?1", params![cutoff])?
+        + connection.execute("DELETE FROM redirects WHERE resolved_at < ?1", params![cutoff])?;
+    connection.execute("VACUUM", [])?;
+    Ok(removed)
+}
+
+/// A function for exporting every entry of the cache database into a file, one JSON object per line
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the database file
+/// * 'output' - A string slice with the path of the file to export into
+///
+/// # Returns
+///
+/// * Result<usize, Box<dyn Error>> - The amount of exported link, path and redirect entries
+pub fn export(file: &str, output: &str) -> Result<usize, Box<dyn Error>> {
+    let connection = open_connection(file)?;
+    let mut writer = BufWriter::new(File::create(output)?);
+    let mut exported = 0;
+
+    let mut statement = connection.prepare(
+        "SELECT wiki, direction, title, links, fetched_at FROM links ORDER BY wiki, direction, title")?;
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let links: String = row.get(3)?;
+        let entry = json!({
+            "type": "links",
+            "wiki": row.get::<_, String>(0)?,
+            "direction": row.get::<_, String>(1)?,
+            "title": row.get::<_, String>(2)?,
+            "links": serde_json::from_str::<serde_json::Value>(&links)?,
+            "fetched_at": row.get::<_, i64>(4)?,
+        });
+        writeln!(writer, "{}", entry)?;
+        exported += 1;
+    }
+
+    let mut statement = connection.prepare(
+        "SELECT wiki, direction, origin, goal, path, found_at FROM paths ORDER BY wiki, direction, origin, goal")?;
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let path: String = row.get(4)?;
+        let entry = json!({
+            "type": "path",
+            "wiki": row.get::<_, String>(0)?,
+            "direction": row.get::<_, String>(1)?,
+            "origin": row.get::<_, String>(2)?,
+            "goal": row.get::<_, String>(3)?,
+            "path": serde_json::from_str::<serde_json::Value>(&path)?,
+            "found_at": row.get::<_, i64>(5)?,
+        });
+        writeln!(writer, "{}", entry)?;
+        exported += 1;
+    }
+
+    let mut statement = connection.prepare(
+        "SELECT wiki, alias, target, resolved_at FROM redirects ORDER BY wiki, alias")?;
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let entry = json!({
+            "type": "redirect",
+            "wiki": row.get::<_, String>(0)?,
+            "alias": row.get::<_, String>(1)?,
+            "target": row.get::<_, String>(2)?,
+            "resolved_at": row.get::<_, i64>(3)?,
+        });
+        writeln!(writer, "{}", entry)?;
+        exported += 1;
+    }
+
+    writer.flush()?;
+    Ok(exported)
+}
+
+/// A function for encoding titles into the JSON array they are stored as
+fn encode_titles(titles: &[ArticleTitle]) -> String {
+    serde_json::Value::from(titles.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>()).to_string()
+}
+
+/// A function for decoding a stored JSON array of titles, returning None if the data was malformed
+fn decode_titles(stored: &str) -> Option<Vec<ArticleTitle>> {
+    let array = serde_json::from_str::<serde_json::Value>(stored).ok()?;
+    array.as_array()?.iter().map(|title| title.as_str().map(ArticleTitle::new)).collect()
+}
+
+/// Returns the current time as seconds since the unix epoch
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs() as i64)
+}
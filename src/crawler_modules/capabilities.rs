@@ -0,0 +1,181 @@
+use serde_json;
+
+use super::article_title::TitleCase;
+use super::wiki_client::WikiClient;
+
+// The limits of en.wikipedia for accounts without the apihighlimits right, assumed when detection fails
+pub const DEFAULT_MAX_TITLES: usize = 50;
+pub const DEFAULT_HIGH_MAX_TITLES: usize = 500;
+
+const QUERY_MODULE: &str = "query";
+const SEARCH_MODULE: &str = "query+search";
+const LINKS_MODULE: &str = "query+links";
+const LINKSHERE_MODULE: &str = "query+linkshere";
+const PAGEVIEWS_MODULE: &str = "query+pageviews";
+const MOSTVIEWED_MODULE: &str = "query+mostviewed";
+const ASSESSMENTS_MODULE: &str = "query+pageassessments";
+const EXTRACTS_MODULE: &str = "query+extracts";
+
+/// A struct describing the limits and the optional features of the API of a wiki
+#[derive(Clone, PartialEq, Debug)]
+pub struct WikiCapabilities {
+    /// The maximum amount of titles in a single query for normal accounts
+    pub max_titles: usize,
+    /// The maximum amount of titles in a single query for accounts with the apihighlimits right
+    pub high_max_titles: usize,
+    /// The maximum amount of links returned per response, if the wiki reported one
+    pub max_links: Option<u64>,
+    /// Whether list=search is available for validating article names
+    pub search: bool,
+    /// Whether search results can be sorted by incoming links, which CirrusSearch allows along with the linksto
+    /// keyword
+    pub link_search: bool,
+    /// Whether prop=links is available for crawling, which wikis restricting their API can have disabled
+    pub links: bool,
+    /// Whether prop=linkshere is available for crawling backlinks
+    pub backlinks: bool,
+    /// Whether prop=pageviews of the PageViewInfo extension is available for annotating paths
+    pub pageviews: bool,
+    /// Whether list=mostviewed of the PageViewInfo extension is available for finding the hub articles to warm up
+    pub most_viewed: bool,
+    /// Whether prop=pageassessments of the PageAssessments extension is available for annotating paths
+    pub assessments: bool,
+    /// Whether prop=extracts of the TextExtracts extension is available for previewing articles
+    pub extracts: bool,
+    /// The capitalization rules of the titles of the main namespace
+    pub title_case: TitleCase,
+    /// Whether the wiki converts titles between the variants of its language, such as simplified and traditional
+    /// Chinese or the Cyrillic and Latin scripts of Serbian
+    pub variants: bool,
+}
+
+impl Default for WikiCapabilities {
+    fn default() -> WikiCapabilities {
+        WikiCapabilities {
+            max_titles: DEFAULT_MAX_TITLES,
+            high_max_titles: DEFAULT_HIGH_MAX_TITLES,
+            max_links: None,
+            search: true,
+            link_search: false,
+            links: true,
+            backlinks: true,
+            pageviews: false,
+            most_viewed: false,
+            assessments: false,
+            extracts: false,
+            title_case: TitleCase::FirstLetter,
+            variants: false,
+        }
+    }
+}
+
+/// An async function for detecting the capabilities of a wiki with a single paraminfo query. Third party wikis
+/// can have lower limits than wikipedia or have modules disabled, so these shouldn't be assumed. Falls back to the
+/// defaults if the query fails. The capitalization rules and the variants are read from the site info loaded when
+/// connecting
+///
+/// # Arguments
+///
+/// * 'client' - A reference to a WikiClient with an api session
+///
+/// # Returns
+///
+/// * WikiCapabilities - The detected capabilities
+pub async fn detect(client: &WikiClient) -> WikiCapabilities {
+    let modules = [QUERY_MODULE, SEARCH_MODULE, LINKS_MODULE, LINKSHERE_MODULE, PAGEVIEWS_MODULE, MOSTVIEWED_MODULE,
+                   ASSESSMENTS_MODULE, EXTRACTS_MODULE].join("|");
+    let query_map = client.params_into(&[
+        ("action", "paraminfo"),
+        ("format", "json"),
+        ("modules", &modules),
+    ]);
+
+    let mut capabilities = WikiCapabilities { title_case: title_case(client), variants: has_variants(client),
+                                              ..WikiCapabilities::default() };
+    let result = match client.get(&query_map).await {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("Error detecting the capabilities of the wiki, assuming the defaults:\n{}", error);
+            return capabilities;
+        },
+    };
+
+    let modules = match result["paraminfo"]["modules"].as_array() {
+        Some(modules) => modules,
+        None => return capabilities,
+    };
+    let find_module = |path: &str| modules.iter().find(|module| module["path"].as_str() == Some(path));
+
+    if let Some(titles) = find_module(QUERY_MODULE).and_then(|module| find_parameter(module, "titles")) {
+        if let Some(limit) = titles["lowlimit"].as_u64().or_else(|| titles["limit"].as_u64()) {
+            capabilities.max_titles = limit as usize;
+        }
+        if let Some(limit) = titles["highlimit"].as_u64() {
+            capabilities.high_max_titles = limit as usize;
+        }
+    }
+    capabilities.max_links = find_module(LINKS_MODULE)
+        .and_then(|module| find_parameter(module, "limit"))
+        .and_then(|limit| limit["max"].as_u64());
+    capabilities.search = find_module(SEARCH_MODULE).is_some();
+    capabilities.link_search = find_module(SEARCH_MODULE)
+        .and_then(|module| find_parameter(module, "sort"))
+        .and_then(|sort| sort["type"].as_array())
+        .is_some_and(|values| values.iter().any(|value| value.as_str() == Some("incoming_links_desc")));
+    capabilities.links = find_module(LINKS_MODULE).is_some();
+    capabilities.backlinks = find_module(LINKSHERE_MODULE).is_some();
+    capabilities.pageviews = find_module(PAGEVIEWS_MODULE).is_some();
+    capabilities.most_viewed = find_module(MOSTVIEWED_MODULE).is_some();
+    capabilities.assessments = find_module(ASSESSMENTS_MODULE).is_some();
+    capabilities.extracts = find_module(EXTRACTS_MODULE).is_some();
+    capabilities
+}
+
+/// A function for reading the capitalization rules of the main namespace from the site info of the wiki. Older
+/// wikis only give the setting for the whole wiki, and first letter capitalization is assumed if neither is given
+///
+/// # Arguments
+///
+/// * 'client' - A reference to a WikiClient with an api session
+///
+/// # Returns
+///
+/// * TitleCase - The capitalization rules of the titles of articles
+fn title_case(client: &WikiClient) -> TitleCase {
+    let api = client.api();
+    api.get_site_info_value("namespaces", "0")["case"].as_str()
+        .or_else(|| api.get_site_info_value("general", "case").as_str())
+        .and_then(TitleCase::from_setting)
+        .unwrap_or(TitleCase::FirstLetter)
+}
+
+/// A function for reading from the site info of the wiki whether it converts titles between the variants of its
+/// language. The variants are listed for every language having them, but only converted if the conversion hasn't
+/// been disabled on the wiki
+///
+/// # Arguments
+///
+/// * 'client' - A reference to a WikiClient with an api session
+///
+/// # Returns
+///
+/// * bool - Whether the titles are converted between variants
+fn has_variants(client: &WikiClient) -> bool {
+    let api = client.api();
+    !api.get_site_info_value("general", "langconversion").is_null()
+        && api.get_site_info_value("general", "variants").as_array().is_some_and(|variants| variants.len() > 1)
+}
+
+/// A function for finding a parameter of a module in a paraminfo response
+///
+/// # Arguments
+///
+/// * 'module' - A reference to the module object of the response
+/// * 'name' - A string slice with the name of the parameter, without the module prefix
+///
+/// # Returns
+///
+/// * Option<&serde_json::Value> - The parameter object, or None if the module doesn't have the parameter
+fn find_parameter<'a>(module: &'a serde_json::Value, name: &str) -> Option<&'a serde_json::Value> {
+    module["parameters"].as_array()?.iter().find(|parameter| parameter["name"].as_str() == Some(name))
+}
@@ -0,0 +1,234 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json;
+
+// How long an injected 429 response asks the client to wait, kept short so that the retries don't slow the crawls
+// down more than needed
+pub const INJECTED_RETRY_AFTER: Duration = Duration::from_secs(1);
+// The warning the API answers with when a result was too large to return whole
+const TRUNCATION_WARNING: &str = "This result was truncated because it would otherwise be larger than the limit.";
+
+/// An enum representing the kinds of failures injected into the requests
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Failure {
+    /// The request times out without an answer
+    Timeout,
+    /// The server answers with 429 Too Many Requests
+    Throttle,
+    /// The response is cut off in the middle, so it isn't valid JSON
+    MalformedJson,
+    /// The API drops part of the result and warns that it was truncated
+    TruncatedBatch,
+}
+
+impl Failure {
+    pub const ALL: [Failure; 4] = [Failure::Timeout, Failure::Throttle, Failure::MalformedJson,
+                                   Failure::TruncatedBatch];
+
+    /// Returns the name the failure is reported with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Failure::Timeout => "timeouts",
+            Failure::Throttle => "429 responses",
+            Failure::MalformedJson => "malformed responses",
+            Failure::TruncatedBatch => "truncated batches",
+        }
+    }
+
+    /// Returns the position of the failure in Failure::ALL
+    fn index(&self) -> usize {
+        match self {
+            Failure::Timeout => 0,
+            Failure::Throttle => 1,
+            Failure::MalformedJson => 2,
+            Failure::TruncatedBatch => 3,
+        }
+    }
+}
+
+/// A struct deciding which requests fail and how, shared by every clone of a WikiClient. Each request fails with
+/// roughly the given chance, with each kind of failure as likely as the others. The failures are drawn from a
+/// generator seeded with the given seed, so that a run can be repeated with the same draws, although concurrent
+/// requests may draw them in a different order
+pub struct Chaos {
+    rate: f64,
+    seed: u64,
+    state: AtomicU64,
+    injected: [AtomicUsize; 4],
+}
+
+impl Chaos {
+    /// A constructor for Chaos
+    ///
+    /// # Arguments
+    ///
+    /// * 'rate' - The chance of a request failing, between 0 and 1
+    /// * 'seed' - An option containing the seed of the draws, or None to seed them with the current time
+    ///
+    /// # Returns
+    ///
+    /// * Chaos - A new Chaos with no failures injected yet
+    pub fn new(rate: f64, seed: Option<u64>) -> Chaos {
+        let seed = seed.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos() as u64)
+        });
+        Chaos { rate, seed, state: AtomicU64::new(seed), injected: Default::default() }
+    }
+
+    /// Returns the seed of the draws, for repeating the run
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the chance of a request failing
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// A function for drawing whether a request fails at a point where only some kinds of failures can happen. The
+    /// chance is split evenly between the kinds, so that a request going through every point fails with the rate
+    ///
+    /// # Arguments
+    ///
+    /// * 'kinds' - A slice of the kinds of failures possible at this point
+    ///
+    /// # Returns
+    ///
+    /// * Option<Failure> - The failure to inject, or None if the request goes through as it is
+    pub fn draw(&self, kinds: &[Failure]) -> Option<Failure> {
+        let share = self.rate / Failure::ALL.len() as f64;
+        let roll = self.next_fraction();
+        let failure = *kinds.get((roll / share) as usize)?;
+        self.injected[failure.index()].fetch_add(1, Ordering::Relaxed);
+        Some(failure)
+    }
+
+    /// Returns how many failures of each kind have been injected so far
+    pub fn injected(&self) -> Vec<(Failure, usize)> {
+        Failure::ALL.iter()
+            .map(|failure| (*failure, self.injected[failure.index()].load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Returns a summary of the injected failures, such as Injected 3 failures: 1 timeouts, 0 429 responses, ...
+    pub fn summary(&self) -> String {
+        let injected = self.injected();
+        let counts: Vec<String> = injected.iter()
+            .map(|(failure, count)| format!("{} {}", count, failure.as_str()))
+            .collect();
+        format!("Injected {} failures with the seed {}: {}", injected.iter().map(|(_, count)| count).sum::<usize>(),
+                self.seed, counts.join(", "))
+    }
+
+    /// Returns the next draw of the generator as a fraction between 0 and 1, using the SplitMix64 generator, whose
+    /// state only ever grows by a constant so that it can be shared without a lock
+    fn next_fraction(&self) -> f64 {
+        const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut value = self.state.fetch_add(GAMMA, Ordering::Relaxed).wrapping_add(GAMMA);
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^= value >> 31;
+        (value >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Returns the first half of a response, which can't be parsed as JSON anymore
+pub fn malform(text: &str) -> &str {
+    let mut end = text.len() / 2;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// A function for truncating a response like the API does when a result would be too large. The links of each page
+/// are cut in half and the continuation is dropped, and the response warns about the truncation
+///
+/// # Arguments
+///
+/// * 'response' - The parsed response
+///
+/// # Returns
+///
+/// * serde_json::Value - The truncated response
+pub fn truncate(mut response: serde_json::Value) -> serde_json::Value {
+    let object = match response.as_object_mut() {
+        Some(object) => object,
+        None => return response,
+    };
+    object.remove("continue");
+    if let Some(pages) = object.get_mut("query").and_then(|query| query.get_mut("pages"))
+        .and_then(serde_json::Value::as_object_mut) {
+        for page in pages.values_mut() {
+            for key in ["links", "linkshere"] {
+                if let Some(links) = page.get_mut(key).and_then(serde_json::Value::as_array_mut) {
+                    links.truncate(links.len() / 2);
+                }
+            }
+        }
+    }
+    object.insert("warnings".to_string(), serde_json::json!({ "result": { "*": TRUNCATION_WARNING } }));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Enough draws for the shares of the kinds to settle close to their chance
+    const DRAWS: usize = 40000;
+
+    #[test]
+    fn draws_nothing_with_a_rate_of_zero() {
+        let chaos = Chaos::new(0.0, Some(42));
+        for _ in 0..DRAWS {
+            assert_eq!(chaos.draw(&Failure::ALL), None);
+        }
+        assert!(chaos.injected().iter().all(|(_, count)| *count == 0));
+    }
+
+    #[test]
+    fn draws_every_time_with_a_rate_of_one() {
+        let chaos = Chaos::new(1.0, Some(42));
+        for _ in 0..DRAWS {
+            assert!(chaos.draw(&Failure::ALL).is_some());
+        }
+    }
+
+    #[test]
+    fn splits_the_rate_evenly_between_the_kinds() {
+        let chaos = Chaos::new(0.4, Some(42));
+        for _ in 0..DRAWS {
+            chaos.draw(&Failure::ALL);
+        }
+        // Each of the four kinds gets a tenth of the draws
+        for (failure, count) in chaos.injected() {
+            let share = count as f64 / DRAWS as f64;
+            assert!((share - 0.1).abs() < 0.01, "{} were drawn {} times", failure.as_str(), count);
+        }
+    }
+
+    #[test]
+    fn draws_only_the_given_kinds_with_their_share_of_the_rate() {
+        let chaos = Chaos::new(0.4, Some(42));
+        let mut failed = 0;
+        for _ in 0..DRAWS {
+            if let Some(failure) = chaos.draw(&[Failure::Timeout, Failure::Throttle]) {
+                assert!(failure == Failure::Timeout || failure == Failure::Throttle);
+                failed += 1;
+            }
+        }
+        // The other two kinds would be drawn at a later point of the request, so only half of the rate fails here
+        let share = failed as f64 / DRAWS as f64;
+        assert!((share - 0.2).abs() < 0.01, "{} of the draws failed", failed);
+    }
+
+    #[test]
+    fn repeats_the_draws_with_the_same_seed() {
+        let (first, second) = (Chaos::new(0.5, Some(7)), Chaos::new(0.5, Some(7)));
+        for _ in 0..1000 {
+            assert_eq!(first.draw(&Failure::ALL), second.draw(&Failure::ALL));
+        }
+    }
+}
@@ -1,36 +1,1135 @@
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use regex::Regex;
+
+use super::article_title::ArticleTitle;
+use super::cache::DEFAULT_CACHE_FILE;
+use super::link_lru::DEFAULT_MEMORY_CACHE_SIZE;
+use super::neighborhood::NeighborhoodFormat;
+use super::output_sink::S3_PREFIX;
+use super::display::{AnimationStyle, DisplayConfig};
+use super::graph_export::GraphFormat;
+use super::stop_condition::StopCondition;
+use super::title_filter::TitleFilter;
+use super::visited::VisitedMode;
+use super::warm_up::{DEFAULT_HUB_COUNT, MAX_HUB_COUNT, MIN_WARM_UP_INTERVAL};
+use super::watch::{DEFAULT_WATCH_INTERVAL, DEFAULT_WATCH_LOG, MIN_WATCH_INTERVAL};
 
 pub const DEFAULT_API_PATH: &str = "https://en.wikipedia.org/w/api.php";
+pub const DEFAULT_SUGGESTION_COUNT: u8 = 5;
+pub const MAX_SUGGESTION_COUNT: u8 = 50;
+pub const MAX_PATH_COUNT: usize = 100;
+pub const DEFAULT_SEARCH_NAMESPACE: i64 = 0;
+pub const MIN_FRAME_INTERVAL: u64 = 50;
+pub const MAX_FRAME_INTERVAL: u64 = 10_000;
+pub const MAX_UPDATE_EVERY: u32 = 100;
+pub const MAX_RATE: f64 = 1000.0;
+pub const DEFAULT_CATEGORY_LIMIT: usize = 5000;
+pub const DEFAULT_VERIFY_LENGTH: usize = 3;
+pub const MAX_VERIFY_LENGTH: usize = 6;
+pub const DEFAULT_NEIGHBORHOOD_DEPTH: usize = 2;
+pub const MAX_NEIGHBORHOOD_DEPTH: usize = 3;
+pub const PROFILES_FILE: &str = "./crawler_profiles.json";
+const COMMAND_NAMES: &[&str] = &["compare", "cache", "watch", "degree", "backlinks", "cycle", "diff", "find", "verify",
+                                      "replay-view", "warm-cache", "exists", "neighborhood", "demo"];
+
+/// An enum representing the operation the program was asked to run from the command line
+#[derive(PartialEq, Debug)]
+pub enum Command {
+    /// The interactive menu loop
+    Interactive,
+    /// A forward and a reverse crawl between the given articles, compared side by side
+    Compare { from: String, to: String },
+    /// An operation on the persistent cache, run without connecting to the wiki
+    Cache(CacheCommand),
+    /// Repeated crawls between the given articles, reporting whenever the shortest path changes
+    Watch { from: String, to: String, every: Duration, log: String },
+    /// A report of the amount of links to and from the given article
+    Degree { title: String },
+    /// A listing of the articles linking to the given article, at most limit of them if a limit was given
+    Backlinks { title: String, limit: Option<usize> },
+    /// A crawl for the shortest path leading from the given article back to itself
+    Cycle { title: String },
+    /// A comparison of two crawl report files, run without connecting to the wiki
+    Diff { first: String, second: String },
+    /// A crawl from the given article to the closest article whose introduction contains the text, or matches it as
+    /// a regular expression if regex is set
+    Find { from: String, text: String, regex: bool },
+    /// An exhaustive depth first check for a path of at most max_length links between the given articles
+    Verify { from: String, to: String, max_length: usize },
+    /// A replay of the crawls recorded into a progress log, speed times faster than recorded, or a summary of them
+    /// without the replay. Run without connecting to the wiki
+    ReplayView { file: String, speed: f64, summary: bool },
+    /// A fetch of the links of the hub articles into the cache, listed in the given file or the top most viewed
+    /// articles of the wiki, repeated at the given interval if there is one
+    WarmCache { hubs: Option<String>, top: usize, every: Option<Duration> },
+    /// A check of whether the titles listed in the given file lead to articles, written as CSV into the output file
+    /// if there is one and printed otherwise
+    Exists { file: String, output: Option<String> },
+    /// A listing of the articles within depth clicks of the given article in the given format, written into the
+    /// output if there is one and printed otherwise
+    Neighborhood { title: String, depth: usize, format: NeighborhoodFormat, output: Option<String> },
+    /// A guided crawl through the demo wiki bundled with the program, followed by the interactive menu on the demo
+    /// wiki if menu is set
+    Demo { menu: bool },
+}
+
+/// An enum representing the operations of the cache command
+#[derive(PartialEq, Debug)]
+pub enum CacheCommand {
+    /// Print the entry counts, file size and hit rate of the cache
+    Stats,
+    /// Print the amount of cached redirects and the articles with the most redirects to them
+    Redirects,
+    /// Remove every entry from the cache
+    Clear,
+    /// Remove the entries older than the given age
+    Prune { older_than: Duration },
+    /// Write every entry into the given file as JSON lines
+    Export { file: String },
+}
+
+impl CacheCommand {
+    /// Constructs a cache command out of the arguments following the cache command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the cache command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<CacheCommand, Box<dyn Error>> - The cache command, or an error if the arguments were malformed
+    fn parse(args: Vec<String>) -> Result<CacheCommand, Box<dyn Error>> {
+        const USAGE: &str = concat!("Usage: cache stats | cache redirects | cache clear ",
+                                    "| cache prune --older-than <age> | cache export <file>");
+        let mut args = args.into_iter();
+        let operation = match args.next() {
+            Some(operation) => operation,
+            None => return Err(config_error(USAGE)),
+        };
+
+        let command = match operation.as_str() {
+            "stats" => CacheCommand::Stats,
+            "redirects" => CacheCommand::Redirects,
+            "clear" => CacheCommand::Clear,
+            "prune" => {
+                let flag = args.next();
+                if flag.as_deref() != Some("--older-than") {
+                    return Err(config_error(USAGE));
+                }
+                let age: String = parse_flag_value("--older-than", args.next())?;
+                match parse_age(&age) {
+                    Some(older_than) => CacheCommand::Prune { older_than },
+                    None => return Err(config_error(&format!(
+                        "Invalid age '{}' for --older-than, expected a number followed by s, m, h, d or w", age))),
+                }
+            },
+            "export" => match args.next() {
+                Some(file) => CacheCommand::Export { file },
+                None => return Err(config_error(USAGE)),
+            },
+            _ => return Err(config_error(USAGE)),
+        };
+
+        if args.next().is_some() {
+            return Err(config_error(USAGE));
+        }
+        Ok(command)
+    }
+}
+
+impl Command {
+    /// Returns true if the given argument is the name of a command
+    fn is_command(name: &str) -> bool {
+        COMMAND_NAMES.contains(&name)
+    }
+
+    /// Constructs a command out of its name and the positional arguments following it
+    ///
+    /// # Arguments
+    ///
+    /// * 'name' - A string slice with the name of the command
+    /// * 'args' - A Vec of the positional arguments given after the command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The command, or an error if the arguments don't match the command
+    fn parse(name: &str, mut args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        match name {
+            "compare" => {
+                if args.len() != 2 {
+                    return Err(config_error("Usage: compare <from> <to>"));
+                }
+                let to = args.remove(1);
+                let from = args.remove(0);
+                Ok(Command::Compare { from, to })
+            },
+            "cache" => Ok(Command::Cache(CacheCommand::parse(args)?)),
+            "watch" => Command::parse_watch(args),
+            "degree" => {
+                if args.len() != 1 {
+                    return Err(config_error("Usage: degree <title>"));
+                }
+                Ok(Command::Degree { title: args.remove(0) })
+            },
+            "backlinks" => Command::parse_backlinks(args),
+            "find" => Command::parse_find(args),
+            "verify" => Command::parse_verify(args),
+            "replay-view" => Command::parse_replay_view(args),
+            "warm-cache" => Command::parse_warm_cache(args),
+            "exists" => Command::parse_exists(args),
+            "neighborhood" => Command::parse_neighborhood(args),
+            "demo" => match args.iter().map(String::as_str).collect::<Vec<&str>>().as_slice() {
+                [] => Ok(Command::Demo { menu: false }),
+                ["--menu"] => Ok(Command::Demo { menu: true }),
+                _ => Err(config_error("Usage: demo [--menu]")),
+            },
+            "cycle" => {
+                if args.len() != 1 {
+                    return Err(config_error("Usage: cycle <title>"));
+                }
+                Ok(Command::Cycle { title: args.remove(0) })
+            },
+            "diff" => {
+                if args.len() != 2 {
+                    return Err(config_error("Usage: diff <first report> <second report>"));
+                }
+                let second = args.remove(1);
+                let first = args.remove(0);
+                Ok(Command::Diff { first, second })
+            },
+            _ => Err(config_error(&format!("Unknown command '{}'", name))),
+        }
+    }
+
+    /// Constructs a watch command out of the arguments following the watch command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the watch command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The watch command, or an error if the arguments were malformed
+    fn parse_watch(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: watch <from> <to> [--every <interval>] [--log <file>]";
+        let mut every = DEFAULT_WATCH_INTERVAL;
+        let mut log = DEFAULT_WATCH_LOG.to_string();
+        let mut titles: Vec<String> = vec!();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--every" => {
+                    let interval: String = parse_flag_value(&arg, args.next())?;
+                    every = match parse_age(&interval) {
+                        Some(every) if every >= MIN_WATCH_INTERVAL => every,
+                        Some(_) => return Err(config_error(&format!("--every must be at least {} seconds",
+                                                                    MIN_WATCH_INTERVAL.as_secs()))),
+                        None => return Err(config_error(&format!(
+                            "Invalid interval '{}' for --every, expected a number followed by s, m, h, d or w",
+                            interval))),
+                    };
+                },
+                "--log" => log = parse_flag_value(&arg, args.next())?,
+                flag if flag.starts_with("--") => return Err(config_error(USAGE)),
+                _ => titles.push(arg),
+            }
+        }
+
+        if titles.len() != 2 {
+            return Err(config_error(USAGE));
+        }
+        let to = titles.remove(1);
+        let from = titles.remove(0);
+        Ok(Command::Watch { from, to, every, log })
+    }
+
+    /// Constructs a find command out of the arguments following the find command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the find command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The find command, or an error if the arguments were malformed or the
+    ///   regular expression was invalid
+    fn parse_find(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: find <from> <text> [--regex]";
+        let mut regex = false;
+        let mut positionals: Vec<String> = vec!();
+        for arg in args.into_iter() {
+            match arg.as_str() {
+                "--regex" => regex = true,
+                flag if flag.starts_with("--") => return Err(config_error(USAGE)),
+                _ => positionals.push(arg),
+            }
+        }
+
+        if positionals.len() != 2 || positionals[1].trim().is_empty() {
+            return Err(config_error(USAGE));
+        }
+        let text = positionals.remove(1);
+        let from = positionals.remove(0);
+        // Compiled right away, so that a typo is noticed before connecting to the wiki
+        if regex {
+            if let Err(error) = StopCondition::pattern(&text) {
+                return Err(config_error(&format!("Invalid regular expression '{}': {}", text, error)));
+            }
+        }
+        Ok(Command::Find { from, text, regex })
+    }
+
+    /// Constructs a verify command out of the arguments following the verify command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the verify command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The verify command, or an error if the arguments were malformed
+    fn parse_verify(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: verify <from> <to> [--max-len <links>]";
+        let mut max_length = DEFAULT_VERIFY_LENGTH;
+        let mut titles: Vec<String> = vec!();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--max-len" => {
+                    max_length = parse_flag_value(&arg, args.next())?;
+                    if max_length == 0 || max_length > MAX_VERIFY_LENGTH {
+                        return Err(config_error(&format!("--max-len must be between 1 and {}", MAX_VERIFY_LENGTH)));
+                    }
+                },
+                flag if flag.starts_with("--") => return Err(config_error(USAGE)),
+                _ => titles.push(arg),
+            }
+        }
+
+        if titles.len() != 2 {
+            return Err(config_error(USAGE));
+        }
+        let to = titles.remove(1);
+        let from = titles.remove(0);
+        Ok(Command::Verify { from, to, max_length })
+    }
+
+    /// Constructs a replay-view command out of the arguments following the replay-view command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the replay-view command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The replay-view command, or an error if the arguments were malformed
+    fn parse_replay_view(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: replay-view <progress log> [--speed <multiplier>] [--summary]";
+        let mut speed: f64 = 1.0;
+        let mut summary = false;
+        let mut files: Vec<String> = vec!();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--speed" => {
+                    speed = parse_flag_value(&arg, args.next())?;
+                    if !(speed > 0.0 && speed.is_finite()) {
+                        return Err(config_error("--speed must be more than 0"));
+                    }
+                },
+                "--summary" => summary = true,
+                flag if flag.starts_with("--") => return Err(config_error(USAGE)),
+                _ => files.push(arg),
+            }
+        }
+
+        if files.len() != 1 {
+            return Err(config_error(USAGE));
+        }
+        Ok(Command::ReplayView { file: files.remove(0), speed, summary })
+    }
+
+    /// Constructs a backlinks command out of the arguments following the backlinks command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the backlinks command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The backlinks command, or an error if the arguments were malformed
+    fn parse_backlinks(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: backlinks <title> [--limit <count>]";
+        let mut limit: Option<usize> = None;
+        let mut titles: Vec<String> = vec!();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--limit" => {
+                    let count: usize = parse_flag_value(&arg, args.next())?;
+                    if count == 0 {
+                        return Err(config_error("--limit must be at least 1"));
+                    }
+                    limit = Some(count);
+                },
+                flag if flag.starts_with("--") => return Err(config_error(USAGE)),
+                _ => titles.push(arg),
+            }
+        }
+
+        if titles.len() != 1 {
+            return Err(config_error(USAGE));
+        }
+        Ok(Command::Backlinks { title: titles.remove(0), limit })
+    }
+
+    /// Constructs an exists command out of the arguments following the exists command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the exists command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The exists command, or an error if the arguments were malformed
+    fn parse_exists(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: exists <file of titles> [--output <file>]";
+        let mut output: Option<String> = None;
+        let mut files: Vec<String> = vec!();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--output" => output = Some(parse_output_target(&arg, args.next())?),
+                flag if flag.starts_with("--") => return Err(config_error(USAGE)),
+                _ => files.push(arg),
+            }
+        }
+
+        if files.len() != 1 {
+            return Err(config_error(USAGE));
+        }
+        Ok(Command::Exists { file: files.remove(0), output })
+    }
+
+    /// Constructs a neighborhood command out of the arguments following the neighborhood command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the neighborhood command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The neighborhood command, or an error if the arguments were malformed
+    fn parse_neighborhood(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: neighborhood <title> [--depth <clicks>] [--format html|csv] [--output <file>]";
+        let mut depth = DEFAULT_NEIGHBORHOOD_DEPTH;
+        let mut format = NeighborhoodFormat::Html;
+        let mut output: Option<String> = None;
+        let mut titles: Vec<String> = vec!();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--depth" => {
+                    depth = parse_flag_value(&arg, args.next())?;
+                    if !(1..=MAX_NEIGHBORHOOD_DEPTH).contains(&depth) {
+                        return Err(config_error(&format!("--depth must be between 1 and {}",
+                                                         MAX_NEIGHBORHOOD_DEPTH)));
+                    }
+                },
+                "--format" => {
+                    let name: String = parse_flag_value(&arg, args.next())?;
+                    format = match NeighborhoodFormat::parse(&name) {
+                        Some(format) => format,
+                        None => return Err(config_error(&format!(
+                            "Invalid value '{}' for --format, expected html or csv", name))),
+                    };
+                },
+                "--output" => output = Some(parse_output_target(&arg, args.next())?),
+                flag if flag.starts_with("--") => return Err(config_error(USAGE)),
+                _ => titles.push(arg),
+            }
+        }
+
+        if titles.len() != 1 {
+            return Err(config_error(USAGE));
+        }
+        Ok(Command::Neighborhood { title: titles.remove(0), depth, format, output })
+    }
+
+    /// Constructs a warm-cache command out of the arguments following the warm-cache command name
+    ///
+    /// # Arguments
+    ///
+    /// * 'args' - A Vec of the arguments given after the warm-cache command name
+    ///
+    /// # Returns
+    ///
+    /// * Result<Command, Box<dyn Error>> - The warm-cache command, or an error if the arguments were malformed
+    fn parse_warm_cache(args: Vec<String>) -> Result<Command, Box<dyn Error>> {
+        const USAGE: &str = "Usage: warm-cache [--hubs <file>] [--top <count>] [--every <interval>]";
+        let mut hubs: Option<String> = None;
+        let mut top = DEFAULT_HUB_COUNT;
+        let mut every: Option<Duration> = None;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--hubs" => hubs = Some(parse_flag_value(&arg, args.next())?),
+                "--top" => {
+                    top = parse_flag_value(&arg, args.next())?;
+                    if top == 0 || top > MAX_HUB_COUNT {
+                        return Err(config_error(&format!("--top must be between 1 and {}", MAX_HUB_COUNT)));
+                    }
+                },
+                "--every" => {
+                    let interval: String = parse_flag_value(&arg, args.next())?;
+                    every = match parse_age(&interval) {
+                        Some(every) if every >= MIN_WARM_UP_INTERVAL => Some(every),
+                        Some(_) => return Err(config_error(&format!("--every must be at least {} seconds",
+                                                                    MIN_WARM_UP_INTERVAL.as_secs()))),
+                        None => return Err(config_error(&format!(
+                            "Invalid interval '{}' for --every, expected a number followed by s, m, h, d or w",
+                            interval))),
+                    };
+                },
+                _ => return Err(config_error(USAGE)),
+            }
+        }
+        Ok(Command::WarmCache { hubs, top, every })
+    }
+}
 
 /// Struct representing the configs of the program
 pub struct Config {
     pub api_path: String,
+    pub command: Command,
+    pub suggestion_count: u8,
+    pub search_namespace: i64,
+    pub fuzzy_search: bool,
+    pub strict_titles: bool,
+    pub json_output: Option<String>,
+    pub sdow_output: Option<String>,
+    pub skip_log: Option<String>,
+    pub graph_output: Option<String>,
+    pub cache_file: Option<String>,
+    pub annotate_paths: bool,
+    pub visited_mode: VisitedMode,
+    pub deterministic: bool,
+    pub progress_json: Option<String>,
+    pub preview: bool,
+    pub verify_cache: bool,
+    pub must_exist_in: Option<String>,
+    pub time_limit: Option<Duration>,
+    pub max_api_calls: Option<usize>,
+    pub cleanup_grace: Option<Duration>,
+    pub api_fixture: Option<String>,
+    pub title_filter: Option<Arc<TitleFilter>>,
+    pub path_count: usize,
+    pub max_frontier: Option<usize>,
+    pub approximate: Option<f64>,
+    pub undirected: bool,
+    pub auto_direction: bool,
+    pub goal_category: Option<String>,
+    pub category_limit: usize,
+    // The articles of the goal category, resolved once connected to the wiki
+    pub category_members: Option<Arc<HashSet<ArticleTitle>>>,
+    pub as_of: Option<String>,
+    pub anchor_pattern: Option<Arc<Regex>>,
+    pub display: DisplayConfig,
+    pub max_rate: Option<f64>,
+    pub memory_cache_size: usize,
+    pub estimate_links: bool,
+    pub html_fallback: bool,
+    pub transcript: Option<String>,
+    pub loose_goal_match: bool,
+    pub provenance: bool,
+    pub inject_failures: Option<f64>,
+    pub chaos_seed: Option<u64>,
 }
 
 impl Config {
 
     /// Constructs a config struct out of the given arguments
-    /// 
+    ///
     /// # Arguments
-    /// 
+    ///
     /// * 'args' - An env::Args iterator
-    /// 
+    ///
     /// # Returns
-    /// 
-    /// * Config - A new Config instance
-    pub fn new(mut args: env::Args) -> Config {
+    ///
+    /// * Result<Config, Box<dyn Error>> - A new Config instance, or an error if the arguments were malformed
+    pub fn new(args: env::Args) -> Result<Config, Box<dyn Error>> {
 
-        // Consume program name
-        args.next();
+        // Skip the program name
+        let mut args = expand_profiles(args.skip(1).collect(), PROFILES_FILE)?.into_iter();
 
-        let api_path = match args.next() {
-            Some(string) => string.to_string(),
-            None => {
+        let mut api_path: Option<String> = None;
+        let mut suggestion_count = DEFAULT_SUGGESTION_COUNT;
+        let mut search_namespace = DEFAULT_SEARCH_NAMESPACE;
+        let mut fuzzy_search = true;
+        let mut strict_titles = false;
+        let mut json_output: Option<String> = None;
+        let mut sdow_output: Option<String> = None;
+        let mut skip_log: Option<String> = None;
+        let mut graph_output: Option<String> = None;
+        let mut cache_file = Some(DEFAULT_CACHE_FILE.to_string());
+        let mut annotate_paths = false;
+        let mut visited_mode = VisitedMode::Titles;
+        let mut deterministic = false;
+        let mut progress_json: Option<String> = None;
+        let mut preview = true;
+        let mut verify_cache = true;
+        let mut must_exist_in: Option<String> = None;
+        let mut time_limit: Option<Duration> = None;
+        let mut max_api_calls: Option<usize> = None;
+        let mut cleanup_grace: Option<Duration> = None;
+        let mut api_fixture: Option<String> = None;
+        let mut block_file: Option<String> = None;
+        let mut allow_file: Option<String> = None;
+        let mut path_count: usize = 1;
+        let mut max_frontier: Option<usize> = None;
+        let mut approximate: Option<f64> = None;
+        let mut undirected = false;
+        let mut auto_direction = false;
+        let mut goal_category: Option<String> = None;
+        let mut category_limit = DEFAULT_CATEGORY_LIMIT;
+        let mut as_of: Option<String> = None;
+        let mut anchor_pattern: Option<Arc<Regex>> = None;
+        let mut display = DisplayConfig::default();
+        let mut max_rate: Option<f64> = None;
+        let mut memory_cache_size = DEFAULT_MEMORY_CACHE_SIZE;
+        let mut estimate_links = false;
+        let mut html_fallback = false;
+        let mut transcript: Option<String> = None;
+        let mut loose_goal_match = false;
+        let mut provenance = false;
+        let mut inject_failures: Option<f64> = None;
+        let mut chaos_seed: Option<u64> = None;
+        let mut positionals: Vec<String> = vec!();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--api" => api_path = Some(parse_flag_value(&arg, args.next())?),
+                "--suggestions" => {
+                    suggestion_count = parse_flag_value(&arg, args.next())?;
+                    if suggestion_count == 0 || suggestion_count > MAX_SUGGESTION_COUNT {
+                        return Err(config_error(&format!("--suggestions must be between 1 and {}",
+                                                         MAX_SUGGESTION_COUNT)));
+                    }
+                },
+                "--search-namespace" => search_namespace = parse_flag_value(&arg, args.next())?,
+                "--no-fuzzy" => fuzzy_search = false,
+                "--strict-titles" => {
+                    fuzzy_search = false;
+                    strict_titles = true;
+                },
+                "--json" => json_output = Some(parse_output_target(&arg, args.next())?),
+                "--sdow" => sdow_output = Some(parse_output_target(&arg, args.next())?),
+                "--skip-log" => skip_log = Some(parse_flag_value(&arg, args.next())?),
+                "--graph" => {
+                    let file: String = parse_flag_value(&arg, args.next())?;
+                    if GraphFormat::from_file(&file).is_none() {
+                        return Err(config_error(&format!(
+                            "Can't tell the format of '{}' for --graph, expected a .dot, .gv, .csv or .graphml file",
+                            file)));
+                    }
+                    graph_output = Some(file);
+                },
+                "--cache" => cache_file = Some(parse_flag_value(&arg, args.next())?),
+                "--no-cache" => cache_file = None,
+                "--memory-cache" => memory_cache_size = parse_flag_value(&arg, args.next())?,
+                "--annotate" => annotate_paths = true,
+                "--deterministic" => deterministic = true,
+                "--undirected" => undirected = true,
+                "--auto-direction" => auto_direction = true,
+                "--progress-json" => progress_json = Some(parse_flag_value(&arg, args.next())?),
+                "--no-preview" => preview = false,
+                "--estimate-links" => estimate_links = true,
+                "--allow-html-fallback" => html_fallback = true,
+                "--loose-goal-match" => loose_goal_match = true,
+                "--provenance" => provenance = true,
+                "--transcript" => transcript = Some(parse_flag_value(&arg, args.next())?),
+                "--verify-cache" => verify_cache = true,
+                "--trust-cache" => verify_cache = false,
+                "--time-limit" => {
+                    let limit: String = parse_flag_value(&arg, args.next())?;
+                    time_limit = match parse_age(&limit) {
+                        Some(limit) if !limit.is_zero() => Some(limit),
+                        _ => return Err(config_error(&format!(
+                            "Invalid time '{}' for --time-limit, expected a number followed by s, m, h, d or w",
+                            limit))),
+                    };
+                },
+                "--max-api-calls" => {
+                    let limit: usize = parse_flag_value(&arg, args.next())?;
+                    if limit == 0 {
+                        return Err(config_error("--max-api-calls must be at least 1"));
+                    }
+                    max_api_calls = Some(limit);
+                },
+                "--cleanup-grace" => {
+                    let grace: String = parse_flag_value(&arg, args.next())?;
+                    cleanup_grace = match parse_age(&grace) {
+                        Some(grace) => Some(grace),
+                        None => return Err(config_error(&format!(
+                            "Invalid time '{}' for --cleanup-grace, expected a number followed by s, m, h, d or w",
+                            grace))),
+                    };
+                },
+                "--max-rate" => {
+                    let rate: f64 = parse_flag_value(&arg, args.next())?;
+                    if !(rate > 0.0 && rate <= MAX_RATE) {
+                        return Err(config_error(&format!("--max-rate must be more than 0 and at most {}", MAX_RATE)));
+                    }
+                    max_rate = Some(rate);
+                },
+                "--paths" => {
+                    path_count = parse_flag_value(&arg, args.next())?;
+                    if path_count == 0 || path_count > MAX_PATH_COUNT {
+                        return Err(config_error(&format!("--paths must be between 1 and {}", MAX_PATH_COUNT)));
+                    }
+                },
+                "--max-frontier-per-depth" => {
+                    let limit: usize = parse_flag_value(&arg, args.next())?;
+                    if limit == 0 {
+                        return Err(config_error("--max-frontier-per-depth must be at least 1"));
+                    }
+                    max_frontier = Some(limit);
+                },
+                "--approximate" => {
+                    let fraction: f64 = parse_flag_value(&arg, args.next())?;
+                    if !(fraction > 0.0 && fraction < 1.0) {
+                        return Err(config_error("--approximate must be more than 0 and less than 1"));
+                    }
+                    approximate = Some(fraction);
+                },
+                "--to-category" => {
+                    let category: String = parse_flag_value(&arg, args.next())?;
+                    let category = category.trim();
+                    if category.is_empty() {
+                        return Err(config_error("--to-category needs the name of a category"));
+                    }
+                    // The canonical prefix works on every wiki, whatever the name of the namespace is in its language
+                    goal_category = Some(if category.contains(':') {
+                        category.to_string()
+                    } else {
+                        format!("Category:{}", category)
+                    });
+                },
+                "--category-limit" => {
+                    category_limit = parse_flag_value(&arg, args.next())?;
+                    if category_limit == 0 {
+                        return Err(config_error("--category-limit must be at least 1"));
+                    }
+                },
+                "--as-of" => {
+                    let date: String = parse_flag_value(&arg, args.next())?;
+                    as_of = match parse_as_of(&date) {
+                        Some(timestamp) => Some(timestamp),
+                        None => return Err(config_error(&format!(
+                            "Invalid date '{}' for --as-of, expected a date such as 2015-06-01", date))),
+                    };
+                },
+                "--anchor-pattern" => {
+                    let pattern: String = parse_flag_value(&arg, args.next())?;
+                    anchor_pattern = match Regex::new(&pattern) {
+                        Ok(regex) => Some(Arc::new(regex)),
+                        Err(error) => return Err(config_error(&format!(
+                            "Invalid pattern '{}' for --anchor-pattern: {}", pattern, error))),
+                    };
+                },
+                "--animation" => {
+                    let style: String = parse_flag_value(&arg, args.next())?;
+                    display.style = match AnimationStyle::parse(&style) {
+                        Some(style) => style,
+                        None => return Err(config_error(&format!(
+                            "Invalid value '{}' for --animation, expected dots, spinner, bar or none", style))),
+                    };
+                },
+                "--frame-interval" => {
+                    let milliseconds: u64 = parse_flag_value(&arg, args.next())?;
+                    if !(MIN_FRAME_INTERVAL..=MAX_FRAME_INTERVAL).contains(&milliseconds) {
+                        return Err(config_error(&format!("--frame-interval must be between {} and {} milliseconds",
+                                                         MIN_FRAME_INTERVAL, MAX_FRAME_INTERVAL)));
+                    }
+                    display.frame_interval = Duration::from_millis(milliseconds);
+                },
+                "--update-every" => {
+                    display.update_every = parse_flag_value(&arg, args.next())?;
+                    if display.update_every == 0 || display.update_every > MAX_UPDATE_EVERY {
+                        return Err(config_error(&format!("--update-every must be between 1 and {}",
+                                                         MAX_UPDATE_EVERY)));
+                    }
+                },
+                "--block-file" => block_file = Some(parse_flag_value(&arg, args.next())?),
+                "--allow-file" => allow_file = Some(parse_flag_value(&arg, args.next())?),
+                "--api-fixture" => {
+                    if !cfg!(feature = "fixture-server") {
+                        return Err(config_error(
+                            "--api-fixture needs the crawler to be built with --features fixture-server"));
+                    }
+                    api_fixture = Some(parse_flag_value(&arg, args.next())?);
+                },
+                "--inject-failures" | "--chaos-seed" if !cfg!(feature = "chaos") => {
+                    return Err(config_error(&format!("{} needs the crawler to be built with --features chaos", arg)));
+                },
+                "--inject-failures" => {
+                    let rate: f64 = parse_flag_value(&arg, args.next())?;
+                    if !(rate > 0.0 && rate <= 1.0) {
+                        return Err(config_error("--inject-failures must be more than 0 and at most 1"));
+                    }
+                    inject_failures = Some(rate);
+                },
+                "--chaos-seed" => chaos_seed = Some(parse_flag_value(&arg, args.next())?),
+                "--must-exist-in" => {
+                    let language: String = parse_flag_value(&arg, args.next())?;
+                    if language.is_empty() || !language.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+                        return Err(config_error(&format!(
+                            "Invalid language code '{}' for --must-exist-in, expected a code such as fi", language)));
+                    }
+                    must_exist_in = Some(language);
+                },
+                "--low-memory" => {
+                    let mode: String = parse_flag_value(&arg, args.next())?;
+                    visited_mode = match VisitedMode::parse(&mode) {
+                        Some(mode) => mode,
+                        None => return Err(config_error(&format!(
+                            "Invalid value '{}' for --low-memory, expected hashed or verified", mode))),
+                    };
+                },
+                // Flags after a command name that aren't global are left for the command to parse
+                flag if flag.starts_with("--") && positionals.iter().any(|arg| Command::is_command(arg)) =>
+                    positionals.push(arg),
+                flag if flag.starts_with("--") => return Err(config_error(&format!("Unknown flag '{}'", flag))),
+                _ => positionals.push(arg),
+            }
+        }
+
+        // The api path can still be given as the first positional argument, before the command
+        let mut positionals = positionals.into_iter().peekable();
+        if let Some(first) = positionals.peek() {
+            if !Command::is_command(first) && api_path.is_none() && api_fixture.is_none() {
+                api_path = positionals.next();
+            }
+        }
+
+        let command = match positionals.next() {
+            Some(name) => Command::parse(&name, positionals.collect())?,
+            None => Command::Interactive,
+        };
+
+        if let Command::Demo { .. } = command {
+            if !cfg!(feature = "fixture-server") {
+                return Err(config_error("demo needs the crawler to be built with --features fixture-server"));
+            }
+            if api_path.is_some() || api_fixture.is_some() {
+                return Err(config_error("demo crawls the wiki bundled with the program, so it can't be used with an \
+                                         api path or --api-fixture"));
+            }
+        }
+
+        let api_path = match (api_path, &api_fixture) {
+            (Some(_), Some(_)) => return Err(config_error("--api-fixture can't be used with an api path")),
+            (Some(string), None) => string,
+            // The fixture server picks its port when started, so the path is set once it's running
+            (None, Some(_)) => String::new(),
+            (None, None) if matches!(command, Command::Demo { .. }) => String::new(),
+            (None, None) => {
                 println!("Didn't find api path in args, using the default: '{}'", DEFAULT_API_PATH);
                 DEFAULT_API_PATH.to_string()
             },
         };
 
-        Config { api_path }
+        if as_of.is_some() {
+            if cache_file.is_none() {
+                return Err(config_error("--as-of needs the cache and can't be used with --no-cache"));
+            }
+            match command {
+                Command::Compare { .. } => return Err(config_error(
+                    "--as-of only follows the links from articles and not to them, so it can't be used with compare")),
+                Command::Watch { .. } => return Err(config_error(
+                    "--as-of can't be used with watch, as the links of the past don't change")),
+                _ => (),
+            }
+        }
+
+        if anchor_pattern.is_some() {
+            if as_of.is_some() {
+                return Err(config_error("--anchor-pattern reads the current links of the articles, so it can't be \
+                                         used with --as-of"));
+            }
+            if let Command::Compare { .. } = command {
+                return Err(config_error("--anchor-pattern only follows the links from articles and not to them, so \
+                                         it can't be used with compare"));
+            }
+        }
+
+        if undirected {
+            if as_of.is_some() {
+                return Err(config_error("--as-of only follows the links from articles, so it can't be used with \
+                                         --undirected"));
+            }
+            if anchor_pattern.is_some() {
+                return Err(config_error("--anchor-pattern only follows the links from articles, so it can't be used \
+                                         with --undirected"));
+            }
+            match command {
+                Command::Compare { .. } => return Err(config_error(
+                    "compare crawls in both directions on its own, so it can't be used with --undirected")),
+                Command::Cycle { .. } => return Err(config_error(
+                    "Every link is a cycle when followed both ways, so cycle can't be used with --undirected")),
+                Command::Verify { .. } => return Err(config_error(
+                    "verify only follows the links from articles, so it can't be used with --undirected")),
+                _ => (),
+            }
+        }
+
+        if auto_direction {
+            if undirected {
+                return Err(config_error("--undirected follows the links both ways already, so it can't be used with \
+                                         --auto-direction"));
+            }
+            if as_of.is_some() {
+                return Err(config_error("--as-of only follows the links from articles, so it can't be used with \
+                                         --auto-direction"));
+            }
+            if anchor_pattern.is_some() {
+                return Err(config_error("--anchor-pattern only follows the links from articles, so it can't be used \
+                                         with --auto-direction"));
+            }
+            if goal_category.is_some() {
+                return Err(config_error("A reverse crawl can't start from every article of a category, so \
+                                         --to-category can't be used with --auto-direction"));
+            }
+        }
+
+        if goal_category.is_some() && command != Command::Interactive {
+            return Err(config_error("--to-category sets the goal of the crawls of the interactive menu, so it can't \
+                                     be used with a command"));
+        }
+
+        if provenance && command != Command::Interactive {
+            return Err(config_error("--provenance is only used for asking why after the crawls of the interactive \
+                                     menu, so it can't be used with a command"));
+        }
+
+        if chaos_seed.is_some() && inject_failures.is_none() {
+            return Err(config_error("--chaos-seed can only be used with --inject-failures"));
+        }
+
+        // The lists are read right away, so that a missing file is noticed before connecting to the wiki
+        let title_filter = match (&block_file, &allow_file) {
+            (None, None) => None,
+            _ => Some(Arc::new(TitleFilter::load(block_file.as_deref(), allow_file.as_deref())?)),
+        };
+
+        Ok(Config { api_path, command, suggestion_count, search_namespace, fuzzy_search, strict_titles,
+                    json_output, sdow_output, skip_log, graph_output, cache_file, annotate_paths, visited_mode,
+                    deterministic, progress_json, preview, verify_cache, must_exist_in, time_limit,
+                    max_api_calls, cleanup_grace, api_fixture, title_filter, path_count, max_frontier, approximate,
+                    undirected, auto_direction, goal_category, category_limit, category_members: None, as_of,
+                    anchor_pattern, display, max_rate, memory_cache_size, estimate_links, html_fallback, transcript,
+                    loose_goal_match, provenance, inject_failures, chaos_seed })
+    }
+
+    /// Returns true if the crawls may only go through some of the articles or only expand a sample of them, may
+    /// follow links backwards or end at any article of a category or another article than the goal, in which case
+    /// the found paths aren't the shortest paths between the articles along the links and mustn't be read from or
+    /// written into the cache
+    pub fn restricts_paths(&self) -> bool {
+        self.must_exist_in.is_some() || self.title_filter.is_some() || self.max_frontier.is_some() || self.undirected
+            || self.goal_category.is_some() || self.loose_goal_match || self.approximate.is_some()
+    }
+}
+
+/// A function for replacing the --profile flags in the arguments with the flags of the named profiles. The flags of
+/// the profiles are put before the rest of the arguments, so that the flags given on the command line win
+///
+/// # Arguments
+///
+/// * 'args' - A Vec of the arguments, without the program name
+/// * 'file' - A string slice with the path of the file the profiles are defined in, read only if a profile was
+///   selected
+///
+/// # Returns
+///
+/// * Result<Vec<String>, Box<dyn Error>> - The arguments with the profiles expanded, or an error if a profile
+///   wasn't defined or the file couldn't be read
+fn expand_profiles(args: Vec<String>, file: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut selected: Vec<String> = vec!();
+    let mut rest: Vec<String> = vec!();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            selected.push(parse_flag_value(&arg, args.next())?);
+        } else {
+            rest.push(arg);
+        }
+    }
+    if selected.is_empty() {
+        return Ok(rest);
     }
+
+    let profiles = load_profiles(file)?;
+    let mut expanded: Vec<String> = vec!();
+    for name in selected.iter() {
+        match profiles.get(name) {
+            Some(flags) => expanded.extend(flags.iter().cloned()),
+            None => {
+                let mut names: Vec<&String> = profiles.keys().collect();
+                names.sort();
+                let names: Vec<&str> = names.into_iter().map(String::as_str).collect();
+                return Err(config_error(&format!("Unknown profile '{}', the profiles defined in '{}' are: {}",
+                                                 name, file, names.join(", "))));
+            },
+        }
+    }
+    expanded.extend(rest);
+    Ok(expanded)
+}
+
+/// A function for reading the named profiles from a file. The file is a JSON object with the flags of each profile
+/// as a list, such as {"fast": ["--no-preview", "--time-limit", "30s"]}
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the file
+///
+/// # Returns
+///
+/// * Result<HashMap<String, Vec<String>>, Box<dyn Error>> - The flags of each profile, or an error if the file
+///   couldn't be read or was malformed
+fn load_profiles(file: &str) -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+    let contents = fs::read_to_string(file)
+        .map_err(|error| config_error(&format!("Couldn't read the profiles from '{}': {}", file, error)))?;
+    let invalid = || config_error(&format!("Invalid profiles in '{}', expected an object with a list of flags for \
+                                            each profile", file));
+    let profiles = match serde_json::from_str::<serde_json::Value>(&contents) {
+        Ok(serde_json::Value::Object(profiles)) => profiles,
+        _ => return Err(invalid()),
+    };
+
+    let mut parsed = HashMap::new();
+    for (name, flags) in profiles.into_iter() {
+        let flags: Vec<String> = flags.as_array()
+            .and_then(|flags| flags.iter().map(|flag| flag.as_str().map(String::from)).collect())
+            .ok_or_else(invalid)?;
+        if flags.iter().any(|flag| flag == "--profile") {
+            return Err(config_error(&format!("The profile '{}' can't select other profiles", name)));
+        }
+        parsed.insert(name, flags);
+    }
+    Ok(parsed)
+}
+
+/// A function for parsing the value following a flag in the arguments
+///
+/// # Arguments
+///
+/// * 'flag' - A string slice with the flag the value belongs to, used for error messages
+/// * 'value' - An option containing the argument after the flag, if there was one
+///
+/// # Returns
+///
+/// * Result<T, Box<dyn Error>> - The parsed value, or an error if the value was missing or malformed
+fn parse_flag_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, Box<dyn Error>> {
+    match value {
+        Some(string) => match string.parse::<T>() {
+            Ok(parsed) => Ok(parsed),
+            Err(_) => Err(config_error(&format!("Invalid value '{}' for {}", string, flag))),
+        },
+        None => Err(config_error(&format!("Missing value for {}", flag))),
+    }
+}
+
+/// A function for parsing the target of an output following a flag, see output_sink::open. S3 targets are checked
+/// here so that a crawler built without the s3 feature fails before the crawl instead of after it
+///
+/// # Arguments
+///
+/// * 'flag' - A string slice with the flag the target belongs to, used for error messages
+/// * 'value' - An option containing the argument after the flag, if there was one
+///
+/// # Returns
+///
+/// * Result<String, Box<dyn Error>> - The target, or an error if it was missing or needs a missing feature
+fn parse_output_target(flag: &str, value: Option<String>) -> Result<String, Box<dyn Error>> {
+    let target: String = parse_flag_value(flag, value)?;
+    if target.starts_with(S3_PREFIX) && !cfg!(feature = "s3") {
+        return Err(config_error(&format!("Writing {} to S3 needs the crawler to be built with --features s3", flag)));
+    }
+    Ok(target)
+}
+
+/// A function for parsing an age given as a number followed by a unit, such as 30d
+///
+/// # Arguments
+///
+/// * 'age' - A string slice with the age
+///
+/// # Returns
+///
+/// * Option<Duration> - The parsed age, or None if the age was malformed
+fn parse_age(age: &str) -> Option<Duration> {
+    let unit_start = age.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = age.split_at(unit_start);
+    let amount: u64 = amount.parse().ok()?;
+    let unit_seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount.checked_mul(unit_seconds)?))
+}
+
+/// A function for parsing the date given to --as-of into the timestamp format of the API
+///
+/// # Arguments
+///
+/// * 'date' - A string slice with the date, such as 2015-06-01, or a full timestamp such as 2015-06-01T12:00:00Z
+///
+/// # Returns
+///
+/// * Option<String> - The date as a timestamp in UTC, or None if it was malformed
+fn parse_as_of(date: &str) -> Option<String> {
+    let (day, time) = date.split_once('T').unwrap_or((date, "00:00:00Z"));
+    let day: Vec<u32> = day.split('-').map(|part| part.parse().ok()).collect::<Option<Vec<u32>>>()?;
+    let time: Vec<u32> = time.strip_suffix('Z')?.split(':').map(|part| part.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    match (day.as_slice(), time.as_slice()) {
+        ([year, month, day], [hour, minute, second])
+            if *year >= 2001 && (1..=12).contains(month) && (1..=31).contains(day) && *hour < 24 && *minute < 60
+                && *second < 60 =>
+            Some(format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, hour, minute, second)),
+        _ => None,
+    }
+}
+
+/// A function for constructing the errors returned while parsing the arguments
+///
+/// # Arguments
+///
+/// * 'message' - A string slice describing what was wrong with the arguments
+///
+/// # Returns
+///
+/// * Box<dyn Error> - The constructed error
+fn config_error(message: &str) -> Box<dyn Error> {
+    Box::new(io::Error::new(io::ErrorKind::InvalidInput, message.to_string()))
 }
@@ -1,17 +1,54 @@
-use std::sync::{Arc, RwLock, mpsc};
-use std::collections::{HashSet, HashMap};
+use std::sync::{Arc, Mutex, RwLock, mpsc};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::io::{stdout, Write};
 
+use serde_json::json;
 use tokio;
 
-use super::wiki_api;
+use super::article_title::ArticleTitle;
+use super::display::{self, DisplayConfig};
+use super::graph_export::GraphWriter;
+use super::progress::{self, BackPressure, CrawlState, LiveProgress, ProgressSnapshot, ProgressWriter};
+use super::provenance::{Discovery, ProvenanceMap};
+use super::sampling::FrontierSample;
+use super::skip_log::{SkipLog, SkipReason};
+use super::statistics::CrawlStats;
+use super::stop_condition::StopCondition;
+use super::title_filter::TitleFilter;
+use super::visited::VisitedSet;
+use super::wiki_api::{self, LinkDirection};
+use super::wiki_client::{Cooldown, CooldownReason, WikiClient};
+
+// The finish states of a crawl, which is running while the state is 0
+const FOUND: u8 = 1;
+const BUDGET_EXHAUSTED: u8 = 2;
+const CANCELLED: u8 = 3;
+const NO_PATH: u8 = 4;
+const FAILED: u8 = 5;
+const ABORTED: u8 = 6;
+// The fraction of a limit of the budget after which the crawl warns that the limit is close
+const SOFT_LIMIT: f64 = 0.8;
+// How long the main thread waits for a batch before checking the budget again
+const BUDGET_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+// How often a progress frame is written during a crawl, if they were asked for
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+// How long the workers still running when a crawl ends are waited for before they're aborted, if not told otherwise
+pub const DEFAULT_CLEANUP_GRACE: Duration = Duration::from_secs(2);
+// The bytes of wikitext per link assumed for the estimates until enough links have been fetched to measure it
+const DEFAULT_BYTES_PER_LINK: f64 = 80.0;
+const MIN_CALIBRATION_LINKS: u64 = 1000;
 
 /// A struct that should be used to build the tree of which the result of the crawl consists
 pub struct ArticleNode {
-    name: String,
+    name: ArticleTitle,
     parent: Option<Arc<ArticleNode>>,
+    depth: usize,
 }
 
 impl ArticleNode {
@@ -19,22 +56,51 @@ impl ArticleNode {
     /// 
     /// # Arguments
     /// 
-    /// * 'name' - A string slice that contains the name of the node
+    /// * 'name' - An ArticleTitle that contains the name of the node
     /// * 'parent' - An option that has an arc containing the parent node of the new node, if it has one
     /// 
     /// # Returns
     /// 
-    /// * ArticleNode - A new article node created from the given parameters
-    fn new(name: &str, parent: Option<Arc<ArticleNode>>) -> ArticleNode {
-        let name = name.to_string();
-        ArticleNode { name, parent }
+    /// * ArticleNode - A new article node created from the given parameters, one level deeper than its parent
+    fn new(name: ArticleTitle, parent: Option<Arc<ArticleNode>>) -> ArticleNode {
+        let depth = parent.as_ref().map_or(0, |parent| parent.depth + 1);
+        ArticleNode { name, parent, depth }
+    }
+}
+
+/// A path found by a crawl, kept as the node of its last article so that the collected paths share the parent chain
+/// of the crawl instead of each holding copies of the titles on it. The titles are only listed once they are shown
+struct FoundPath {
+    node: Arc<ArticleNode>,
+    // The goal the last article links to, or None if the path ends at the article of the node
+    goal: Option<ArticleTitle>,
+}
+
+impl FoundPath {
+    /// Returns the amount of articles on the path
+    fn len(&self) -> usize {
+        self.node.depth + 1 + usize::from(self.goal.is_some())
+    }
+
+    /// Returns the titles on the path from the origin to the goal of the crawl
+    ///
+    /// # Arguments
+    ///
+    /// * 'direction' - The LinkDirection of the crawl, the path of a crawl following backlinks is turned around
+    fn titles(&self, direction: LinkDirection) -> Vec<ArticleTitle> {
+        let mut path = path_to(&self.node);
+        path.extend(self.goal.iter().cloned());
+        if direction == LinkDirection::Incoming {
+            path.reverse();
+        }
+        path
     }
 }
 
 /// A struct that should be used to transfer analysis results from worker threads back to the main thread
 struct BatchData {
     parent: Option<Arc<ArticleNode>>,
-    new_batch: Vec<String>,
+    new_batch: Vec<ArticleTitle>,
 }
 
 impl BatchData {
@@ -43,49 +109,604 @@ impl BatchData {
     /// # Arguments
     /// 
     /// * 'parent' - An option that has the parent for the future ArticleNodes spawned from the result
-    /// * 'new_batch' - A Vec that houses String representations of the new articles to be queried in main thread
+    /// * 'new_batch' - A Vec that houses the titles of the new articles to be queried in main thread
     /// 
     /// # Returns
     /// 
     /// * BatchData - A new batch data struct created from the given parameters
-    fn new(parent: Option<Arc<ArticleNode>>, new_batch: Vec<String>) -> BatchData {
+    fn new(parent: Option<Arc<ArticleNode>>, new_batch: Vec<ArticleTitle>) -> BatchData {
         BatchData { parent, new_batch }
     }
 }
 
+/// A struct estimating the amount of links of articles out of the size of their wikitext, which prop=info tells
+/// without fetching the links. The bytes per link are measured from the batches whose links have been fetched
+#[derive(Default)]
+struct LinkEstimate {
+    bytes: u64,
+    links: u64,
+    // The estimated links of the batch being fetched, None while no estimate is shown
+    expected: Option<usize>,
+}
+
+impl LinkEstimate {
+    /// Returns the measured bytes per link, or the default until enough links have been fetched
+    fn bytes_per_link(&self) -> f64 {
+        if self.links < MIN_CALIBRATION_LINKS {
+            DEFAULT_BYTES_PER_LINK
+        } else {
+            self.bytes as f64 / self.links as f64
+        }
+    }
+
+    /// Records the size of the wikitext of a fetched batch along with the amount of links it had
+    fn calibrate(&mut self, bytes: u64, links: usize) {
+        self.bytes += bytes;
+        self.links += links as u64;
+        self.expected = None;
+    }
+}
+
 /// A struct that houses the data of a crawl shared between main thread and worker threads
 /// Should always be housed in an arc while crawling
 pub struct Crawler {
     origin: ArticleNode,
-    goal: String,
-    visited: RwLock<HashSet<String>>,
+    goal: ArticleTitle,
+    goals: Option<Arc<HashSet<ArticleTitle>>>,
+    loose_goal_match: bool,
+    direction: LinkDirection,
+    undirected: bool,
+    visited: RwLock<VisitedSet>,
     finished: RwLock<u8>,
-    final_node: RwLock<Option<ArticleNode>>
+    final_node: RwLock<Option<ArticleNode>>,
+    stats: Arc<Mutex<CrawlStats>>,
+    live: Arc<LiveProgress>,
+    skip_log: Option<Arc<SkipLog>>,
+    graph: Option<Arc<GraphWriter>>,
+    provenance: Option<Arc<ProvenanceMap>>,
+    deterministic: bool,
+    progress: Option<Arc<ProgressWriter>>,
+    must_exist_in: Option<String>,
+    title_filter: Option<Arc<TitleFilter>>,
+    stop_condition: Option<Arc<StopCondition>>,
+    matched_text: Arc<Mutex<Option<String>>>,
+    max_paths: usize,
+    found_paths: Arc<Mutex<Vec<FoundPath>>>,
+    // The depth of the article before the goal on the first found path, usize::MAX until a path is found
+    path_depth: AtomicUsize,
+    max_frontier: Option<usize>,
+    sample: Option<FrontierSample>,
+    // The estimate of the links of the batches, if the links should be estimated before fetching them
+    link_estimate: Option<Mutex<LinkEstimate>>,
+    budget: CrawlBudget,
+    display: DisplayConfig,
+    cancel: Arc<AtomicBool>,
+    cleanup_grace: Duration,
+    running_workers: AtomicUsize,
+    queued_batches: AtomicUsize,
+    blocked_senders: AtomicUsize,
+    errors: mpsc::Sender<CrawlError>,
+    // The end of the error channel the status line shows the errors from, if nobody else observes them
+    shown_errors: Mutex<Option<mpsc::Receiver<CrawlError>>>,
+    // The paths found by a crawl collecting several of them, sent by the workers for the status line to show
+    found_reports: mpsc::Sender<String>,
+    shown_paths: Mutex<Option<mpsc::Receiver<String>>>,
+}
+
+/// A struct representing an error a crawl ran into and carried on from, such as a fetch failing and its batch being
+/// dropped. The errors are sent through a channel instead of being printed, so that they never get in the way of
+/// the output of the program using the crawler
+#[derive(Clone, PartialEq, Debug)]
+pub struct CrawlError {
+    pub message: String,
+}
+
+impl fmt::Display for CrawlError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.message)
+    }
+}
+
+/// A struct holding the optional settings of a crawl
+#[derive(Default)]
+pub struct CrawlOptions {
+    /// An empty VisitedSet for remembering the reached articles in
+    pub visited: VisitedSet,
+    /// The log to record dropped links in, if one should be kept
+    pub skip_log: Option<Arc<SkipLog>>,
+    /// The writer to append the claimed links to, if the graph of the crawl should be exported
+    pub graph: Option<Arc<GraphWriter>>,
+    /// The map to record how each article was reached into, if the crawl should be explained afterwards
+    pub provenance: Option<Arc<ProvenanceMap>>,
+    /// Whether the articles should be expanded one at a time in a fixed order, so that the same links always
+    /// produce the same path
+    pub deterministic: bool,
+    /// The writer to write machine readable progress frames with, if they were asked for
+    pub progress: Option<Arc<ProgressWriter>>,
+    /// The code of the language every article on the path has to have a version in, if there is one
+    pub must_exist_in: Option<String>,
+    /// The filter of the blocked and allowed articles, if lists of them were given
+    pub title_filter: Option<Arc<TitleFilter>>,
+    /// The condition ending the crawl at the first expanded article meeting it, if there is one. Links to the goal
+    /// don't end such crawls
+    pub stop_condition: Option<Arc<StopCondition>>,
+    /// The amount of shortest paths to collect, each ending with a different article before the goal. With more
+    /// than one, the paths are printed as soon as they are found, and the crawl only ends once it has expanded every
+    /// article shallower than the shortest found path, along with the ones as deep as it unless it has all of the
+    /// paths already. 0 is taken as 1
+    pub max_paths: usize,
+    /// The largest amount of articles queued at each depth, if there is one. Once a depth is full, the rest of the
+    /// articles found at it are left out, which keeps deep crawls from queueing millions of titles but means that the
+    /// found path may not be the shortest one
+    pub max_frontier: Option<usize>,
+    /// The fraction of the articles found past the start of the crawl to expand, if only a random sample of each
+    /// depth should be expanded for estimating the distance cheaply. The found path may then not be the shortest one
+    pub approximate: Option<f64>,
+    /// Whether the backlinks of every article are followed along with its links, treating the links as undirected
+    pub undirected: bool,
+    /// Whether the amount of links of each batch should be estimated from the size of the articles before fetching
+    /// the links, for showing on the status line. Costs one query per batch, and only done for crawls following
+    /// outgoing links
+    pub estimate_links: bool,
+    /// The articles reaching any of which ends the crawl like reaching the goal does, such as the members of a
+    /// category. The found path then ends at the reached article instead of the goal. Only for crawls following
+    /// outgoing links
+    pub goals: Option<Arc<HashSet<ArticleTitle>>>,
+    /// Whether the disambiguators in parentheses are ignored when checking for the goal, so that a goal of Mercury is
+    /// reached by Mercury (planet), and a goal of Mercury (planet) by Mercury. The found path then ends at the
+    /// reached article
+    pub loose_goal_match: bool,
+    /// The limits after which the crawl gives up and answers with a partial path
+    pub budget: CrawlBudget,
+    /// How the status line of the crawl is drawn
+    pub display: DisplayConfig,
+    /// A flag the crawl is cancelled with once it's set, answering with a partial path
+    pub cancel: Arc<AtomicBool>,
+    /// The channel to send the errors the crawl carries on from into, for observing them, along with the errors of
+    /// the requests and the cache of the crawl. Without one the errors are shown on lines of their own above the
+    /// status line of the crawl
+    pub errors: Option<mpsc::Sender<CrawlError>>,
+    /// How long the workers still running when the crawl ends are waited for before they're aborted, or None for
+    /// DEFAULT_CLEANUP_GRACE
+    pub cleanup_grace: Option<Duration>,
+}
+
+/// A struct holding the limits of a crawl. A crawl running out of its budget returns the partial path it got the
+/// furthest with instead of a path to the goal
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct CrawlBudget {
+    /// The longest time the crawl may run for
+    pub time: Option<Duration>,
+    /// The largest amount of API calls the crawl may make
+    pub api_calls: Option<usize>,
+}
+
+impl CrawlBudget {
+    /// Returns true if the crawl has used up either of its limits
+    ///
+    /// # Arguments
+    ///
+    /// * 'elapsed' - The Duration the crawl has been running for
+    /// * 'api_calls' - The amount of API calls the crawl has made
+    pub fn is_exhausted(&self, elapsed: Duration, api_calls: usize) -> bool {
+        self.time.is_some_and(|time| elapsed >= time) || self.api_calls.is_some_and(|limit| api_calls >= limit)
+    }
+
+    /// A function for listing the limits of which the crawl has used at least the soft limit, for warning about them
+    /// before the crawl has to give up
+    ///
+    /// # Arguments
+    ///
+    /// * 'elapsed' - The Duration the crawl has been running for
+    /// * 'api_calls' - The amount of API calls the crawl has made
+    ///
+    /// # Returns
+    ///
+    /// * Vec<BudgetLimit> - The nearly used up limits
+    pub fn nearly_exhausted(&self, elapsed: Duration, api_calls: usize) -> Vec<BudgetLimit> {
+        let mut limits = vec!();
+        if self.time.is_some_and(|time| elapsed >= time.mul_f64(SOFT_LIMIT)) {
+            limits.push(BudgetLimit::Time);
+        }
+        if self.api_calls.is_some_and(|limit| api_calls as f64 >= limit as f64 * SOFT_LIMIT) {
+            limits.push(BudgetLimit::ApiCalls);
+        }
+        limits
+    }
+
+    /// A function for describing how much of a limit the crawl has used
+    ///
+    /// # Arguments
+    ///
+    /// * 'limit' - The BudgetLimit to describe
+    /// * 'elapsed' - The Duration the crawl has been running for
+    /// * 'api_calls' - The amount of API calls the crawl has made
+    ///
+    /// # Returns
+    ///
+    /// * String - The use of the limit, such as 400 of 500 API calls, or an empty string if the crawl has no such
+    ///   limit
+    fn describe_use(&self, limit: BudgetLimit, elapsed: Duration, api_calls: usize) -> String {
+        match (limit, self.time, self.api_calls) {
+            (BudgetLimit::Time, Some(time), _) => format!("{} of {} seconds", elapsed.as_secs(), time.as_secs()),
+            (BudgetLimit::ApiCalls, _, Some(calls)) => format!("{} of {} API calls", api_calls, calls),
+            _ => String::new(),
+        }
+    }
+}
+
+/// An enum representing the limits of a CrawlBudget
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BudgetLimit {
+    Time,
+    ApiCalls,
+}
+
+impl BudgetLimit {
+    /// Returns the name the limit is written to the progress frames with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetLimit::Time => "time",
+            BudgetLimit::ApiCalls => "api_calls",
+        }
+    }
+
+    /// Returns the name the limit is shown to the user with
+    fn describe(&self) -> &'static str {
+        match self {
+            BudgetLimit::Time => "time budget",
+            BudgetLimit::ApiCalls => "request budget",
+        }
+    }
+}
+
+/// An enum representing how a crawl ended
+///
+/// The partial paths of the unfinished crawls lead to the deepest article the crawl expanded. They are ordered from
+/// the origin onwards, or towards the goal for crawls following incoming links
+#[derive(Debug)]
+pub enum CrawlOutcome {
+    /// The shortest path from the origin to the goal was found
+    Found(Vec<ArticleTitle>),
+    /// Every article reachable from the start of the crawl was expanded without reaching the goal
+    NoPath,
+    /// The budget of the crawl ran out before the goal was found
+    BudgetExhausted(Vec<ArticleTitle>),
+    /// The crawl was cancelled before the goal was found
+    Cancelled(Vec<ArticleTitle>),
+    /// The API answered with a fatal error, such as the access of the account being revoked or the API being gone,
+    /// before the goal was found. Holds the error along with the partial path
+    Aborted(String, Vec<ArticleTitle>),
+    /// The crawl couldn't be carried out
+    Failed(Box<dyn Error + Send + Sync>),
+}
+
+impl CrawlOutcome {
+    /// Returns the name the outcome is written to the exported graph and the progress frames with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrawlOutcome::Found(_) => "found",
+            CrawlOutcome::NoPath => "no_path",
+            CrawlOutcome::BudgetExhausted(_) => "budget_exhausted",
+            CrawlOutcome::Cancelled(_) => "cancelled",
+            CrawlOutcome::Aborted(..) => "aborted",
+            CrawlOutcome::Failed(_) => "failed",
+        }
+    }
+
+    /// Returns the CrawlState the progress snapshots of the ended crawl show
+    pub fn state(&self) -> CrawlState {
+        match self {
+            CrawlOutcome::Found(_) => CrawlState::Found,
+            CrawlOutcome::NoPath => CrawlState::NoPath,
+            CrawlOutcome::BudgetExhausted(_) => CrawlState::BudgetExhausted,
+            CrawlOutcome::Cancelled(_) => CrawlState::Cancelled,
+            CrawlOutcome::Aborted(..) => CrawlState::Aborted,
+            CrawlOutcome::Failed(_) => CrawlState::Failed,
+        }
+    }
+}
+
+/// A struct holding everything a finished crawl produced
+pub struct CrawlResult {
+    pub outcome: CrawlOutcome,
+    pub stats: CrawlStats,
+    /// The other paths found by a crawl collecting several shortest paths, besides the one in the outcome
+    pub other_paths: Vec<Vec<ArticleTitle>>,
+    /// The text the article at the end of the path met the stop condition of the crawl with, if it had one
+    pub matched_text: Option<String>,
+    /// How each article the crawl reached was reached, if it was recorded
+    pub provenance: Option<Arc<ProvenanceMap>>,
+}
+
+impl CrawlResult {
+    /// A constructor for the result of a path answered without crawling, such as one read from the cache
+    ///
+    /// # Arguments
+    ///
+    /// * 'path' - The path from the origin to the goal
+    ///
+    /// # Returns
+    ///
+    /// * CrawlResult - A result with the path and empty statistics
+    pub fn without_crawl(path: Vec<ArticleTitle>) -> CrawlResult {
+        CrawlResult { outcome: CrawlOutcome::Found(path), stats: CrawlStats::default(), other_paths: vec!(),
+                      matched_text: None, provenance: None }
+    }
+
+    /// Returns the found path, or None if the crawl didn't find one
+    pub fn path(&self) -> Option<&Vec<ArticleTitle>> {
+        match &self.outcome {
+            CrawlOutcome::Found(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Takes the found path out of the result, or None if the crawl didn't find one
+    pub fn into_path(self) -> Option<Vec<ArticleTitle>> {
+        match self.outcome {
+            CrawlOutcome::Found(path) => Some(path),
+            _ => None,
+        }
+    }
 }
 
 impl Crawler {
     /// A constructor for Crawler that automatically wraps the created Crawler in an Arc
     /// Note that creating a crawler doesn't automatically start a crawl, instead call start for that
     /// 
+    /// A crawl following incoming links starts from the goal and searches backlinks until it reaches the origin.
+    /// The resulting path is still returned in the order from origin to goal
+    /// 
+    /// Giving the same article as the origin and the goal searches for the shortest cycle through the article
+    /// 
     /// # Arguments
     /// 
-    /// * 'origin' - A string slice with the name of the origin article of the crawl
-    /// * 'goal' - A string slice with the name of the goal of the crawl
+    /// * 'origin' - A reference to the title of the origin article of the crawl
+    /// * 'goal' - A reference to the title of the goal of the crawl
+    /// * 'direction' - The LinkDirection the crawl follows the links in
+    /// * 'options' - A CrawlOptions struct with the optional settings of the crawl
     /// 
     /// # Returns
     /// 
     /// * Arc<Crawler> - An Arc that has the created Crawler instance wrapped inside it
-    pub fn new_arc(origin: &str, goal: &str) -> Arc<Crawler> {
-        let mut visited_set: HashSet<String> = HashSet::new();
-        visited_set.insert(origin.to_string());
+    pub fn new_arc(origin: &ArticleTitle, goal: &ArticleTitle, direction: LinkDirection, options: CrawlOptions)
+        -> Arc<Crawler> {
+        let CrawlOptions { mut visited, skip_log, graph, provenance, deterministic, progress, must_exist_in,
+                           title_filter, stop_condition, max_paths, max_frontier, approximate, undirected,
+                           estimate_links, goals, loose_goal_match, budget, display, cancel, errors,
+                           cleanup_grace } = options;
+        let (start, target) = match direction {
+            LinkDirection::Outgoing => (origin, goal),
+            LinkDirection::Incoming => (goal, origin),
+        };
+        let (errors, shown_errors) = match errors {
+            Some(errors) => (errors, None),
+            None => {
+                let (errors, shown_errors) = mpsc::channel();
+                (errors, Some(shown_errors))
+            },
+        };
+        let (found_reports, shown_paths) = mpsc::channel();
+        visited.set_error_reporter(ErrorReporter::new(errors.clone()));
+        visited.insert(start);
+        if let Some(provenance) = &provenance {
+            provenance.record(None, std::slice::from_ref(start), 0, Discovery::default());
+        }
+        let mut stats = CrawlStats::default();
+        stats.record_discovered(0, 1);
+        let live = LiveProgress::new(budget.time);
+        live.set_visited(visited.len());
+        live.record_discovered(1);
         Arc::new( Crawler {
-            origin: ArticleNode::new(origin, None),
-            goal: goal.to_string(),
-            visited: RwLock::new(visited_set),
+            origin: ArticleNode::new(start.clone(), None),
+            goal: target.clone(),
+            goals,
+            loose_goal_match,
+            direction,
+            undirected,
+            visited: RwLock::new(visited),
             finished: RwLock::new(0),
             final_node: RwLock::new(None),
+            stats: Arc::new(Mutex::new(stats)),
+            live: Arc::new(live),
+            skip_log,
+            graph,
+            provenance,
+            deterministic,
+            progress,
+            must_exist_in,
+            title_filter,
+            stop_condition,
+            matched_text: Arc::new(Mutex::new(None)),
+            max_paths: max_paths.max(1),
+            found_paths: Arc::new(Mutex::new(vec!())),
+            path_depth: AtomicUsize::new(usize::MAX),
+            max_frontier,
+            sample: approximate.map(|fraction| FrontierSample::new(fraction, deterministic)),
+            // The size of an article tells about its own links, but not about the articles linking to it
+            link_estimate: (estimate_links && direction == LinkDirection::Outgoing)
+                .then(|| Mutex::new(LinkEstimate::default())),
+            budget,
+            display,
+            cancel,
+            cleanup_grace: cleanup_grace.unwrap_or(DEFAULT_CLEANUP_GRACE),
+            running_workers: AtomicUsize::new(0),
+            queued_batches: AtomicUsize::new(0),
+            blocked_senders: AtomicUsize::new(0),
+            errors,
+            shown_errors: Mutex::new(shown_errors),
+            found_reports,
+            shown_paths: Mutex::new(Some(shown_paths)),
         })
     }
+
+    /// Returns true once the crawl has finished or been cancelled, after which the links still being analyzed lead
+    /// nowhere useful
+    fn is_stale(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed) || self.finished.read().map_or(true, |finished| *finished != 0)
+    }
+
+    /// Returns true if reaching the article ends the crawl, being the goal or one of the other goals of the crawl
+    fn is_goal(&self, title: &ArticleTitle) -> bool {
+        title == &self.goal || self.goals.as_ref().is_some_and(|goals| goals.contains(title))
+            || (self.loose_goal_match && (title.without_disambiguator() == self.goal.as_str()
+                                          || title.as_str() == self.goal.without_disambiguator()))
+    }
+
+    /// Sends an error the crawl carries on from into the error channel of the crawl
+    ///
+    /// # Arguments
+    ///
+    /// * 'message' - A String describing the error
+    fn report_error(&self, message: String) {
+        send_error(&self.errors, message);
+    }
+
+    /// Returns an ErrorReporter sending into the error channel of the crawl, for the client and the cache of the crawl
+    fn error_reporter(&self) -> ErrorReporter {
+        ErrorReporter::new(self.errors.clone())
+    }
+
+    /// Returns the progress of the crawl at this moment: the reached articles, the deepest expanded depth, the
+    /// articles waiting to be expanded, the rate and the state of the crawl. Reads only counters kept without locks,
+    /// so it can be called as often as needed, such as on every frame of a GUI
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        self.live.snapshot()
+    }
+
+    /// Returns the live progress of the crawl, for taking snapshots of the progress with without holding on to the
+    /// crawler, which the crawl needs to own again once it ends
+    pub fn live_progress(&self) -> Arc<LiveProgress> {
+        Arc::clone(&self.live)
+    }
+
+    /// Returns the current state of the channel the workers send their batches through, telling whether the crawl
+    /// is waiting on the API or on the workers
+    pub fn back_pressure(&self) -> BackPressure {
+        BackPressure {
+            queued_batches: self.queued_batches.load(Ordering::SeqCst),
+            blocked_senders: self.blocked_senders.load(Ordering::SeqCst),
+            running_workers: self.running_workers.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// A function for sending an error into the error channel of a crawl. The error is dropped if nobody observes the
+/// channel anymore
+/// 
+/// # Arguments
+/// 
+/// * 'errors' - A reference to the sender of the error channel
+/// * 'message' - A String describing the error
+fn send_error(errors: &mpsc::Sender<CrawlError>, message: String) {
+    let _ = errors.send(CrawlError { message });
+}
+
+/// The error channel of a crawl as handed to the parts of the program the crawl goes through, such as the client and
+/// the cache, so that the errors they carry on from end up in the same channel as the errors of the crawl itself.
+/// Without a channel, such as outside of crawls, the errors are printed
+#[derive(Clone, Default)]
+pub struct ErrorReporter {
+    errors: Option<mpsc::Sender<CrawlError>>,
+}
+
+impl ErrorReporter {
+    /// A constructor for ErrorReporter sending the errors into the given channel
+    pub fn new(errors: mpsc::Sender<CrawlError>) -> ErrorReporter {
+        ErrorReporter { errors: Some(errors) }
+    }
+
+    /// Sends an error into the channel, or prints it if there's no channel
+    ///
+    /// # Arguments
+    ///
+    /// * 'message' - A String describing the error
+    pub fn report(&self, message: String) {
+        match &self.errors {
+            Some(errors) => send_error(errors, message),
+            None => eprintln!("{}", message),
+        }
+    }
+}
+
+/// An async function that runs a crawl and collects its statistics, whether a path was found or not
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - An arc that houses the Crawler struct used for data transfer between main thread and workers
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * CrawlResult - The CrawlOutcome telling how the crawl ended, along with the statistics of the crawl
+pub async fn start(crawler_arc: Arc<Crawler>, client: &WikiClient) -> CrawlResult {
+    let reporter = crawler_arc.error_reporter();
+    let client = &client.for_crawl(reporter.clone());
+    let stats_arc = Arc::clone(&crawler_arc.stats);
+    let paths_arc = Arc::clone(&crawler_arc.found_paths);
+    let direction = crawler_arc.direction;
+    let matched_arc = Arc::clone(&crawler_arc.matched_text);
+    let errors = crawler_arc.errors.clone();
+    let progress = crawler_arc.progress.clone();
+    let provenance = crawler_arc.provenance.clone();
+    let live = crawler_arc.live_progress();
+    if let Some(progress) = &progress {
+        // A crawl following backlinks starts from its goal, so the articles are turned back around for the frame
+        let (origin, goal) = match crawler_arc.direction {
+            LinkDirection::Outgoing => (&crawler_arc.origin.name, &crawler_arc.goal),
+            LinkDirection::Incoming => (&crawler_arc.goal, &crawler_arc.origin.name),
+        };
+        progress.write_frame(&json!({
+            "origin": origin.as_str(),
+            "goal": goal.as_str(),
+            "direction": crawler_arc.direction.as_str(),
+        }), &reporter);
+    }
+    let warnings_before = client.warning_counts();
+    let started = Instant::now();
+    crawler_arc.live.start();
+    let outcome = run_crawl(crawler_arc, client).await;
+    client.abort_prefetches();
+    // The state set when the crawl stopped can still change, such as when a worker finds the goal while the others
+    // are closing after a cancellation
+    live.finish(outcome.state());
+
+    let mut stats = match stats_arc.lock() {
+        Ok(stats) => stats.clone(),
+        Err(error) => {
+            send_error(&errors, format!("Error acquiring lock for crawl statistics:\n{:?}", error));
+            CrawlStats::default()
+        },
+    };
+    stats.elapsed = started.elapsed();
+    stats.record_warnings(&warnings_before, &client.warning_counts());
+    stats.degraded = client.is_degraded();
+    if let Some(cache) = client.cache() {
+        cache.save_usage();
+    }
+
+    // The shortest of the collected paths is the outcome, so the rest are the other paths
+    let other_paths = match &outcome {
+        CrawlOutcome::Found(path) => sorted_paths(&paths_arc, direction, &errors).into_iter()
+            .filter(|other| other != path)
+            .collect(),
+        _ => vec!(),
+    };
+    let matched_text = matched_arc.lock().map_or(None, |matched| matched.clone());
+    if let Some(progress) = &progress {
+        let path: Option<Vec<&str>> = match &outcome {
+            CrawlOutcome::Found(path) => Some(path.iter().map(ArticleTitle::as_str).collect()),
+            _ => None,
+        };
+        progress.write_frame(&json!({
+            "outcome": outcome.as_str(),
+            "path": path,
+            "api_calls": stats.total_api_calls(),
+            "elapsed_seconds": (stats.elapsed.as_secs_f64() * 10.0).round() / 10.0,
+        }), &reporter);
+    }
+    CrawlResult { outcome, stats, other_paths, matched_text, provenance }
 }
 
 /// An async function that performs the actual crawl by spawning an UI thread and worker threads when necessary.
@@ -94,13 +715,16 @@ impl Crawler {
 /// # Arguments
 /// 
 /// * 'crawler_arc' - An arc that houses the Crawler struct used for data transfer between main thread and workers
-/// * 'api' - A reference to a logged in mediawiki::api::Api instance
+/// * 'client' - A reference to a WikiClient with a logged in api session
 /// 
 /// # Returns
 /// 
-/// * Option<Vec<String>> - An option that holds a Vec of Strings of the shortest path, or None if error occurred
-pub async fn start(crawler_arc: Arc<Crawler>, api: &mediawiki::api::Api) -> Option<Vec<String>> {
+/// * CrawlOutcome - How the crawl ended
+async fn run_crawl(crawler_arc: Arc<Crawler>, client: &WikiClient) -> CrawlOutcome {
     let crawler_display_clone = Arc::clone(&crawler_arc);
+    let display_client = client.clone();
+    let started = Instant::now();
+    let requests_at_start = client.request_count();
 
     // When this buffer fills child threads are forced to wait to dispatch their data. This means the program 
     // will be bottlenecked by the API rate limit after that, slowing it down significantly. Considering this
@@ -108,157 +732,846 @@ pub async fn start(crawler_arc: Arc<Crawler>, api: &mediawiki::api::Api) -> Opti
     let (sender, reciever) = mpsc::sync_channel::<BatchData>(500000);
 
     let display_processing_handle = thread::spawn(move || {
-        display_process(&crawler_display_clone);
+        display_process(&crawler_display_clone, &display_client);
     });
 
     // Init the process by fetching the first bunch of links and initing the sender
-    match sender.clone().send(BatchData::new(None, vec!(crawler_arc.origin.name.clone()))) {
+    match send_batch(&crawler_arc, &sender, BatchData::new(None, vec!(crawler_arc.origin.name.clone()))) {
         Ok(_) => (),
         Err(error) => {
-            eprintln!("An error occurred while initing the first crawl link fetch batch:\n{:?}", error);
-            return None;
+            set_finished(&crawler_arc, FAILED);
+            return CrawlOutcome::Failed(format!(
+                "An error occurred while initing the first crawl link fetch batch: {:?}", error).into());
         },
     };
-    drop(api);
 
     let mut thread_handlers = vec!();
 
     // Ensure something wonky doesn't happen to the channel by forcing quit after 5 failed recieves
     let mut channel_failsafe: u8 = 0;
 
+    // The deepest expanded article, preferring the one with the most links, for answering when the budget runs out
+    let mut deepest: Option<(Arc<ArticleNode>, usize)> = None;
+
+    // The amount of batches whose links have been fetched, for telling in which batch each article was found
+    let mut expanded_batches: usize = 0;
+
+    // The limits of the budget already warned about, so that each is warned about only once
+    let mut warned_limits: Vec<BudgetLimit> = vec!();
+
     loop {
         let loop_crawler = crawler_arc.clone();
-        let finish_read = match loop_crawler.finished.read() {
-            Ok(read_lock) => read_lock,
+        let is_finished = match loop_crawler.finished.read() {
+            Ok(read_lock) => *read_lock != 0,
             Err(error) => {
-                eprintln!("Error fetching read lock for finish shate check in main thread:\n{:?}", error);
+                crawler_arc.report_error(format!(
+                    "Error fetching read lock for finish shate check in main thread:\n{:?}", error));
                 continue;
             },
         };
-            if *finish_read != 0 {
-                break;
+        if is_finished {
+            break;
+        }
+
+        // The fatal error is passed on as a cancellation, which the workers and the display stop on
+        if client.fatal_error().is_some() {
+            crawler_arc.cancel.store(true, Ordering::Relaxed);
+            set_finished(&crawler_arc, ABORTED);
+            break;
+        }
+        if crawler_arc.cancel.load(Ordering::Relaxed) {
+            set_finished(&crawler_arc, CANCELLED);
+            break;
+        }
+        let (elapsed, api_calls) = (started.elapsed(), client.request_count() - requests_at_start);
+        if crawler_arc.budget.is_exhausted(elapsed, api_calls) {
+            set_finished(&crawler_arc, BUDGET_EXHAUSTED);
+            break;
+        }
+        for limit in crawler_arc.budget.nearly_exhausted(elapsed, api_calls) {
+            if !warned_limits.contains(&limit) {
+                warned_limits.push(limit);
+                warn_budget(&crawler_arc, limit, elapsed, api_calls);
             }
-            drop(finish_read);
+        }
 
-        let to_analyse = match reciever.recv() {
+        let to_analyse = match reciever.recv_timeout(BUDGET_CHECK_INTERVAL) {
             Ok(batch) => {
                 channel_failsafe = 0;
+                crawler_arc.queued_batches.fetch_sub(1, Ordering::SeqCst);
                 batch
             },
+            // With no batches waiting and no workers left to send more, every reachable article has been expanded
+            Err(mpsc::RecvTimeoutError::Timeout) if crawler_arc.running_workers.load(Ordering::SeqCst) == 0 => {
+                match reciever.try_recv() {
+                    Ok(batch) => {
+                        crawler_arc.queued_batches.fetch_sub(1, Ordering::SeqCst);
+                        batch
+                    },
+                    Err(_) => {
+                        let found_paths = crawler_arc.path_depth.load(Ordering::SeqCst) != usize::MAX;
+                        set_finished(&crawler_arc, if found_paths { FOUND } else { NO_PATH });
+                        break;
+                    },
+                }
+            },
+            Err(mpsc::RecvTimeoutError::Timeout) => continue,
             Err(error) => {
-                eprintln!("Error recieving next batch from channel:");
-                eprintln!("{:?}\nDropping batch and fetching next one...", error);
+                crawler_arc.report_error(format!(
+                    "Error recieving next batch from channel:\n{:?}\nDropping batch and fetching next one...", error));
                 channel_failsafe += 1;
                 if channel_failsafe >= 5 {
-                    return None;
+                    set_finished(&crawler_arc, FAILED);
+                    return CrawlOutcome::Failed(format!("Receiving batches kept failing: {:?}", error).into());
                 }
                 continue;
             }
         };
 
-        if to_analyse.new_batch.len() == 0 {
+        if to_analyse.new_batch.is_empty() {
+            continue;
+        }
+
+        // Batches deeper than the shortest found path can't lead to another shortest path, and neither can the ones
+        // as deep as it once all of the paths have been collected. They are skipped instead of ending the crawl, as a
+        // worker can send one before the shallower batches arrive, which can still lead to a shorter path. The crawl
+        // ends once those have been expanded too and the channel runs dry
+        let depth = to_analyse.parent.as_ref().map_or(0, |parent| parent.depth + 1);
+        let path_depth = crawler_arc.path_depth.load(Ordering::SeqCst);
+        if depth > path_depth || (depth == path_depth && has_all_paths(&crawler_arc)) {
             continue;
         }
 
-        let new_batches = match wiki_api::get_links(&to_analyse.new_batch, api).await {
+        if let Some(parent) = &to_analyse.parent {
+            let links = to_analyse.new_batch.len();
+            let deeper = deepest.as_ref().is_none_or(|(node, node_links)| {
+                (parent.depth, links) > (node.depth, *node_links)
+            });
+            if deeper {
+                deepest = Some((Arc::clone(parent), links));
+            }
+        }
+
+        // The start of the crawl is always expanded, so that the links straight to the goal are never missed
+        let new_batch = match (&crawler_arc.sample, &to_analyse.parent) {
+            (Some(sample), Some(_)) => {
+                let (drawn, sampled_out) = sample.draw(to_analyse.new_batch);
+                match crawler_arc.stats.lock() {
+                    Ok(mut stats) => stats.record_sampled_out(depth, sampled_out),
+                    Err(error) => crawler_arc.report_error(format!(
+                        "Error acquiring lock for crawl statistics:\n{:?}", error)),
+                }
+                if drawn.is_empty() {
+                    continue;
+                }
+                drawn
+            },
+            _ => to_analyse.new_batch,
+        };
+
+        let requests_before = client.request_count();
+        let to_expand = match (&crawler_arc.must_exist_in, &to_analyse.parent) {
+            (Some(language), Some(parent)) => filter_language(&new_batch, language, parent, &crawler_arc,
+                                                              client).await,
+            _ => new_batch,
+        };
+        if let Some(condition) = &crawler_arc.stop_condition {
+            let matched = tokio::select! {
+                matched = condition.first_match(&to_expand, client) => matched,
+                _ = stopped(&crawler_arc, started, requests_at_start, client) => continue,
+            };
+            match matched {
+                Ok(Some((article, text))) => {
+                    stop_at(&crawler_arc, Arc::new(ArticleNode::new(article, to_analyse.parent.clone())), text);
+                    break;
+                },
+                Ok(None) => (),
+                Err(error) => crawler_arc.report_error(format!(
+                    "Error occurred while fetching the texts of articles: {}", error)),
+            }
+        }
+        let bytes = match &crawler_arc.link_estimate {
+            Some(estimate) => tokio::select! {
+                bytes = estimate_links(&to_expand, estimate, &crawler_arc, client) => bytes,
+                _ = stopped(&crawler_arc, started, requests_at_start, client) => continue,
+            },
+            None => None,
+        };
+        // The requests are held for as long as the wiki can't be reached, so the fetch is given up on only if the crawl
+        // is stopped while waiting for it
+        let fetched = tokio::select! {
+            fetched = fetch_links(&to_expand, &crawler_arc, client) => fetched,
+            _ = stopped(&crawler_arc, started, requests_at_start, client) => continue,
+        };
+        let new_batches = match fetched {
             Ok(map) => map,
+            // A fatal error ends the crawl on the next round, where it's reported once as the outcome
+            Err(_) if client.fatal_error().is_some() => continue,
             Err(error) => {
-                eprintln!("Error occurred while fetching links: {:?}", error);
+                crawler_arc.report_error(format!("Error occurred while fetching links: {}", error));
                 continue;
             }
         };
+        let new_batches = fold_variants(&to_expand, new_batches, &to_analyse.parent, &crawler_arc, client);
+        expanded_batches += 1;
+        let discovery = Discovery { batch: expanded_batches, api_call: client.request_count() - requests_at_start };
+        if let (Some(estimate), Some(bytes)) = (&crawler_arc.link_estimate, bytes) {
+            if let Ok(mut estimate) = estimate.lock() {
+                estimate.calibrate(bytes, new_batches.values().map(Vec::len).sum());
+            }
+        }
+        crawler_arc.live.record_expanded(depth, to_expand.len());
+        match crawler_arc.stats.lock() {
+            Ok(mut stats) => stats.record_expanded(depth, to_expand.len(),
+                                                   client.request_count() - requests_before),
+            Err(error) => crawler_arc.report_error(format!(
+                "Error acquiring lock for crawl statistics:\n{:?}", error)),
+        }
         let parent = to_analyse.parent.clone();
         let sender_clone = sender.clone();
+        let batch_size = client.batch_size();
+
+        // Worker threads finish in whatever order they happen to, so deterministic crawls process the batches
+        // right away instead. The channel then hands them out in the order they were sent
+        if crawler_arc.deterministic {
+            threaded_processing(loop_crawler, new_batches, parent, sender_clone, batch_size, discovery).await;
+            continue;
+        }
 
+        // The count is lowered only after the worker has sent all of its batches, so that a crawl with no running
+        // workers and no waiting batches has nothing left to expand
+        loop_crawler.running_workers.fetch_add(1, Ordering::SeqCst);
         let new_handle = tokio::spawn(async move {
-            threaded_processing(loop_crawler, new_batches, parent, sender_clone).await;
+            let worker_crawler = Arc::clone(&loop_crawler);
+            threaded_processing(loop_crawler, new_batches, parent, sender_clone, batch_size, discovery).await;
+            worker_crawler.running_workers.fetch_sub(1, Ordering::SeqCst);
         });
 
         thread_handlers.push(new_handle);
     }
 
+    // Closing the channel right away discards the batches still waiting in it and makes the workers still sending
+    // stop instead of filling it up for nothing
+    drop(reciever);
+
+    // The nodes have to be let go of before the path is unwrapped out of them
+    let mut partial = match deepest {
+        Some((node, _)) => path_to(&node),
+        None => vec!(crawler_arc.origin.name.clone()),
+    };
+
     match display_processing_handle.join() {
         Ok(_) => (),
         Err(error) => {
-            eprintln!("Fatal error while closing display thread:\n{:?}", error);
-            return None;
+            return CrawlOutcome::Failed(format!("Fatal error while closing display thread: {:?}", error).into());
         },
     }
 
-    drop(reciever);
-
-    for handler in thread_handlers {
-        match handler.await {
+    // The display has already announced the outcome and the finish state makes the workers stale, so the ones still
+    // going through their links are given a grace period to notice it and aborted after it, instead of the answer
+    // waiting for every worker spawned during the crawl
+    let deadline = tokio::time::Instant::now() + crawler_arc.cleanup_grace;
+    for mut handler in thread_handlers {
+        let joined = match tokio::time::timeout_at(deadline, &mut handler).await {
+            Ok(joined) => joined,
+            Err(_) => {
+                handler.abort();
+                handler.await
+            },
+        };
+        match joined {
             Ok(_) => (),
+            // An aborted worker has let go of the crawler once its handle resolves
+            Err(error) if error.is_cancelled() => (),
             Err(error) => {
-                eprintln!("Fatal error while waiting for all threads to close during crawl cleanup:{:?}", error);
-                return None;
+                return CrawlOutcome::Failed(format!(
+                    "Fatal error while waiting for all threads to close during crawl cleanup: {:?}", error).into());
             },
         };
     }
 
     let crawler_raw = match Arc::try_unwrap(crawler_arc) {
         Ok(crawler) => crawler,
-        Err(_) => {
-            eprintln!("Fatal error while attempting to unwrap crawler during crawl cleanup.");
-            return None
+        Err(_) => return CrawlOutcome::Failed("Fatal error while attempting to unwrap crawler during crawl cleanup."
+                                              .into()),
+    };
+    let direction = crawler_raw.direction;
+    if direction == LinkDirection::Incoming {
+        partial.reverse();
+    }
+
+    // A worker can still find the goal while the others are closing after the crawl was stopped
+    let state = crawler_raw.finished.read().map_or(FAILED, |state| *state);
+    let found = crawler_raw.final_node.read().is_ok_and(|node| node.is_some());
+    if !found {
+        if let Some(path) = sorted_paths(&crawler_raw.found_paths, direction, &crawler_raw.errors).into_iter().next() {
+            return CrawlOutcome::Found(path);
+        }
+        return match state {
+            BUDGET_EXHAUSTED => CrawlOutcome::BudgetExhausted(partial),
+            CANCELLED => CrawlOutcome::Cancelled(partial),
+            ABORTED => CrawlOutcome::Aborted(client.fatal_error().unwrap_or_default(), partial),
+            NO_PATH => CrawlOutcome::NoPath,
+            _ => CrawlOutcome::Failed("The crawl ended without a finish state".into()),
+        };
+    }
+    match detravel_path(crawler_raw).await {
+        Some(mut path) => {
+            if direction == LinkDirection::Incoming {
+                path.reverse();
+            }
+            CrawlOutcome::Found(path)
+        },
+        None => CrawlOutcome::Failed("Something went wrong while traversing the path backwards to complete an answer"
+                                     .into()),
+    }
+}
+
+/// An async function for estimating the amount of links a batch has out of the size of the wikitext of its articles,
+/// which is shown on the status line while the links are fetched
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles to expand
+/// * 'estimate' - A reference to the mutex holding the LinkEstimate of the crawl
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'client' - A reference to the WikiClient of the crawl
+/// 
+/// # Returns
+/// 
+/// * Option<u64> - The size of the wikitext of the batch in bytes, or None if it couldn't be fetched
+async fn estimate_links(articles: &[ArticleTitle], estimate: &Mutex<LinkEstimate>, crawler_arc: &Arc<Crawler>,
+                        client: &WikiClient) -> Option<u64> {
+    let bytes: u64 = match wiki_api::page_lengths(articles, client).await {
+        Ok(lengths) => lengths.values().sum(),
+        Err(error) => {
+            crawler_arc.report_error(format!("Error occurred while fetching the sizes of articles: {}", error));
+            return None;
         },
     };
-    detravel_path(crawler_raw).await
+    let mut estimate = estimate.lock().ok()?;
+    estimate.expected = Some((bytes as f64 / estimate.bytes_per_link()).round() as usize);
+    Some(bytes)
 }
 
-/// A function that handles the crawl UI component (keeping the user entertained with pretty blinking text)
+/// An async function for fetching the links of the articles to expand in the direction of the crawl. Undirected
+/// crawls fetch the backlinks of the articles too, and follow both as if they were links
 /// 
 /// # Arguments
 /// 
-/// * 'crawler_arc' - A Crawler struct wrapped in an arc for data transfer between threads
-pub fn display_process(crawler_arc: &Arc<Crawler>) {
-    print!("\n");
-    loop {
+/// * 'articles' - A slice of the titles of the articles to expand
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'client' - A reference to the WikiClient of the crawl
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, Vec<ArticleTitle>>, Box<dyn Error>> - The articles paired up with their links
+async fn fetch_links(articles: &[ArticleTitle], crawler_arc: &Arc<Crawler>, client: &WikiClient)
+    -> Result<HashMap<ArticleTitle, Vec<ArticleTitle>>, Box<dyn Error>> {
+    let mut links = wiki_api::get_links(articles, crawler_arc.direction, client).await?;
+    if crawler_arc.undirected {
+        // Articles linking both ways are dropped as repeats once the links are claimed
+        for (article, backlinks) in wiki_api::get_links(articles, LinkDirection::Incoming, client).await? {
+            links.entry(article).or_default().extend(backlinks);
+        }
+    }
+    Ok(links)
+}
 
-        let total_analysed: usize;
-        {         
-            let read_set = match crawler_arc.visited.read() {
-                Ok(read_lock) => read_lock,
-                Err(error) => {
-                    eprintln!("Error acquiring read lock for visited set size:\n{:?}", error);
-                    thread::sleep(Duration::from_millis(1000));
-                    continue;
-                },
-            };
-            total_analysed = (*read_set).len();
-            drop(read_set);
+/// A function for folding the articles whose titles the wiki converted from another variant of its language into the
+/// articles they were converted into, so that a crawl on a wiki with variants doesn't reach the same article once
+/// per script. An article already reached under its own title is left out, as it's expanded under that title, and
+/// a title converted into the goal means that the parent of the batch links to the goal
+/// 
+/// # Arguments
+/// 
+/// * 'requested' - A slice of the titles the links were fetched for
+/// * 'batches' - A HashMap of the fetched titles paired up with their links
+/// * 'parent' - A reference to an option containing the node of the article the titles were linked from
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'client' - A reference to the WikiClient the links were fetched with
+/// 
+/// # Returns
+/// 
+/// * HashMap<ArticleTitle, Vec<ArticleTitle>> - The articles under the titles they were converted into, paired up
+///   with their links
+fn fold_variants(requested: &[ArticleTitle], mut batches: HashMap<ArticleTitle, Vec<ArticleTitle>>,
+                 parent: &Option<Arc<ArticleNode>>, crawler_arc: &Arc<Crawler>, client: &WikiClient)
+    -> HashMap<ArticleTitle, Vec<ArticleTitle>> {
+    if !client.converts_titles() {
+        return batches;
+    }
+    for title in requested.iter() {
+        let converted = match client.converted_title(title) {
+            Some(converted) if converted != *title => converted,
+            _ => continue,
+        };
+        let links = batches.remove(title);
+        if crawler_arc.is_goal(&converted) && crawler_arc.stop_condition.is_none() {
+            if let Some(parent) = parent {
+                reach_converted_goal(crawler_arc, parent, &converted);
+            }
+            continue;
+        }
+        if requested.contains(&converted) {
+            continue;
         }
+        let unvisited = match crawler_arc.visited.write() {
+            Ok(mut visited) => {
+                let inserted = visited.insert(&converted);
+                crawler_arc.live.set_visited(visited.len());
+                inserted
+            },
+            Err(error) => {
+                crawler_arc.report_error(format!("Error acquiring write lock for visited articles:\n{:?}", error));
+                false
+            },
+        };
+        if let (true, Some(links)) = (unvisited, links) {
+            batches.insert(converted, links);
+        }
+    }
+    batches
+}
+
+/// A function for ending a crawl at the goal linked from the given article under another variant of its title, like
+/// a worker does when it finds a link to the goal
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'article' - A reference to the node of the article linking to the goal
+/// * 'goal' - A reference to the title of the reached goal
+fn reach_converted_goal(crawler_arc: &Arc<Crawler>, article: &Arc<ArticleNode>, goal: &ArticleTitle) {
+    if crawler_arc.max_paths > 1 {
+        record_path(crawler_arc, &article.name, goal, &article.parent);
+        return;
+    }
+    match crawler_arc.final_node.write() {
+        Ok(mut node) if node.is_none() => *node = Some(ArticleNode::new(goal.clone(), Some(Arc::clone(article)))),
+        Ok(_) => (),
+        Err(error) => crawler_arc.report_error(format!("Error acquiring write lock for final node:\n{:?}", error)),
+    }
+    set_finished(crawler_arc, FOUND);
+}
+
+/// A function for sending a batch to the main thread, keeping count of the batches waiting in the channel and of
+/// the senders blocked on a full channel
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'sender' - A reference to a SyncSender of the channel to the main thread
+/// * 'batch' - The BatchData to send
+/// 
+/// # Returns
+/// 
+/// * Result<(), mpsc::SendError<BatchData>> - Result containing the batch if the channel was closed
+fn send_batch(crawler_arc: &Arc<Crawler>, sender: &mpsc::SyncSender<BatchData>, batch: BatchData)
+    -> Result<(), mpsc::SendError<BatchData>> {
+    // Counted before sending, so that the main thread never takes a batch off the count before it was added
+    crawler_arc.queued_batches.fetch_add(1, Ordering::SeqCst);
+    let result = match sender.try_send(batch) {
+        Ok(()) => Ok(()),
+        Err(mpsc::TrySendError::Full(batch)) => {
+            crawler_arc.blocked_senders.fetch_add(1, Ordering::SeqCst);
+            let result = sender.send(batch);
+            crawler_arc.blocked_senders.fetch_sub(1, Ordering::SeqCst);
+            result
+        },
+        Err(mpsc::TrySendError::Disconnected(batch)) => Err(mpsc::SendError(batch)),
+    };
+    if result.is_err() {
+        crawler_arc.queued_batches.fetch_sub(1, Ordering::SeqCst);
+    }
+    result
+}
 
-        print!("\rCrawling, analyzed {} articles.  ", total_analysed);
-        let _ = stdout().flush();
+/// A function for recording a path found by a crawl collecting several shortest paths and showing it right away
+/// above the status line, so that long runs show their results as they come. The batches aren't expanded strictly
+/// one depth after another, so a shorter path can still turn up after longer ones, in which case the longer ones are
+/// dropped. Paths longer than the shortest found one are left out
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'article' - A reference to the title of the article linking to the goal
+/// * 'goal' - A reference to the title of the reached goal, the goal of the crawl or one of its other goals
+/// * 'parent' - A reference to an option containing the node of the article the linking article was found from
+fn record_path(crawler_arc: &Arc<Crawler>, article: &ArticleTitle, goal: &ArticleTitle,
+               parent: &Option<Arc<ArticleNode>>) {
+    let node = Arc::new(ArticleNode::new(article.clone(), parent.clone()));
+    let mut paths = match crawler_arc.found_paths.lock() {
+        Ok(paths) => paths,
+        Err(error) => {
+            crawler_arc.report_error(format!("Error acquiring lock for found paths:\n{:?}", error));
+            return;
+        },
+    };
+    // The depth is changed only while holding the lock, so that it always matches the collected paths
+    let path_depth = crawler_arc.path_depth.fetch_min(node.depth, Ordering::SeqCst);
+    if node.depth > path_depth {
+        return;
+    }
+    let longer = paths.len();
+    if node.depth < path_depth {
+        paths.retain(|found| found.node.depth <= node.depth);
+    }
+    let longer = longer - paths.len();
+    if paths.len() >= crawler_arc.max_paths {
+        return;
+    }
+    let found = FoundPath { node, goal: Some(goal.clone()) };
+    let path = found.titles(crawler_arc.direction);
+    paths.push(found);
+    let count = paths.len();
+    drop(paths);
 
-        thread::sleep(Duration::from_millis(600));
+    let titles: Vec<&str> = path.iter().map(ArticleTitle::as_str).collect();
+    let mut report = format!("Found path {} ({} links): {}", count, path.len() - 1, titles.join(" -> "));
+    if longer > 0 {
+        report = format!("Found a shorter path, leaving out the {} longer ones found before it\n{}", longer, report);
+    }
+    // Nobody shows the paths anymore once the display of the crawl has ended, as the paths are in the result then
+    let _ = crawler_arc.found_reports.send(report);
+    if let Some(progress) = &crawler_arc.progress {
+        progress.write_frame(&json!({ "path": titles, "length": path.len() - 1 }), &crawler_arc.error_reporter());
+    }
+}
+
+/// Returns true if a crawl collecting several shortest paths has all of the paths it collects at the depth of the
+/// shortest found one, after which only the articles shallower than that can lead to another path
+///
+/// # Arguments
+///
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+fn has_all_paths(crawler_arc: &Arc<Crawler>) -> bool {
+    crawler_arc.found_paths.lock().is_ok_and(|paths| paths.len() >= crawler_arc.max_paths)
+}
 
-        print!("\rCrawling, analyzed {} articles.. ", total_analysed);
-        let _ = stdout().flush();
+/// A function for listing the paths collected by a crawl, shortest first
+/// 
+/// # Arguments
+/// 
+/// * 'paths' - A reference to the mutex holding the collected paths
+/// * 'direction' - The LinkDirection of the crawl
+/// * 'errors' - A reference to the sender of the error channel of the crawl
+/// 
+/// # Returns
+/// 
+/// * Vec<Vec<ArticleTitle>> - The paths in the order they were found in, the shorter ones first
+fn sorted_paths(paths: &Mutex<Vec<FoundPath>>, direction: LinkDirection, errors: &mpsc::Sender<CrawlError>)
+    -> Vec<Vec<ArticleTitle>> {
+    let paths = match paths.lock() {
+        Ok(paths) => paths,
+        Err(error) => {
+            send_error(errors, format!("Error acquiring lock for found paths:\n{:?}", error));
+            return vec!();
+        },
+    };
+    let mut sorted: Vec<&FoundPath> = paths.iter().collect();
+    sorted.sort_by_key(|found| found.len());
+    sorted.into_iter().map(|found| found.titles(direction)).collect()
+}
 
-        thread::sleep(Duration::from_millis(600));
+/// A function for warning that the crawl has used most of a limit of its budget, so that the user can decide to
+/// adjust the crawl or cancel it before it gives up. The warning is shown like the errors of the crawl, and written
+/// as a frame of its own if progress frames were asked for
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'limit' - The nearly used up BudgetLimit
+/// * 'elapsed' - The Duration the crawl has been running for
+/// * 'api_calls' - The amount of API calls the crawl has made
+fn warn_budget(crawler_arc: &Arc<Crawler>, limit: BudgetLimit, elapsed: Duration, api_calls: usize) {
+    let depth = crawler_arc.live.snapshot().depth;
+    crawler_arc.report_error(format!("Warning: {:.0}% of the {} consumed at depth {} ({})", SOFT_LIMIT * 100.0,
+                                     limit.describe(), depth,
+                                     crawler_arc.budget.describe_use(limit, elapsed, api_calls)));
+    if let Some(progress) = &crawler_arc.progress {
+        progress.write_frame(&json!({
+            "budget_warning": limit.as_str(),
+            "depth": depth,
+            "api_calls": api_calls,
+            "elapsed_seconds": (elapsed.as_secs_f64() * 10.0).round() / 10.0,
+        }), &crawler_arc.error_reporter());
+    }
+}
 
-        print!("\rCrawling, analyzed {} articles...", total_analysed);
-        let _ = stdout().flush();
+/// A function for ending a crawl at an article meeting its stop condition
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'node' - The ArticleNode of the article
+/// * 'text' - A String with the text the article met the condition with
+fn stop_at(crawler_arc: &Arc<Crawler>, node: Arc<ArticleNode>, text: String) {
+    match (crawler_arc.found_paths.lock(), crawler_arc.matched_text.lock()) {
+        (Ok(mut paths), Ok(mut matched)) => {
+            paths.push(FoundPath { node, goal: None });
+            *matched = Some(text);
+        },
+        _ => crawler_arc.report_error("Error acquiring lock for the article meeting the stop condition".to_string()),
+    }
+    set_finished(crawler_arc, FOUND);
+}
 
-        thread::sleep(Duration::from_millis(800));
+/// A function for setting the finish state of a crawl, unless the crawl has already finished
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'state' - The finish state to set
+fn set_finished(crawler_arc: &Arc<Crawler>, state: u8) {
+    match crawler_arc.finished.write() {
+        Ok(mut finished) if *finished == 0 => {
+            *finished = state;
+            crawler_arc.live.finish(match state {
+                FOUND => CrawlState::Found,
+                BUDGET_EXHAUSTED => CrawlState::BudgetExhausted,
+                CANCELLED => CrawlState::Cancelled,
+                NO_PATH => CrawlState::NoPath,
+                ABORTED => CrawlState::Aborted,
+                _ => CrawlState::Failed,
+            });
+        },
+        Ok(_) => (),
+        Err(error) => crawler_arc.report_error(format!("Error acquiring write lock for finish state:\n{:?}", error)),
+    }
+}
 
-        let finish_read = match crawler_arc.finished.read() {
-            Ok(read_lock) => read_lock,
+/// A function for listing the titles on the way from the start of the crawl to a node, without taking the nodes
+/// apart like detravel_path does
+/// 
+/// # Arguments
+/// 
+/// * 'node' - A reference to the node
+/// 
+/// # Returns
+/// 
+/// * Vec<ArticleTitle> - The titles from the start of the crawl to the node
+fn path_to(node: &ArticleNode) -> Vec<ArticleTitle> {
+    let mut path = vec!(node.name.clone());
+    let mut current = node;
+    while let Some(parent) = &current.parent {
+        path.push(parent.name.clone());
+        current = parent;
+    }
+    path.reverse();
+    path
+}
+
+/// A function that handles the crawl UI component (keeping the user entertained with pretty blinking text). The
+/// status line is drawn by a single loop ticking once per frame of the display config. The paths found by a crawl
+/// collecting several of them are printed as they come, as are the errors of the crawl unless they are observed
+/// elsewhere, with the status line drawn again below them
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A Crawler struct wrapped in an arc for data transfer between threads
+/// * 'client' - A reference to the WikiClient of the crawl, for showing when it's holding its requests
+pub fn display_process(crawler_arc: &Arc<Crawler>, client: &WikiClient) {
+    let display = crawler_arc.display;
+    let started = Instant::now();
+    let mut last_report: Option<Instant> = None;
+    let mut line_width = 0;
+    let mut total_analysed = 0;
+    let mut status = String::new();
+    let shown_errors = crawler_arc.shown_errors.lock().ok().and_then(|mut shown_errors| shown_errors.take());
+    let shown_paths = crawler_arc.shown_paths.lock().ok().and_then(|mut shown_paths| shown_paths.take());
+    println!();
+    for tick in 0.. {
+        let mut redraw = display.redraws_on(tick);
+        if let Some(shown_paths) = &shown_paths {
+            for report in shown_paths.try_iter() {
+                println!("\r{:<width$}\r{}", "", report, width = line_width);
+                redraw = true;
+            }
+        }
+        if let Some(shown_errors) = &shown_errors {
+            for error in shown_errors.try_iter() {
+                print!("\r{:<width$}\r", "", width = line_width);
+                let _ = stdout().flush();
+                eprintln!("{}", error);
+                redraw = true;
+            }
+        }
+        if display.updates_on(tick) {
+            total_analysed = match crawler_arc.visited.read() {
+                Ok(read_lock) => read_lock.len(),
+                Err(error) => {
+                    crawler_arc.report_error(format!(
+                        "Error acquiring read lock for visited set size:\n{:?}", error));
+                    total_analysed
+                },
+            };
+            let expected = crawler_arc.link_estimate.as_ref()
+                .and_then(|estimate| estimate.lock().ok().and_then(|estimate| estimate.expected))
+                .map_or(String::new(), |links| format!(", expecting ~{} links from the next batch", links));
+            status = format!("Crawling, analyzed {} articles{}{}", total_analysed, expected,
+                             queue_status(&crawler_arc.back_pressure(), client.cooldown()));
+        }
+        // The progress frames keep their own pace, so that programs reading them aren't affected by the display
+        if last_report.is_none_or(|reported| reported.elapsed() >= PROGRESS_INTERVAL) {
+            report_progress(crawler_arc, total_analysed, started.elapsed(), false);
+            last_report = Some(Instant::now());
+        }
+
+        if redraw {
+            // A line wrapping over the edge of the terminal couldn't be drawn over, so the status is cut to fit with
+            // the animation. The status can get shorter as the queue drains, so the line is padded over the longest
+            // one printed that still fits
+            let frame = display.frame(tick);
+            let terminal_width = display::terminal_width().saturating_sub(1);
+            let fitting = terminal_width.saturating_sub(display::text_width(frame));
+            let line = format!("{}{}", display::truncate_to_width(&status, fitting), frame);
+            line_width = line_width.max(display::text_width(&line)).min(terminal_width);
+            print!("\r{}", display::pad_to_width(&line, line_width));
+            let _ = stdout().flush();
+        }
+        thread::sleep(display.frame_interval);
+
+        let finished = match crawler_arc.finished.read() {
+            Ok(read_lock) => *read_lock,
             Err(error) => {
-                eprintln!("Error acquiring read lock to check display thread health:\n{:?}", error);
+                crawler_arc.report_error(format!(
+                    "Error acquiring read lock to check display thread health:\n{:?}", error));
                 continue;
             },
         };
-        if *finish_read != 0 {
-            println!("\nArticle found! Tidying up some threads. This may take some time...");
-            break;
+        let message = match finished {
+            0 => continue,
+            FOUND => "Article found! Tidying up some threads. This may take some time...",
+            BUDGET_EXHAUSTED => "The crawl budget ran out before the goal was found. Tidying up some threads...",
+            CANCELLED => "The crawl was cancelled. Tidying up some threads...",
+            ABORTED => "The API can't be used anymore, aborting the crawl. Tidying up some threads...",
+            NO_PATH => "Every reachable article has been analyzed without finding the goal.",
+            _ => "The crawl failed.",
+        };
+        report_progress(crawler_arc, total_analysed, started.elapsed(), finished == FOUND);
+        if let Some(shown_paths) = &shown_paths {
+            for report in shown_paths.try_iter() {
+                println!("\r{:<width$}\r{}", "", report, width = line_width);
+            }
+        }
+        println!("\n{}", message);
+        if let Some(shown_errors) = &shown_errors {
+            for error in shown_errors.try_iter() {
+                eprintln!("{}", error);
+            }
         }
+        break;
     }
 }
 
+/// An async function for leaving the articles without a version in the given language out of a batch about to be
+/// expanded, so that every article on the found path can be read in the language. The dropped articles are
+/// recorded into the skip log
+/// 
+/// # Arguments
+/// 
+/// * 'batch' - A slice of the titles of the articles in the batch
+/// * 'language' - A string slice with the code of the language
+/// * 'parent' - A reference to the node of the article the batch was linked from
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Vec<ArticleTitle> - The articles of the batch that have a version in the language. If the lookup fails, the
+///   batch is dropped as a whole
+async fn filter_language(batch: &[ArticleTitle], language: &str, parent: &ArticleNode, crawler_arc: &Arc<Crawler>,
+                         client: &WikiClient) -> Vec<ArticleTitle> {
+    let kept = match wiki_api::with_langlink(batch, language, client).await {
+        Ok(kept) => kept,
+        Err(error) => {
+            crawler_arc.report_error(format!("Error occurred while fetching language links: {}", error));
+            vec!()
+        },
+    };
+    if let Some(skip_log) = &crawler_arc.skip_log {
+        for title in batch.iter().filter(|title| !kept.contains(title)) {
+            skip_log.record(title, SkipReason::MissingLanguage, &parent.name, &crawler_arc.error_reporter());
+        }
+    }
+    kept
+}
+
+/// An async function that resolves once the crawl has been cancelled, has run out of its budget or the API has
+/// answered with a fatal error, for giving up on requests that are held while the wiki can't be reached
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'started' - The Instant the crawl started at
+/// * 'requests_at_start' - The request count of the client when the crawl started
+/// * 'client' - A reference to the WikiClient of the crawl
+async fn stopped(crawler_arc: &Arc<Crawler>, started: Instant, requests_at_start: usize, client: &WikiClient) {
+    while !crawler_arc.cancel.load(Ordering::Relaxed) && client.fatal_error().is_none()
+        && !crawler_arc.budget.is_exhausted(started.elapsed(), client.request_count() - requests_at_start) {
+        tokio::time::sleep(BUDGET_CHECK_INTERVAL).await;
+    }
+}
+
+/// A function for describing the state of the batch channel on the crawl status line, so that it can be seen
+/// whether the crawl is waiting on the API or on the workers
+/// 
+/// # Arguments
+/// 
+/// * 'back_pressure' - A reference to the current BackPressure of the crawl
+/// * 'cooldown' - An option containing the Cooldown the requests are held for, if the server throttled them or
+///   couldn't be reached
+/// 
+/// # Returns
+/// 
+/// * String - The description in brackets, such as [12 batches queued, API-bound]
+fn queue_status(back_pressure: &BackPressure, cooldown: Option<Cooldown>) -> String {
+    let mut status = format!(" [{} batches queued", back_pressure.queued_batches);
+    if back_pressure.blocked_senders > 0 {
+        status.push_str(&format!(", {} workers blocked", back_pressure.blocked_senders));
+    }
+    match back_pressure.bound() {
+        Some("api") => status.push_str(", API-bound"),
+        Some(_) => status.push_str(", processing-bound"),
+        None => (),
+    }
+    if let Some(cooldown) = cooldown {
+        // Rounded up, so that the countdown doesn't show 0 seconds while still waiting
+        let seconds = cooldown.until.saturating_duration_since(Instant::now()).as_secs() + 1;
+        match cooldown.reason {
+            CooldownReason::Requested => status.push_str(&format!(", cooling down {}s (server requested)", seconds)),
+            CooldownReason::Throttled => status.push_str(&format!(", cooling down {}s (server throttled)", seconds)),
+            CooldownReason::Offline => status.push_str(&format!(", offline, reconnecting in {}s", seconds)),
+        }
+    }
+    status.push(']');
+    status
+}
+
+/// A function for writing a machine readable progress frame of the crawl, if progress frames were asked for
+/// 
+/// # Arguments
+/// 
+/// * 'crawler_arc' - A reference to an arc housing the Crawler instance of the crawl
+/// * 'visited' - The amount of articles the crawl has reached
+/// * 'elapsed' - The Duration the crawl has been running for
+/// * 'finished' - Whether the crawl has found the goal
+fn report_progress(crawler_arc: &Arc<Crawler>, visited: usize, elapsed: Duration, finished: bool) {
+    let progress = match &crawler_arc.progress {
+        Some(progress) => progress,
+        None => return,
+    };
+    let frame = match crawler_arc.stats.lock() {
+        Ok(stats) => progress::progress_frame(&stats, visited, elapsed, finished, &crawler_arc.back_pressure()),
+        Err(error) => {
+            crawler_arc.report_error(format!("Error acquiring lock for crawl statistics:\n{:?}", error));
+            return;
+        },
+    };
+    progress.write_frame(&frame, &crawler_arc.error_reporter());
+}
+
 /// A function that takes a raw crawler (unwrapped from an arc at the end of a crawl) and travels backwards from
 /// it's final node to construct a path from the origin to the goal
 /// 
@@ -268,23 +1581,25 @@ pub fn display_process(crawler_arc: &Arc<Crawler>) {
 /// 
 /// # Returns
 /// 
-/// * Option<Vec<String>> - An option that holds the final path as a Vec of Strings representing article names
-pub async fn detravel_path(crawler: Crawler) -> Option<Vec<String>> {
+/// * Option<Vec<ArticleTitle>> - An option that holds the final path as a Vec of article titles
+pub async fn detravel_path(crawler: Crawler) -> Option<Vec<ArticleTitle>> {
+    let errors = crawler.errors.clone();
     let mut _traverse_node = match crawler.final_node.into_inner() {
         Ok(option) => match option {
             Some(node) => node,
             None => {
-                eprintln!("Error while fetching goal node: no node");
+                send_error(&errors, "Error while fetching goal node: no node".to_string());
                 return None
             },
         },
         Err(error) => {
-            eprintln!("Error while fetching goal node: failure in getting lock inner object:\n{:?}", error);
+            send_error(&errors, format!(
+                "Error while fetching goal node: failure in getting lock inner object:\n{:?}", error));
             return None
         },
     };
 
-    let mut constructed: Vec<String> = vec!();
+    let mut constructed: Vec<ArticleTitle> = vec!();
 
     loop {
         constructed.push(_traverse_node.name.clone());
@@ -292,8 +1607,8 @@ pub async fn detravel_path(crawler: Crawler) -> Option<Vec<String>> {
             Some(arc) => match Arc::try_unwrap(arc) {
                 Ok(node) => node,
                 Err(error_node) => {
-                    eprintln!("Error while traveling path backwards: Unable to unwrap node {:?}:",
-                                error_node.name);
+                    send_error(&errors, format!("Error while traveling path backwards: Unable to unwrap node {:?}:",
+                                                error_node.name));
                     return None
                 },
             },
@@ -311,24 +1626,45 @@ pub async fn detravel_path(crawler: Crawler) -> Option<Vec<String>> {
 /// # Arguments
 /// 
 /// * 'crawler_arc' - A Crawler struct wrapped in an Arc for inter-thread communication
-/// * 'new_batches' - A HashMap of String - Vec<String> pairs that houses articles and their respective links
+/// * 'new_batches' - A HashMap of ArticleTitle - Vec<ArticleTitle> pairs that houses articles and their respective
+///   links
 /// * 'parent' - The ArticleNode that should be the parent of the ArticleNodes spawned from the data in new_batch
 /// * 'sender' - A SyncSender for sending BatchData instances back to main thread
-async fn threaded_processing(crawler_arc: Arc<Crawler>, new_batches: HashMap<String, Vec<String>>,
-                                parent: Option<Arc<ArticleNode>>, sender: mpsc::SyncSender<BatchData>) -> () { 
+/// * 'batch_size' - The amount of titles to put in a single batch of links
+/// * 'discovery' - The Discovery of the batch the links were fetched in
+async fn threaded_processing(crawler_arc: Arc<Crawler>, new_batches: HashMap<ArticleTitle, Vec<ArticleTitle>>,
+                                parent: Option<Arc<ArticleNode>>, sender: mpsc::SyncSender<BatchData>,
+                                batch_size: usize, discovery: Discovery) { 
+    // The workers spawned before the crawl ended would otherwise go through all of their links for nothing
+    if crawler_arc.is_stale() {
+        return;
+    }
 
-    for (article, links) in new_batches.iter() {
-        
+    // The iteration order of a HashMap changes from run to run, so deterministic crawls sort the articles first
+    let mut articles: Vec<(&ArticleTitle, &Vec<ArticleTitle>)> = new_batches.iter().collect();
+    if crawler_arc.deterministic {
+        articles.sort_by_key(|(article, _)| *article);
+    }
+
+    for &(article, links) in articles.iter() {
         for candidate in links.iter() {
-            if candidate == &crawler_arc.goal {
+            // A link of an article to itself is never a step forward. This also keeps cycle crawls, where the goal
+            // is the origin, from ending on a self-link of the origin
+            if crawler_arc.is_goal(candidate) && candidate != article && crawler_arc.stop_condition.is_none() {
+                if crawler_arc.max_paths > 1 {
+                    record_path(&crawler_arc, article, candidate, &parent);
+                    break;
+                }
+
                 const MAX_TRIES: u8 = 10;
                 let mut tries = 0;
                 let mut finished = loop {
                     match crawler_arc.finished.write() {
                         Ok(write_lock) => break write_lock,
                         Err(error) => {
-                            eprintln!("Error acquiring write lock for finish state (try {} out of {}):\n{:?}",
-                                        tries, MAX_TRIES, error);
+                            crawler_arc.report_error(format!(
+                                "Error acquiring write lock for finish state (try {} out of {}):\n{:?}",
+                                tries, MAX_TRIES, error));
                         }
                     }
                     if tries >= MAX_TRIES {
@@ -337,7 +1673,8 @@ async fn threaded_processing(crawler_arc: Arc<Crawler>, new_batches: HashMap<Str
                     }
                     tries += 1;
                 };
-                *finished = 1;
+                *finished = FOUND;
+                crawler_arc.live.finish(CrawlState::Found);
                 drop(finished);
                 tries = 0;
 
@@ -345,8 +1682,9 @@ async fn threaded_processing(crawler_arc: Arc<Crawler>, new_batches: HashMap<Str
                     match crawler_arc.final_node.write() {
                         Ok(write_lock) => break write_lock,
                         Err(error) => {
-                            eprintln!("Fatal error acquiring write lock for final node (try {} out of {}):\n{:?}",
-                                        tries, MAX_TRIES, error);
+                            crawler_arc.report_error(format!(
+                                "Fatal error acquiring write lock for final node (try {} out of {}):\n{:?}",
+                                tries, MAX_TRIES, error));
                         }
                     }
                     if tries >= MAX_TRIES {
@@ -355,19 +1693,31 @@ async fn threaded_processing(crawler_arc: Arc<Crawler>, new_batches: HashMap<Str
                     }
                     tries += 1;
                 };
-                let temp_node = Arc::new(ArticleNode::new(article, parent.clone()));
-                *node_lock = Some(ArticleNode::new(candidate, Some(temp_node.clone())));
+                let temp_node = Arc::new(ArticleNode::new(article.clone(), parent.clone()));
+                if let Some(provenance) = &crawler_arc.provenance {
+                    provenance.record(Some(article), std::slice::from_ref(candidate), temp_node.depth + 1,
+                                      discovery);
+                }
+                *node_lock = Some(ArticleNode::new(candidate.clone(), Some(temp_node.clone())));
                 return;
             }
-
         }
+    }
 
-        let article_node = ArticleNode::new(article, parent.clone());
-        let article_node = Arc::new(article_node);
+    let article_nodes: Vec<Arc<ArticleNode>> = articles.iter()
+        .map(|(article, _)| Arc::new(ArticleNode::new((*article).clone(), parent.clone())))
+        .collect();
+    let new_links = claim_links(&articles, &article_nodes, &crawler_arc, discovery);
 
-        for link_batch in paginate_links(links, &crawler_arc) {
-            let article_node_clone = Arc::clone(&article_node);
-            match sender.send(BatchData::new(Some(article_node_clone), link_batch)) {
+    for (article_node, links) in article_nodes.iter().zip(new_links) {
+        if crawler_arc.is_stale() {
+            return;
+        }
+        // Yielding between the articles lets a worker still running when the crawl is cleaned up be aborted
+        tokio::task::yield_now().await;
+        for link_batch in links.chunks(batch_size.max(1)) {
+            let article_node_clone = Arc::clone(article_node);
+            match send_batch(&crawler_arc, &sender, BatchData::new(Some(article_node_clone), link_batch.to_vec())) {
                 Ok(_) => (),
 
                 // Note that finding the correct result will close the reciever. This WILL cause an error here
@@ -375,49 +1725,47 @@ async fn threaded_processing(crawler_arc: Arc<Crawler>, new_batches: HashMap<Str
                     let finished = match crawler_arc.finished.read() {
                         Ok(read_lock) => read_lock,
                         Err(error) => {
-                            eprintln!("Error acquiring read lock to check finished state:\n{:?}", error);
+                            crawler_arc.report_error(format!(
+                                "Error acquiring read lock to check finished state:\n{:?}", error));
                             return;
                         },
                     };
-                    if *finished == 1 {
+                    if *finished != 0 {
                         return;
                     }
-                    eprintln!("Error while sending data back to main thread:\n{:?}", outer_error);
+                    crawler_arc.report_error(format!(
+                        "Error while sending data back to main thread:\n{:?}", outer_error));
                 },
             }
         }
     };
 }
 
-/// A function that takes a list of all links in an article and divides them into pieces small enough for the
-/// wikipedia API to handle
+/// A function for claiming the not yet visited links of the analyzed articles into the visited set. Links repeated
+/// within the batch and links dropped by the title filter are left out before the visited set is locked, and the
+/// rest are inserted in one short critical section, so that the workers don't keep each other waiting on articles
+/// with a lot of links. A link found from several articles is kept by the first one of them. If the depth of the
+/// links would grow past the frontier limit of the crawl, only the most linked of them are claimed
 /// 
 /// # Arguments
 /// 
-/// * 'links' - A reference to a Vec holding Strings representing all the links found from one article
+/// * 'articles' - A slice of the analyzed articles paired up with all of their links
+/// * 'article_nodes' - A slice of the nodes of the articles, in the same order
 /// * 'crawler_arc' - A reference to an arc housing a Crawler instance for inter-thread communication
+/// * 'discovery' - The Discovery of the batch the links were fetched in
 /// 
 /// # Returns
 /// 
-/// * Vec<Vec<String>> - A Vec holding Vecs of Strings representing the broken down link bunches
-fn paginate_links(links: &Vec<String>, crawler_arc: &Arc<Crawler>) -> Vec<Vec<String>> {
-    // The request data without the title string for the en.wikipedia api is 105 chars
-    // I am leaving 20 chars extra space to ensure smooth operation in all conditions.
-    // Most of the time the 50 article cap is met before the 2000 char cap, but one
-    // cannot be too careful (2000 / 50 = 40, after all, a valid article name length)
-    const MAX_URI: usize = 2000;
-    const QUERY_LENGTH: usize = 105;
-    const GRACE_SPACE: usize = 20;
-    const MAX_LINKS: usize = 50;
-
-    let max_chars: usize = MAX_URI - QUERY_LENGTH - GRACE_SPACE;
-    let mut available_chars: usize = max_chars;
-    let mut current_vector: usize = 0;
-    let mut link_count: usize = 0;
-    let mut link_batches: Vec<Vec<String>> = vec!();
-
-    let new_vector: Vec<String> = vec!();
-    link_batches.push(new_vector);
+/// * Vec<Vec<ArticleTitle>> - The newly visited links of each article, in the same order as the articles
+fn claim_links(articles: &[(&ArticleTitle, &Vec<ArticleTitle>)], article_nodes: &[Arc<ArticleNode>],
+               crawler_arc: &Arc<Crawler>, discovery: Discovery) -> Vec<Vec<ArticleTitle>> {
+    let filtered = |link: &ArticleTitle| crawler_arc.title_filter.as_ref().and_then(|filter| filter.skip_reason(link));
+    let mut seen: HashSet<&ArticleTitle> = HashSet::new();
+    let candidates: Vec<Vec<&ArticleTitle>> = articles.iter()
+        .map(|(_, links)| links.iter().filter(|link| seen.insert(*link) && filtered(link).is_none()).collect())
+        .collect();
+    // Every analyzed article was found from the same parent, so their links are all on the same depth
+    let depth = article_nodes.first().map_or(0, |article_node| article_node.depth + 1);
 
     let mut tries: u8 = 0;
     const MAX_TRIES: u8 = 10;
@@ -425,8 +1773,9 @@ fn paginate_links(links: &Vec<String>, crawler_arc: &Arc<Crawler>) -> Vec<Vec<St
         match crawler_arc.visited.write() {
             Ok(write_lock) => break write_lock,
             Err(error) => {
-                eprintln!("Error acquiring write lock for visite articles(try {} out of {}):\n{:?}",
-                            tries, MAX_TRIES, error);
+                crawler_arc.report_error(format!(
+                    "Error acquiring write lock for visite articles(try {} out of {}):\n{:?}",
+                    tries, MAX_TRIES, error));
             }
         }
 
@@ -437,29 +1786,88 @@ fn paginate_links(links: &Vec<String>, crawler_arc: &Arc<Crawler>) -> Vec<Vec<St
 
         tries += 1;
     };
-    for link in links {
-
-        if (*visited_lock).contains(link) {
-            continue;
+    // The articles already queued at the depth are counted and the new ones recorded while the visited set is
+    // locked, so that the workers can't fill the same room twice
+    let (kept, left_out) = match crawler_arc.max_frontier {
+        Some(max_frontier) => {
+            let queued = crawler_arc.stats.lock()
+                .map_or(0, |stats| stats.depths.get(depth).map_or(0, |depth_stats| depth_stats.discovered));
+            most_linked(articles, &candidates, &visited_lock, max_frontier.saturating_sub(queued))
+        },
+        None => (None, HashSet::new()),
+    };
+    let new_links: Vec<Vec<ArticleTitle>> = candidates.into_iter()
+        .map(|links| links.into_iter()
+            .filter(|link| kept.as_ref().is_none_or(|kept| kept.contains(link)) && (*visited_lock).insert(link))
+            .cloned()
+            .collect())
+        .collect();
+    crawler_arc.live.set_visited(visited_lock.len());
+    if !article_nodes.is_empty() {
+        crawler_arc.live.record_discovered(new_links.iter().map(Vec::len).sum());
+        match crawler_arc.stats.lock() {
+            Ok(mut stats) => {
+                stats.record_discovered(depth, new_links.iter().map(Vec::len).sum());
+                stats.record_left_out(depth, left_out.len());
+            },
+            Err(error) => crawler_arc.report_error(format!(
+                "Error acquiring lock for crawl statistics:\n{:?}", error)),
         }
+    }
+    drop(visited_lock);
 
-        (*visited_lock).insert(link.to_string());
-
-        link_count += 1;
-        if (available_chars < link.len() + 1) | (link_count > MAX_LINKS) {
-            available_chars = max_chars;
-            link_count = 1;
-            current_vector += 1;
-
-            let new_vector: Vec<String> = vec!();
-            link_batches.push(new_vector);
-        } else {
-            available_chars -= 1;
+    if let Some(graph) = &crawler_arc.graph {
+        let errors = crawler_arc.error_reporter();
+        for (article_node, new) in article_nodes.iter().zip(new_links.iter()) {
+            graph.record_edges(&article_node.name, new, &errors);
+        }
+    }
+    if let Some(provenance) = &crawler_arc.provenance {
+        for (article_node, new) in article_nodes.iter().zip(new_links.iter()) {
+            provenance.record(Some(&article_node.name), new, depth, discovery);
+        }
+    }
+    if let Some(skip_log) = &crawler_arc.skip_log {
+        let errors = crawler_arc.error_reporter();
+        for (((_, links), article_node), new) in articles.iter().zip(article_nodes).zip(new_links.iter()) {
+            let new: HashSet<&ArticleTitle> = new.iter().collect();
+            for link in links.iter().filter(|link| !new.contains(link)) {
+                let reason = match filtered(link) {
+                    Some(reason) => reason,
+                    None if left_out.contains(link) => SkipReason::FrontierFull,
+                    None => SkipReason::AlreadyVisited,
+                };
+                skip_log.record(link, reason, &article_node.name, &errors);
+            }
         }
+    }
+    new_links
+}
 
-        available_chars -= link.len();
-        link_batches[current_vector].push(link.to_string())
+/// A function for choosing which of the new links fit into a depth with room for only some of them. A link found
+/// from several of the analyzed articles is likely a hub leading to many articles in a few steps, so the links are
+/// preferred by the amount of the analyzed articles linking to them. Links found equally often keep their order
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the analyzed articles paired up with all of their links
+/// * 'candidates' - A slice of the links that may be claimed for each article
+/// * 'visited' - A reference to the visited set of the crawl
+/// * 'room' - The amount of articles the depth still has room for
+/// 
+/// # Returns
+/// 
+/// * (Option<HashSet<&ArticleTitle>>, HashSet<&ArticleTitle>) - The links to claim and the new links left out
+fn most_linked<'a>(articles: &[(&ArticleTitle, &Vec<ArticleTitle>)], candidates: &[Vec<&'a ArticleTitle>],
+                   visited: &VisitedSet, room: usize)
+    -> (Option<HashSet<&'a ArticleTitle>>, HashSet<&'a ArticleTitle>) {
+    let mut linked_from: HashMap<&ArticleTitle, usize> = HashMap::new();
+    for link in articles.iter().flat_map(|(_, links)| links.iter()) {
+        *linked_from.entry(link).or_insert(0) += 1;
     }
-    drop(visited_lock);
-    link_batches
+    let mut new: Vec<&ArticleTitle> = candidates.iter().flatten().copied().filter(|link| !visited.contains(link))
+        .collect();
+    new.sort_by_key(|link| Reverse(linked_from.get(link).copied().unwrap_or(0)));
+    let left_out = new.split_off(room.min(new.len()));
+    (Some(new.into_iter().collect()), left_out.into_iter().collect())
 }
\ No newline at end of file
@@ -0,0 +1,7 @@
+/// The link graph of the small wiki the demo crawls, served by the fixture server, see fixture_server::FixtureGraph
+pub const GRAPH: &str = include_str!("demo_graph.json");
+/// The article names the demo crawl is started with. Both are redirects, so that the validation has something to show
+pub const ORIGIN: &str = "Coffee bean";
+pub const GOAL: &str = "Luna";
+/// The amount of links on the shortest path between the articles, which the path found by the demo is checked against
+pub const SHORTEST_PATH_LINKS: usize = 4;
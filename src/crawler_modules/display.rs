@@ -0,0 +1,217 @@
+use std::env;
+use std::time::Duration;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// The defaults redraw the animation twice a second and read the state of the crawl every two seconds
+const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_UPDATE_EVERY: u32 = 4;
+
+// The width assumed for the terminal when it can't be read, such as when the output is redirected into a file
+const DEFAULT_TERMINAL_WIDTH: usize = 80;
+// The indentation of the lines a path continues on when it doesn't fit on a single line
+const WRAP_INDENT: &str = "    ";
+
+const DOTS_FRAMES: [&str; 3] = [".  ", ".. ", "..."];
+const SPINNER_FRAMES: [&str; 4] = [" |", " /", " -", " \\"];
+const BAR_FRAMES: [&str; 8] = [" [=   ]", " [==  ]", " [=== ]", " [====]", " [ ===]", " [  ==]", " [   =]",
+                               " [    ]"];
+
+/// An enum representing the animations the crawl status line can be drawn with
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AnimationStyle {
+    /// Dots appearing one at a time after the status
+    Dots,
+    /// A spinning line after the status
+    Spinner,
+    /// A bar filling and emptying after the status
+    Bar,
+    /// No animation, the status line is only redrawn when it's updated
+    None,
+}
+
+impl AnimationStyle {
+    /// Parses the style out of the value given to --animation
+    ///
+    /// # Arguments
+    ///
+    /// * 'name' - A string slice with the name of the style
+    ///
+    /// # Returns
+    ///
+    /// * Option<AnimationStyle> - The style, or None if the name wasn't a style
+    pub fn parse(name: &str) -> Option<AnimationStyle> {
+        match name {
+            "dots" => Some(AnimationStyle::Dots),
+            "spinner" => Some(AnimationStyle::Spinner),
+            "bar" => Some(AnimationStyle::Bar),
+            "none" => Some(AnimationStyle::None),
+            _ => None,
+        }
+    }
+
+    /// Returns the frames of the animation, drawn after the status one at a time
+    pub fn frames(&self) -> &'static [&'static str] {
+        match self {
+            AnimationStyle::Dots => &DOTS_FRAMES,
+            AnimationStyle::Spinner => &SPINNER_FRAMES,
+            AnimationStyle::Bar => &BAR_FRAMES,
+            AnimationStyle::None => &[""],
+        }
+    }
+}
+
+/// A struct holding how the status line of a crawl is drawn. The status line is redrawn once every frame interval,
+/// and the state of the crawl shown on it is read again every update_every frames
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DisplayConfig {
+    /// The time between two frames of the animation
+    pub frame_interval: Duration,
+    /// The amount of frames after which the status is updated, at least 1
+    pub update_every: u32,
+    /// The animation drawn after the status
+    pub style: AnimationStyle,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> DisplayConfig {
+        DisplayConfig {
+            frame_interval: DEFAULT_FRAME_INTERVAL,
+            update_every: DEFAULT_UPDATE_EVERY,
+            style: AnimationStyle::Dots,
+        }
+    }
+}
+
+impl DisplayConfig {
+    /// Returns true if the status should be updated on the given frame
+    ///
+    /// # Arguments
+    ///
+    /// * 'tick' - The number of the frame, counting from 0
+    pub fn updates_on(&self, tick: u64) -> bool {
+        tick % u64::from(self.update_every.max(1)) == 0
+    }
+
+    /// Returns the frame of the animation drawn on the given tick
+    ///
+    /// # Arguments
+    ///
+    /// * 'tick' - The number of the frame, counting from 0
+    pub fn frame(&self, tick: u64) -> &'static str {
+        let frames = self.style.frames();
+        frames[(tick % frames.len() as u64) as usize]
+    }
+
+    /// Returns true if the status line needs to be drawn on the given tick, which is every tick unless there is no
+    /// animation, in which case the line only changes when the status is updated
+    ///
+    /// # Arguments
+    ///
+    /// * 'tick' - The number of the frame, counting from 0
+    pub fn redraws_on(&self, tick: u64) -> bool {
+        self.style != AnimationStyle::None || self.updates_on(tick)
+    }
+}
+
+/// Returns the width of the terminal in columns, as given by the COLUMNS environment variable or read from the
+/// terminal, or 80 if neither tells it
+pub fn terminal_width() -> usize {
+    let columns = env::var("COLUMNS").ok().and_then(|columns| columns.parse::<usize>().ok());
+    if let Some(columns) = columns.filter(|columns| *columns > 0) {
+        return columns;
+    }
+    #[cfg(unix)]
+    if let Ok(size) = rustix::termios::tcgetwinsize(std::io::stdout()) {
+        if size.ws_col > 0 {
+            return usize::from(size.ws_col);
+        }
+    }
+    DEFAULT_TERMINAL_WIDTH
+}
+
+/// Returns the amount of columns the text takes on the terminal. Most CJK characters take two columns and combining
+/// characters none, so this differs from the amount of characters
+pub fn text_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// A function for cutting a text to fit in the given amount of columns, ending it with an ellipsis if it was cut.
+/// Combining characters stay with the character they combine with
+///
+/// # Arguments
+///
+/// * 'text' - A string slice with the text
+/// * 'width' - The amount of columns the text may take
+///
+/// # Returns
+///
+/// * String - The text, cut if it was wider than the columns
+pub fn truncate_to_width(text: &str, width: usize) -> String {
+    if text_width(text) <= width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for character in text.chars() {
+        let character_width = character.width().unwrap_or(0);
+        // One column is left for the ellipsis
+        if used + character_width >= width {
+            break;
+        }
+        truncated.push(character);
+        used += character_width;
+    }
+    if width > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// A function for padding a text with spaces to take the given amount of columns, so that a line drawn over a wider
+/// one with a carriage return covers it completely
+///
+/// # Arguments
+///
+/// * 'text' - A string slice with the text
+/// * 'width' - The amount of columns the padded text takes, unless the text is wider
+///
+/// # Returns
+///
+/// * String - The padded text
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(text_width(text))))
+}
+
+/// A function for breaking the parts of a path onto lines of at most the given width. The parts are joined by
+/// spaces, and the lines after the first one are indented. A part wider than the lines gets a line of its own
+///
+/// # Arguments
+///
+/// * 'parts' - A slice of the parts of the path, such as the origin followed by an arrow and an article for each link
+/// * 'width' - The amount of columns a line may take
+///
+/// # Returns
+///
+/// * String - The wrapped path, with the lines separated by newlines
+pub fn wrap_parts(parts: &[String], width: usize) -> String {
+    let mut wrapped = String::new();
+    let mut line_width = 0;
+    for part in parts.iter() {
+        let part_width = text_width(part);
+        if line_width == 0 {
+            wrapped.push_str(part);
+            line_width = part_width;
+        } else if line_width + 1 + part_width <= width {
+            wrapped.push(' ');
+            wrapped.push_str(part);
+            line_width += 1 + part_width;
+        } else {
+            wrapped.push('\n');
+            wrapped.push_str(WRAP_INDENT);
+            wrapped.push_str(part);
+            line_width = WRAP_INDENT.len() + part_width;
+        }
+    }
+    wrapped
+}
@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+
+use super::graph_export::csv_field;
+use super::wiki_api::TitleStatus;
+
+const CSV_HEADER: &str = "title,status,target\n";
+const STATUS_NAMES: [&str; 5] = ["exists", "redirect", "broken_redirect", "missing", "invalid"];
+
+/// A function for reading the titles to check from a file, one title per line. Empty lines and lines starting with #
+/// are skipped. The titles are kept as written, so that the report shows them the way they were given
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the file
+///
+/// # Returns
+///
+/// * Result<Vec<String>, Box<dyn Error>> - The titles in the file, or an error if it couldn't be read or didn't
+///   have any titles
+pub fn read_titles(file: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let titles: Vec<String> = fs::read_to_string(file)?.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+    if titles.is_empty() {
+        return Err(Box::new(io::Error::new(io::ErrorKind::InvalidData,
+                                           format!("'{}' doesn't list any titles", file))));
+    }
+    Ok(titles)
+}
+
+/// A function for writing the statuses of the titles as CSV, with a row of the title as given, its status and the
+/// article it leads to for each title. The target is left empty for the titles not leading to an article
+///
+/// # Arguments
+///
+/// * 'titles' - A slice of the titles as given
+/// * 'statuses' - A slice of the TitleStatus of each title, in the same order
+///
+/// # Returns
+///
+/// * String - The CSV, starting with a header row
+pub fn to_csv(titles: &[String], statuses: &[TitleStatus]) -> String {
+    let mut csv = CSV_HEADER.to_string();
+    for (title, status) in titles.iter().zip(statuses.iter()) {
+        let target = status.target().map_or(String::new(), |target| csv_field(target.as_str()));
+        csv.push_str(&format!("{},{},{}\n", csv_field(title), status.as_str(), target));
+    }
+    csv
+}
+
+/// Returns a summary of the statuses by the names they are written with, such as Checked 120 titles: 100 exists,
+/// 15 redirect, ...
+pub fn summary(statuses: &[TitleStatus]) -> String {
+    let counts: Vec<String> = STATUS_NAMES.iter()
+        .map(|name| format!("{} {}", statuses.iter().filter(|status| status.as_str() == *name).count(), name))
+        .collect();
+    format!("Checked {} titles: {}", statuses.len(), counts.join(", "))
+}
@@ -0,0 +1,864 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use serde_json::{self, json};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+
+use super::demo;
+
+// The wiki id reported by the fixture server if the fixture file doesn't give one
+const DEFAULT_WIKI_ID: &str = "fixture";
+// The limits of en.wikipedia for accounts without the apihighlimits right
+const DEFAULT_LINK_LIMIT: usize = 500;
+const MAX_TITLES: usize = 50;
+const HIGH_MAX_TITLES: usize = 500;
+// The size of wikitext reported for each link of an article, close to that of the articles of en.wikipedia
+const FIXTURE_BYTES_PER_LINK: usize = 90;
+
+/// A link graph answering the Action API queries of the crawler in place of a real wiki. Read from a JSON file such
+/// as {"articles": {"A": ["B", "C"], "B": ["C"]}, "redirects": {"Alias": "A"}, "link_limit": 2}, where the
+/// redirects and the link limit are optional. The introductions of the articles can be given under "texts", which
+/// makes the server answer prop=extracts, and {"every": 3, "retry_after": 2} under "throttle" makes the server
+/// answer every third query of links with 429 Too Many Requests and a Retry-After header of two seconds. Likewise
+/// {"after": 5, "seconds": 10} under "outage" makes the server stop listening for ten seconds after answering the
+/// fifth query of links, as if the network was lost, and {"after": 5} under "gone" makes the server answer every
+/// request after the fifth query of links with 410 Gone, as if the API had been removed. {"after": 5, "code":
+/// "ratelimited"} under "error" makes the server answer the queries of links after the fifth one with that API
+/// error instead. Titles are case sensitive if "case" is "case-sensitive". The
+/// articles in categories can be given under "categories", such as {"Category:Letters": ["A", "B"]}. If
+/// "restricted" is true, the server refuses to list links through the Action API, and only serves the links as the
+/// HTML of the articles through the REST API. The daily views of the articles can be given under "views", such as
+/// {"A": 120}, which makes the server answer list=mostviewed. The texts of the links in the HTML of the articles are
+/// the linked titles, unless other texts are given under "anchors", such as {"A": {"B": "the war of B"}}. Titles
+/// written in another variant of the language of the wiki can be given under "variants" with the titles they're
+/// converted into, such as {"計算機": "计算机"}, which makes the server convert them when asked to
+pub struct FixtureGraph {
+    wiki_id: String,
+    case: String,
+    link_limit: usize,
+    links: BTreeMap<String, Vec<String>>,
+    backlinks: BTreeMap<String, Vec<String>>,
+    redirects: BTreeMap<String, String>,
+    variants: BTreeMap<String, String>,
+    categories: BTreeMap<String, Vec<String>>,
+    texts: BTreeMap<String, String>,
+    views: BTreeMap<String, u64>,
+    anchors: BTreeMap<String, BTreeMap<String, String>>,
+    restricted: bool,
+    // How often and for how many seconds the queries of links are throttled, if they are
+    throttle: Option<(usize, u64)>,
+    // After which query of links and for how many seconds the server stops listening, if it does
+    outage: Option<(usize, u64)>,
+    outage_starts: Notify,
+    // After which query of links the server answers every request with 410 Gone, if it does
+    gone_after: Option<usize>,
+    // After which query of links and with which error code the server answers the queries of links, if it does
+    error_after: Option<(usize, String)>,
+    link_queries: AtomicUsize,
+}
+
+impl FixtureGraph {
+    /// A function for reading a fixture graph from a file
+    ///
+    /// # Arguments
+    ///
+    /// * 'file' - A string slice with the path of the fixture file
+    ///
+    /// # Returns
+    ///
+    /// * Result<FixtureGraph, Box<dyn Error>> - The graph, or an error if the file couldn't be read or was malformed
+    pub fn load(file: &str) -> Result<FixtureGraph, Box<dyn Error>> {
+        FixtureGraph::parse(file, &fs::read_to_string(file)?)
+    }
+
+    /// A function for reading a fixture graph from the JSON text of a fixture
+    ///
+    /// # Arguments
+    ///
+    /// * 'name' - A string slice naming the fixture in the errors, such as the path of its file
+    /// * 'text' - A string slice with the JSON text of the fixture
+    ///
+    /// # Returns
+    ///
+    /// * Result<FixtureGraph, Box<dyn Error>> - The graph, or an error if the fixture was malformed
+    pub fn parse(name: &str, text: &str) -> Result<FixtureGraph, Box<dyn Error>> {
+        let invalid = |message: &str| -> Box<dyn Error> {
+            Box::new(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid fixture '{}': {}", name, message)))
+        };
+        let fixture: serde_json::Value = serde_json::from_str(text)?;
+
+        let articles = match fixture["articles"].as_object() {
+            Some(articles) => articles,
+            None => return Err(invalid("expected an object of the articles and their links")),
+        };
+        let mut links = BTreeMap::new();
+        let mut backlinks: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (title, targets) in articles.iter() {
+            let targets: Vec<String> = targets.as_array()
+                .and_then(|targets| targets.iter().map(|target| target.as_str().map(String::from)).collect())
+                .ok_or_else(|| invalid(&format!("the links of '{}' aren't a list of titles", title)))?;
+            links.insert(title.clone(), targets);
+        }
+        for (title, targets) in links.iter() {
+            for target in targets.iter() {
+                backlinks.entry(target.clone()).or_default().push(title.clone());
+            }
+        }
+
+        let mut redirects = BTreeMap::new();
+        if let Some(aliases) = fixture["redirects"].as_object() {
+            for (alias, target) in aliases.iter() {
+                let target = target.as_str()
+                    .ok_or_else(|| invalid(&format!("the target of the redirect '{}' isn't a title", alias)))?;
+                redirects.insert(alias.clone(), target.to_string());
+            }
+        }
+
+        let mut variants = BTreeMap::new();
+        if let Some(titles) = fixture["variants"].as_object() {
+            for (variant, title) in titles.iter() {
+                let title = title.as_str()
+                    .ok_or_else(|| invalid(&format!("the conversion of the variant '{}' isn't a title", variant)))?;
+                variants.insert(variant.clone(), title.to_string());
+            }
+        }
+
+        let mut categories = BTreeMap::new();
+        if let Some(listed) = fixture["categories"].as_object() {
+            for (category, members) in listed.iter() {
+                let members: Vec<String> = members.as_array()
+                    .and_then(|members| members.iter().map(|member| member.as_str().map(String::from)).collect())
+                    .ok_or_else(|| invalid(&format!("the members of '{}' aren't a list of titles", category)))?;
+                categories.insert(category.clone(), members);
+            }
+        }
+
+        let mut texts = BTreeMap::new();
+        if let Some(introductions) = fixture["texts"].as_object() {
+            for (title, text) in introductions.iter() {
+                let text = text.as_str()
+                    .ok_or_else(|| invalid(&format!("the text of '{}' isn't a string", title)))?;
+                texts.insert(title.clone(), text.to_string());
+            }
+        }
+
+        let mut views = BTreeMap::new();
+        if let Some(counts) = fixture["views"].as_object() {
+            for (title, count) in counts.iter() {
+                let count = count.as_u64()
+                    .ok_or_else(|| invalid(&format!("the views of '{}' aren't a count", title)))?;
+                views.insert(title.clone(), count);
+            }
+        }
+
+        let mut anchors = BTreeMap::new();
+        if let Some(articles) = fixture["anchors"].as_object() {
+            for (title, texts) in articles.iter() {
+                let texts = texts.as_object()
+                    .and_then(|texts| texts.iter().map(|(link, text)| Some((link.clone(), text.as_str()?.to_string())))
+                        .collect())
+                    .ok_or_else(|| invalid(&format!("the anchors of '{}' aren't texts of links", title)))?;
+                anchors.insert(title.clone(), texts);
+            }
+        }
+
+        let link_limit = match fixture["link_limit"].as_u64() {
+            Some(0) => return Err(invalid("link_limit must be at least 1")),
+            Some(limit) => limit as usize,
+            None => DEFAULT_LINK_LIMIT,
+        };
+        let throttle = match (fixture["throttle"]["every"].as_u64(), fixture["throttle"]["retry_after"].as_u64()) {
+            (Some(0), _) => return Err(invalid("throttle.every must be at least 1")),
+            (Some(every), Some(retry_after)) => Some((every as usize, retry_after)),
+            (None, None) => None,
+            _ => return Err(invalid("throttle needs both every and retry_after")),
+        };
+        let outage = match (fixture["outage"]["after"].as_u64(), fixture["outage"]["seconds"].as_u64()) {
+            (Some(after), Some(seconds)) => Some((after as usize, seconds)),
+            (None, None) => None,
+            _ => return Err(invalid("outage needs both after and seconds")),
+        };
+        let gone_after = match (fixture.get("gone"), fixture["gone"]["after"].as_u64()) {
+            (Some(_), Some(after)) => Some(after as usize),
+            (Some(_), None) => return Err(invalid("gone needs after")),
+            (None, _) => None,
+        };
+        let error_after = match (fixture.get("error"), fixture["error"]["after"].as_u64(),
+                                 fixture["error"]["code"].as_str()) {
+            (Some(_), Some(after), Some(code)) => Some((after as usize, code.to_string())),
+            (Some(_), _, _) => return Err(invalid("error needs both after and code")),
+            (None, _, _) => None,
+        };
+        let restricted = fixture["restricted"].as_bool().unwrap_or(false);
+        let wiki_id = fixture["wikiid"].as_str().unwrap_or(DEFAULT_WIKI_ID).to_string();
+        let case = match fixture["case"].as_str() {
+            Some(case @ ("first-letter" | "case-sensitive")) => case.to_string(),
+            Some(_) => return Err(invalid("case must be first-letter or case-sensitive")),
+            None => "first-letter".to_string(),
+        };
+
+        Ok(FixtureGraph { wiki_id, case, link_limit, links, backlinks, redirects, variants, categories, texts, views,
+                          anchors, restricted, throttle, outage, outage_starts: Notify::new(), gone_after,
+                          error_after, link_queries: AtomicUsize::new(0) })
+    }
+
+    /// A function for answering a single API request
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the decoded parameters of the request
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - The response, an API error if the request isn't one the crawler makes
+    fn respond(&self, params: &HashMap<String, String>) -> serde_json::Value {
+        let param = |name: &str| params.get(name).map(String::as_str);
+        match param("action") {
+            Some("paraminfo") => self.param_info(),
+            Some("opensearch") => self.open_search(param("search").unwrap_or_default(), parse_limit(param("limit"))),
+            Some("query") if param("meta") == Some("siteinfo") => self.site_info(),
+            Some("query") if param("list") == Some("search") => {
+                self.search(param("srsearch").unwrap_or_default(), parse_limit(param("srlimit")))
+            },
+            Some("query") if param("list") == Some("backlinks") => self.list_backlinks(params),
+            Some("query") if param("list") == Some("categorymembers") => self.list_category_members(params),
+            Some("query") if param("list") == Some("mostviewed") => self.list_most_viewed(params),
+            Some("query") if param("titles").is_some() && param("list").is_none() => self.query_titles(params),
+            _ => unsupported(),
+        }
+    }
+
+    /// A function for counting the queries of links, which the throttling and the outage of the server are timed by
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the decoded parameters of the request
+    ///
+    /// # Returns
+    ///
+    /// * Option<usize> - The amount of queries of links so far including this one, or None for other requests
+    fn count_link_query(&self, params: &HashMap<String, String>) -> Option<usize> {
+        if !matches!(params.get("prop").map(String::as_str), Some("links") | Some("linkshere")) {
+            return None;
+        }
+        Some(self.link_queries.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+
+    /// A function for checking whether a query of links should be throttled instead of answered
+    ///
+    /// # Arguments
+    ///
+    /// * 'count' - The amount of queries of links so far including this one
+    ///
+    /// # Returns
+    ///
+    /// * Option<u64> - The seconds to ask the client to wait for, or None if the request should be answered
+    fn throttled(&self, count: usize) -> Option<u64> {
+        let (every, retry_after) = self.throttle?;
+        if count % every == 0 { Some(retry_after) } else { None }
+    }
+
+    /// A function for checking whether the server should answer that the API is gone instead of answering a request
+    ///
+    /// # Arguments
+    ///
+    /// * 'link_query' - An option containing the amount of queries of links so far including this one, or None if
+    ///   the request isn't one
+    ///
+    /// # Returns
+    ///
+    /// * bool - True if the queries of links answered before the request have reached the limit of the fixture
+    fn is_gone(&self, link_query: Option<usize>) -> bool {
+        let answered = link_query.map_or_else(|| self.link_queries.load(Ordering::SeqCst), |count| count - 1);
+        self.gone_after.is_some_and(|after| answered >= after)
+    }
+
+    /// A function for checking whether a query of links should be answered with an API error instead of its links
+    ///
+    /// # Arguments
+    ///
+    /// * 'count' - The amount of queries of links so far including this one
+    ///
+    /// # Returns
+    ///
+    /// * Option<serde_json::Value> - The error response, or None if the request should be answered
+    fn api_error(&self, count: usize) -> Option<serde_json::Value> {
+        let (after, code) = self.error_after.as_ref().filter(|(after, _)| count > *after)?;
+        Some(json!({ "error": { "code": code,
+                                "info": format!("The fixture server answers with this error after {} queries of \
+                                                 links", after) } }))
+    }
+
+    /// Returns the site info read by mediawiki::api::Api when connecting
+    fn site_info(&self) -> serde_json::Value {
+        let mut site_info = json!({
+            "batchcomplete": "",
+            "query": {
+                "general": {
+                    "wikiid": self.wiki_id,
+                    "sitename": "Fixture",
+                    "mainpage": self.links.keys().next(),
+                    "lang": "en",
+                    "case": self.case,
+                    "server": format!("https://{}.invalid", self.wiki_id),
+                    "articlepath": "/wiki/$1",
+                },
+                "namespaces": { "0": { "id": 0, "case": self.case, "*": "" } },
+                "namespacealiases": [],
+                "libraries": [],
+                "extensions": [],
+                "statistics": { "articles": self.links.len() },
+            },
+        });
+        // Like a wiki in Serbian, converting the titles between its two scripts
+        if !self.variants.is_empty() {
+            site_info["query"]["general"]["langconversion"] = json!("");
+            site_info["query"]["general"]["variants"] = json!([
+                { "code": "sr", "name": "sr" },
+                { "code": "sr-ec", "name": "sr-ec" },
+                { "code": "sr-el", "name": "sr-el" },
+            ]);
+        }
+        site_info
+    }
+
+    /// Returns the module descriptions read by capabilities::detect
+    fn param_info(&self) -> serde_json::Value {
+        let limit = json!({ "name": "limit", "type": "limit", "max": self.link_limit, "highmax": self.link_limit });
+        let mut modules = vec!(
+            json!({ "path": "query", "parameters": [
+                { "name": "titles", "limit": MAX_TITLES, "lowlimit": MAX_TITLES, "highlimit": HIGH_MAX_TITLES },
+            ] }),
+            json!({ "path": "query+search", "parameters": [{ "name": "sort", "type": ["relevance"] }] }),
+        );
+        if !self.restricted {
+            modules.push(json!({ "path": "query+links", "parameters": [limit.clone()] }));
+            modules.push(json!({ "path": "query+linkshere", "parameters": [limit] }));
+        }
+        if !self.texts.is_empty() {
+            modules.push(json!({ "path": "query+extracts", "parameters": [] }));
+        }
+        if !self.views.is_empty() {
+            modules.push(json!({ "path": "query+mostviewed", "parameters": [] }));
+        }
+        json!({ "paraminfo": { "modules": modules } })
+    }
+
+    /// A function for answering list=search. Exact title matches come first, then the titles containing the search,
+    /// with matching redirects giving their targets
+    ///
+    /// # Arguments
+    ///
+    /// * 'term' - A string slice with the search
+    /// * 'limit' - The maximum amount of results
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - The response with the results
+    fn search(&self, term: &str, limit: usize) -> serde_json::Value {
+        let term = term.to_lowercase();
+        let titles = self.links.keys().map(|title| (title, None));
+        let redirected = self.redirects.iter()
+            .filter(|(_, target)| self.links.contains_key(*target))
+            .map(|(alias, target)| (target, Some(alias)));
+        let mut matches: Vec<(usize, &String, Option<&String>)> = titles.chain(redirected)
+            .filter_map(|(title, redirect)| {
+                let matched = redirect.unwrap_or(title).to_lowercase();
+                let rank = if matched == term { 0 } else if matched.contains(&term) { 2 } else { return None };
+                Some((rank + redirect.is_some() as usize, title, redirect))
+            })
+            .collect();
+        matches.sort_by_key(|(rank, _, _)| *rank);
+
+        let mut seen = HashSet::new();
+        let results: Vec<serde_json::Value> = matches.into_iter()
+            .filter(|(_, title, _)| seen.insert(*title))
+            .take(limit)
+            .map(|(_, title, redirect)| match redirect {
+                Some(redirect) => json!({ "ns": 0, "title": title, "redirecttitle": redirect }),
+                None => json!({ "ns": 0, "title": title }),
+            })
+            .collect();
+        json!({ "batchcomplete": "", "query": { "search": results } })
+    }
+
+    /// A function for answering action=opensearch with the titles starting with the prefix, redirects resolved
+    ///
+    /// # Arguments
+    ///
+    /// * 'prefix' - A string slice with the beginning of the title
+    /// * 'limit' - The maximum amount of titles
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - The response with the titles
+    fn open_search(&self, prefix: &str, limit: usize) -> serde_json::Value {
+        let lowercase = prefix.to_lowercase();
+        let titles = self.links.keys().filter(|title| title.to_lowercase().starts_with(&lowercase));
+        let redirected = self.redirects.iter()
+            .filter(|(alias, _)| alias.to_lowercase().starts_with(&lowercase))
+            .map(|(_, target)| target);
+        let mut seen = HashSet::new();
+        let titles: Vec<&String> = titles.chain(redirected).filter(|title| seen.insert(*title)).take(limit).collect();
+        let empty = vec!(""; titles.len());
+        json!([prefix, titles, empty, empty])
+    }
+
+    /// A function for answering queries of titles, with their links or backlinks if they were asked for. The links
+    /// of all the titles are paged through together, limit at a time, like the real API does
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the parameters of the request
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - The response with the pages and the continuation if there are more links
+    fn query_titles(&self, params: &HashMap<String, String>) -> serde_json::Value {
+        let (prefix, links_key) = match params.get("prop").map(String::as_str) {
+            Some("links" | "linkshere") if self.restricted => return json!({ "error": {
+                "code": "readapidenied", "info": "You need read permission to use this module" } }),
+            Some("links") => ("pl", "links"),
+            Some("linkshere") => ("lh", "linkshere"),
+            Some("extracts") if !self.texts.is_empty() => ("ex", ""),
+            Some("info") => ("in", ""),
+            None => ("", ""),
+            Some(_) => return unsupported(),
+        };
+
+        let mut converted = vec!();
+        let mut redirects = vec!();
+        let mut titles: Vec<&str> = vec!();
+        for title in params["titles"].split('|') {
+            let title = match (params.contains_key("converttitles"), self.variants.get(title)) {
+                (true, Some(target)) => {
+                    converted.push(json!({ "from": title, "to": target }));
+                    target.as_str()
+                },
+                _ => title,
+            };
+            let title = match (params.contains_key("redirects"), self.redirects.get(title)) {
+                (true, Some(target)) => {
+                    redirects.push(json!({ "from": title, "to": target }));
+                    target.as_str()
+                },
+                _ => title,
+            };
+            if !titles.contains(&title) {
+                titles.push(title);
+            }
+        }
+
+        let limit = parse_limit(params.get(&format!("{}limit", prefix)).map(String::as_str)).min(self.link_limit);
+        let offset: usize = params.get(&format!("{}continue", prefix)).and_then(|offset| offset.parse().ok())
+            .unwrap_or(0);
+        let (mut skip, mut left, mut total) = (offset, limit, 0);
+
+        let mut pages = serde_json::Map::new();
+        for (index, title) in titles.iter().enumerate() {
+            let (page_id, mut page) = self.page(title, index);
+            if prefix == "ex" {
+                if let Some(text) = self.texts.get(*title) {
+                    page["extract"] = json!(text);
+                }
+            }
+            if prefix == "in" && page.get("missing").is_none() {
+                page["fullurl"] = json!(format!("https://{}.invalid/wiki/{}", self.wiki_id, title.replace(' ', "_")));
+                page["length"] = json!(self.links_of(title, "links").len() * FIXTURE_BYTES_PER_LINK);
+            }
+            if !links_key.is_empty() {
+                let links = self.links_of(title, links_key);
+                total += links.len();
+                let start = skip.min(links.len());
+                skip -= start;
+                let shown: Vec<serde_json::Value> = links[start..].iter().take(left)
+                    .map(|link| json!({ "ns": 0, "title": link }))
+                    .collect();
+                left -= shown.len();
+                if !shown.is_empty() {
+                    page[links_key] = serde_json::Value::Array(shown);
+                }
+            }
+            pages.insert(page_id.to_string(), page);
+        }
+
+        let mut response = json!({ "query": { "pages": pages } });
+        if !converted.is_empty() {
+            response["query"]["converted"] = serde_json::Value::Array(converted);
+        }
+        if !redirects.is_empty() {
+            response["query"]["redirects"] = serde_json::Value::Array(redirects);
+        }
+        if offset + limit < total {
+            response["continue"] = json!({ format!("{}continue", prefix): (offset + limit).to_string(),
+                                           "continue": "||" });
+        } else {
+            response["batchcomplete"] = json!("");
+        }
+        response
+    }
+
+    /// A function for answering a request for the HTML of an article through the REST API, with the links of the
+    /// article marked up like Parsoid does and the texts of the links escaped. Redirects are followed
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A string slice with the title of the article
+    ///
+    /// # Returns
+    ///
+    /// * Option<String> - The HTML of the article, or None if it doesn't exist
+    fn page_html(&self, title: &str) -> Option<String> {
+        let title = self.redirects.get(title).map_or(title, String::as_str);
+        let links = self.links.get(title)?;
+        let texts = self.anchors.get(title);
+        let anchors: Vec<String> = links.iter()
+            .map(|link| {
+                let path = link.replace(' ', "_").replace('%', "%25").replace('"', "%22").replace('?', "%3F")
+                    .replace('#', "%23");
+                let text = texts.and_then(|texts| texts.get(link)).unwrap_or(link);
+                format!("<a rel=\"mw:WikiLink\" href=\"./{}\" title=\"{}\">{}</a>", path,
+                        link.replace('"', "&quot;"), text.replace('&', "&amp;").replace('<', "&lt;"))
+            })
+            .collect();
+        Some(format!("<!DOCTYPE html>\n<html><head><title>{}</title></head><body><p>{}</p></body></html>", title,
+                     anchors.join(" ")))
+    }
+
+    /// A function for answering list=backlinks. The articles linking through a redirect are listed under it
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the parameters of the request
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - The response with the backlinks and the continuation if there are more of them
+    fn list_backlinks(&self, params: &HashMap<String, String>) -> serde_json::Value {
+        let title = params.get("bltitle").map(String::as_str).unwrap_or_default();
+        let direct = self.links_of(title, "linkshere").iter().map(|link| json!({ "ns": 0, "title": link }));
+        let redirected = self.redirects.iter()
+            .filter(|(_, target)| target.as_str() == title)
+            .map(|(alias, _)| {
+                let links: Vec<serde_json::Value> = self.links_of(alias, "linkshere").iter()
+                    .map(|link| json!({ "ns": 0, "title": link }))
+                    .collect();
+                json!({ "ns": 0, "title": alias, "redirect": "", "redirlinks": links })
+            });
+        let backlinks: Vec<serde_json::Value> = direct.chain(redirected).collect();
+
+        let limit = parse_limit(params.get("bllimit").map(String::as_str)).min(self.link_limit);
+        let offset: usize = params.get("blcontinue").and_then(|offset| offset.parse().ok()).unwrap_or(0);
+        let shown: Vec<&serde_json::Value> = backlinks.iter().skip(offset).take(limit).collect();
+        let mut response = json!({ "query": { "backlinks": shown } });
+        if offset + limit < backlinks.len() {
+            response["continue"] = json!({ "blcontinue": (offset + limit).to_string(), "continue": "-||" });
+        } else {
+            response["batchcomplete"] = json!("");
+        }
+        response
+    }
+
+    /// A function for answering list=categorymembers with continuation. Every member is listed as an article
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the parameters of the request
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - The response with the members, empty if the category isn't in the fixture
+    fn list_category_members(&self, params: &HashMap<String, String>) -> serde_json::Value {
+        let category = params.get("cmtitle").map(String::as_str).unwrap_or_default();
+        let members = self.categories.get(category).map(Vec::as_slice).unwrap_or_default();
+
+        let limit = parse_limit(params.get("cmlimit").map(String::as_str)).min(self.link_limit);
+        let offset: usize = params.get("cmcontinue").and_then(|offset| offset.parse().ok()).unwrap_or(0);
+        let shown: Vec<serde_json::Value> = members.iter().skip(offset).take(limit)
+            .map(|member| json!({ "ns": 0, "title": member }))
+            .collect();
+        let mut response = json!({ "query": { "categorymembers": shown } });
+        if offset + limit < members.len() {
+            response["continue"] = json!({ "cmcontinue": (offset + limit).to_string(), "continue": "-||" });
+        } else {
+            response["batchcomplete"] = json!("");
+        }
+        response
+    }
+
+    /// A function for answering list=mostviewed with continuation, the most viewed article first
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the parameters of the request
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - The response with the articles and their views
+    fn list_most_viewed(&self, params: &HashMap<String, String>) -> serde_json::Value {
+        let mut ranked: Vec<(&String, &u64)> = self.views.iter().collect();
+        ranked.sort_by(|first, second| second.1.cmp(first.1));
+
+        let limit = parse_limit(params.get("pvimlimit").map(String::as_str)).min(self.link_limit);
+        let offset: usize = params.get("pvimoffset").and_then(|offset| offset.parse().ok()).unwrap_or(0);
+        let shown: Vec<serde_json::Value> = ranked.iter().skip(offset).take(limit)
+            .map(|(title, count)| json!({ "ns": 0, "title": title, "count": count }))
+            .collect();
+        let mut response = json!({ "query": { "mostviewed": shown } });
+        if offset + limit < ranked.len() {
+            response["continue"] = json!({ "pvimoffset": offset + limit, "continue": "-||" });
+        } else {
+            response["batchcomplete"] = json!("");
+        }
+        response
+    }
+
+    /// A function for constructing the page object of a title in a query response
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A string slice with the title
+    /// * 'index' - The position of the title in the query, used for the ids of missing pages
+    ///
+    /// # Returns
+    ///
+    /// * (i64, serde_json::Value) - The page id and the page object
+    fn page(&self, title: &str, index: usize) -> (i64, serde_json::Value) {
+        if let Some(position) = self.links.keys().position(|article| article == title) {
+            let page_id = position as i64 + 1;
+            return (page_id, json!({ "pageid": page_id, "ns": 0, "title": title }));
+        }
+        if let Some(position) = self.redirects.keys().position(|alias| alias == title) {
+            let page_id = (self.links.len() + position) as i64 + 1;
+            return (page_id, json!({ "pageid": page_id, "ns": 0, "title": title, "redirect": "" }));
+        }
+        (-(index as i64) - 1, json!({ "ns": 0, "title": title, "missing": "" }))
+    }
+
+    /// A function for finding the links of a title in a direction. A redirect links only to its target
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A string slice with the title
+    /// * 'links_key' - The result key of the direction, either links or linkshere
+    ///
+    /// # Returns
+    ///
+    /// * &[String] - The titles linked to or from the title
+    fn links_of(&self, title: &str, links_key: &str) -> &[String] {
+        match links_key {
+            "links" => match (self.links.get(title), self.redirects.get(title)) {
+                (Some(links), _) => links,
+                (None, Some(target)) => std::slice::from_ref(target),
+                (None, None) => &[],
+            },
+            _ => self.backlinks.get(title).map_or(&[], Vec::as_slice),
+        }
+    }
+}
+
+/// An async function for starting the fixture server in the background
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the fixture file, see FixtureGraph
+///
+/// # Returns
+///
+/// * Result<String, Box<dyn Error>> - The url of the API of the server, or an error if the fixture couldn't be read
+///   or the server couldn't be started
+pub async fn start(file: &str) -> Result<String, Box<dyn Error>> {
+    serve(FixtureGraph::load(file)?).await
+}
+
+/// An async function for starting the fixture server in the background with the graph of the demo wiki bundled with
+/// the program
+///
+/// # Returns
+///
+/// * Result<String, Box<dyn Error>> - The url of the API of the server, or an error if the server couldn't be started
+pub async fn start_demo() -> Result<String, Box<dyn Error>> {
+    serve(FixtureGraph::parse("demo", demo::GRAPH)?).await
+}
+
+/// An async function for serving the given graph in the background from a port picked by the system
+///
+/// # Arguments
+///
+/// * 'graph' - The FixtureGraph answering the requests
+///
+/// # Returns
+///
+/// * Result<String, Box<dyn Error>> - The url of the API of the server, or an error if the server couldn't be started
+async fn serve(graph: FixtureGraph) -> Result<String, Box<dyn Error>> {
+    let graph = Arc::new(graph);
+    let mut listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        loop {
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = graph.outage_starts.notified() => {
+                    // Closing the listener makes the connections to the server fail like they would without network
+                    drop(listener);
+                    tokio::time::sleep(Duration::from_secs(graph.outage.map_or(0, |(_, seconds)| seconds))).await;
+                    listener = match TcpListener::bind(address).await {
+                        Ok(listener) => listener,
+                        Err(error) => {
+                            eprintln!("Error restarting the fixture server after its outage:\n{:?}", error);
+                            return;
+                        },
+                    };
+                    continue;
+                },
+            };
+            let stream = match accepted {
+                Ok((stream, _)) => stream,
+                Err(error) => {
+                    eprintln!("Error accepting a connection to the fixture server:\n{:?}", error);
+                    continue;
+                },
+            };
+            let graph = graph.clone();
+            tokio::spawn(async move {
+                if let Err(error) = serve_connection(stream, &graph).await {
+                    eprintln!("Error answering a request to the fixture server:\n{:?}", error);
+                }
+            });
+        }
+    });
+    Ok(format!("http://{}/w/api.php", address))
+}
+
+/// An async function for reading a single HTTP request from a connection and answering it. The connection is closed
+/// after the response, so that the client doesn't try to reuse it
+///
+/// # Arguments
+///
+/// * 'stream' - The TcpStream of the connection
+/// * 'graph' - A reference to the FixtureGraph answering the request
+///
+/// # Returns
+///
+/// * io::Result<()> - Result containing possible errors
+async fn serve_connection(stream: TcpStream, graph: &FixtureGraph) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec!(0; content_length);
+    reader.read_exact(&mut body).await?;
+
+    // GET requests have the parameters in the query string and POST requests in the form encoded body
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let query = if body.is_empty() {
+        target.split_once('?').map_or("", |(_, query)| query).to_string()
+    } else {
+        String::from_utf8_lossy(&body).into_owned()
+    };
+    let params: HashMap<String, String> = query.split('&')
+        .filter_map(|pair| pair.split_once('=').map(|(name, value)| (percent_decode(name), percent_decode(value))))
+        .collect();
+
+    let mut stream = reader.into_inner();
+    let path = target.split('?').next().unwrap_or_default();
+    if let Some(title) = path.strip_prefix("/w/rest.php/v1/page/").and_then(|rest| rest.strip_suffix("/html")) {
+        let (status, html) = match graph.page_html(&percent_decode(title).replace('_', " ")) {
+            Some(html) => ("200 OK", html),
+            None => ("404 Not Found", String::new()),
+        };
+        stream.write_all(format!(concat!("HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\n",
+                                         "Content-Length: {}\r\nConnection: close\r\n\r\n{}"),
+                                 status, html.len(), html).as_bytes()).await?;
+        return stream.shutdown().await;
+    }
+    let link_query = graph.count_link_query(&params);
+    if graph.is_gone(link_query) {
+        stream.write_all(b"HTTP/1.1 410 Gone\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await?;
+        return stream.shutdown().await;
+    }
+    if let Some(retry_after) = link_query.and_then(|count| graph.throttled(count)) {
+        stream.write_all(format!(concat!("HTTP/1.1 429 Too Many Requests\r\nRetry-After: {}\r\n",
+                                         "Content-Length: 0\r\nConnection: close\r\n\r\n"),
+                                 retry_after).as_bytes()).await?;
+        return stream.shutdown().await;
+    }
+
+    let response = match link_query.and_then(|count| graph.api_error(count)) {
+        Some(error) => error.to_string(),
+        None => graph.respond(&params).to_string(),
+    };
+    stream.write_all(format!(concat!("HTTP/1.1 200 OK\r\nContent-Type: application/json; charset=utf-8\r\n",
+                                     "Content-Length: {}\r\nConnection: close\r\n\r\n{}"),
+                             response.len(), response).as_bytes()).await?;
+    if link_query.is_some() && link_query == graph.outage.map(|(after, _)| after) {
+        graph.outage_starts.notify_one();
+    }
+    stream.shutdown().await
+}
+
+/// A function for decoding a form encoded string
+///
+/// # Arguments
+///
+/// * 'encoded' - A string slice with the encoded string
+///
+/// # Returns
+///
+/// * String - The decoded string, with malformed escapes kept as they are
+fn percent_decode(encoded: &str) -> String {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = encoded.get(index + 1..index + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], escaped) {
+            (b'+', _) => decoded.push(b' '),
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 2;
+            },
+            (byte, _) => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A function for parsing a limit parameter of a request
+///
+/// # Arguments
+///
+/// * 'limit' - An option containing the value of the parameter, a number or max
+///
+/// # Returns
+///
+/// * usize - The limit, unlimited for max or a missing limit
+fn parse_limit(limit: Option<&str>) -> usize {
+    limit.and_then(|limit| limit.parse().ok()).unwrap_or(usize::MAX)
+}
+
+/// Returns the error response given to the requests the crawler doesn't make
+fn unsupported() -> serde_json::Value {
+    json!({ "error": { "code": "fixtureunsupported", "info": "The fixture server doesn't support this request" } })
+}
@@ -0,0 +1,85 @@
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// A function for folding a string into a form where case and diacritics don't matter
+///
+/// # Arguments
+///
+/// * 'string' - A string slice to fold
+///
+/// # Returns
+///
+/// * String - The folded string: decomposed, stripped of combining marks and lowercased
+pub fn fold(string: &str) -> String {
+    string.nfd().filter(|character| !is_combining_mark(*character)).collect::<String>().to_lowercase()
+}
+
+/// A function for calculating the levenshtein distance between two strings, counted in chars
+///
+/// # Arguments
+///
+/// * 'first' - The first string slice to compare
+/// * 'second' - The second string slice to compare
+///
+/// # Returns
+///
+/// * usize - The minimum amount of single char insertions, deletions and substitutions between the strings
+pub fn levenshtein(first: &str, second: &str) -> usize {
+    let second_chars: Vec<char> = second.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=second_chars.len()).collect();
+
+    for (first_index, first_char) in first.chars().enumerate() {
+        let mut current_row = vec!(first_index + 1);
+        for (second_index, second_char) in second_chars.iter().enumerate() {
+            let substitution_cost = if first_char == *second_char { 0 } else { 1 };
+            let distance = (previous_row[second_index] + substitution_cost)
+                .min(previous_row[second_index + 1] + 1)
+                .min(current_row[second_index] + 1);
+            current_row.push(distance);
+        }
+        previous_row = current_row;
+    }
+
+    previous_row[second_chars.len()]
+}
+
+/// A function for deciding how many typos are tolerated in a string of the given length. Short titles tolerate
+/// none, as a single edit can easily turn them into a completely different article
+///
+/// # Arguments
+///
+/// * 'length' - The length of the input in chars
+///
+/// # Returns
+///
+/// * usize - The maximum edit distance still considered a typo
+fn max_typo_distance(length: usize) -> usize {
+    match length {
+        0..=4 => 0,
+        5..=10 => 1,
+        _ => 2,
+    }
+}
+
+/// A function for calculating how close a user input is to a candidate title, if it is close enough to be
+/// considered the same title with a typo, a case difference or missing diacritics
+///
+/// # Arguments
+///
+/// * 'input' - A string slice with the input given by the user
+/// * 'candidate' - A string slice with the candidate title
+///
+/// # Returns
+///
+/// * Option<usize> - The edit distance between the folded strings, or None if they are too far apart
+pub fn close_match_distance(input: &str, candidate: &str) -> Option<usize> {
+    let folded_input = fold(input);
+    let folded_candidate = fold(candidate);
+    let distance = levenshtein(&folded_input, &folded_candidate);
+
+    if distance <= max_typo_distance(folded_input.chars().count()) {
+        Some(distance)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::sync::Mutex;
+
+use super::article_title::ArticleTitle;
+use super::crawler::ErrorReporter;
+
+const DOT_HEADER: &str = "strict digraph crawl {\n";
+const DOT_TRAILER: &str = "}\n";
+const CSV_HEADER: &str = "source,target\n";
+const GRAPHML_HEADER: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+    "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+    "  <key id=\"outcome\" for=\"graph\" attr.name=\"outcome\" attr.type=\"string\"/>\n",
+    "  <key id=\"path\" for=\"graph\" attr.name=\"path\" attr.type=\"string\"/>\n",
+    "  <graph id=\"crawl\" edgedefault=\"directed\">\n");
+const GRAPHML_TRAILER: &str = "  </graph>\n</graphml>\n";
+
+/// An enum representing the file formats the graph of a crawl can be exported in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GraphFormat {
+    /// The DOT language of Graphviz
+    Dot,
+    /// A table of the edges with a source and a target column
+    Csv,
+    /// The XML based GraphML format read by Gephi and networkx among others
+    GraphMl,
+}
+
+impl GraphFormat {
+    /// A function for choosing the format by the extension of the file
+    ///
+    /// # Arguments
+    ///
+    /// * 'file' - A string slice with the path of the file
+    ///
+    /// # Returns
+    ///
+    /// * Option<GraphFormat> - The format, or None if the extension isn't .dot, .gv, .csv or .graphml
+    pub fn from_file(file: &str) -> Option<GraphFormat> {
+        let extension = file.rsplit_once('.')?.1.to_lowercase();
+        match extension.as_str() {
+            "dot" | "gv" => Some(GraphFormat::Dot),
+            "csv" => Some(GraphFormat::Csv),
+            "graphml" => Some(GraphFormat::GraphMl),
+            _ => None,
+        }
+    }
+
+    /// Returns the text the file starts with
+    fn header(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => DOT_HEADER,
+            GraphFormat::Csv => CSV_HEADER,
+            GraphFormat::GraphMl => GRAPHML_HEADER,
+        }
+    }
+
+    /// Returns the text closing the file, which the file always ends with so that it's complete at any moment
+    fn trailer(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => DOT_TRAILER,
+            GraphFormat::Csv => "",
+            GraphFormat::GraphMl => GRAPHML_TRAILER,
+        }
+    }
+}
+
+/// The file of a GraphWriter along with the nodes already written into it
+struct GraphFile {
+    file: File,
+    nodes: HashSet<ArticleTitle>,
+}
+
+/// A writer of the graph a crawl discovers, appending the links to the file as they are claimed. The edges lead
+/// from the article a link was found from to the linked article, so together they form the tree the crawl searched
+///
+/// Every batch of edges is written straight into the file before the closing part of the format, so that a crawl
+/// that is cancelled or crashes still leaves a complete file with the part of the graph discovered so far. Once the
+/// crawl has ended, finish marks how it ended and the articles on the found path
+pub struct GraphWriter {
+    format: GraphFormat,
+    file: Mutex<GraphFile>,
+}
+
+impl GraphWriter {
+    /// A constructor for GraphWriter, replacing the file if it already exists
+    ///
+    /// # Arguments
+    ///
+    /// * 'file' - A string slice with the path of the file, whose extension chooses the format
+    ///
+    /// # Returns
+    ///
+    /// * Result<GraphWriter, Box<dyn Error>> - A new GraphWriter, or an error if the format wasn't recognized or the
+    ///   file couldn't be written
+    pub fn open(file: &str) -> Result<GraphWriter, Box<dyn Error>> {
+        let format = match GraphFormat::from_file(file) {
+            Some(format) => format,
+            None => return Err(Box::new(io::Error::new(io::ErrorKind::InvalidInput, format!(
+                "Can't tell the format of '{}', expected a .dot, .gv, .csv or .graphml file", file)))),
+        };
+        let mut file = File::create(file)?;
+        file.write_all(format.header().as_bytes())?;
+        file.write_all(format.trailer().as_bytes())?;
+        Ok(GraphWriter { format, file: Mutex::new(GraphFile { file, nodes: HashSet::new() }) })
+    }
+
+    /// A function for appending the links claimed from an article to the file
+    ///
+    /// # Arguments
+    ///
+    /// * 'source' - A reference to the title of the article the links were found from
+    /// * 'targets' - A slice of the titles of the claimed links
+    /// * 'errors' - A reference to the ErrorReporter a failed write is reported with
+    pub fn record_edges(&self, source: &ArticleTitle, targets: &[ArticleTitle], errors: &ErrorReporter) {
+        if targets.is_empty() {
+            return;
+        }
+        let mut graph = match self.file.lock() {
+            Ok(graph) => graph,
+            Err(error) => {
+                errors.report(format!("Error acquiring lock for graph export:\n{:?}", error));
+                return;
+            },
+        };
+
+        let mut text = String::new();
+        for target in targets.iter() {
+            match self.format {
+                GraphFormat::Dot => text.push_str(&format!("  {} -> {};\n", dot_id(source.as_str()),
+                                                           dot_id(target.as_str()))),
+                GraphFormat::Csv => text.push_str(&format!("{},{}\n", csv_field(source.as_str()),
+                                                           csv_field(target.as_str()))),
+                GraphFormat::GraphMl => {
+                    for node in [source, target] {
+                        if graph.nodes.insert(node.clone()) {
+                            text.push_str(&format!("    <node id=\"{}\"/>\n", xml_escape(node.as_str())));
+                        }
+                    }
+                    text.push_str(&format!("    <edge source=\"{}\" target=\"{}\"/>\n",
+                                           xml_escape(source.as_str()), xml_escape(target.as_str())));
+                },
+            }
+        }
+        if let Err(error) = append(&mut graph.file, &text, self.format.trailer()) {
+            errors.report(format!("Error writing to graph export:\n{:?}", error));
+        }
+    }
+
+    /// A function for marking how the crawl ended and the found path, once the crawl has ended. DOT files get the
+    /// outcome as their label and the path drawn in red, and GraphML files get both as data of the graph, with the
+    /// titles on the path separated by |. The last link of the path leads to an article the crawl never claimed, so
+    /// it's added to the graph here. CSV files have no place for the outcome, so they only get that link
+    ///
+    /// # Arguments
+    ///
+    /// * 'outcome' - A string slice naming how the crawl ended, such as found or cancelled
+    /// * 'path' - An option containing the found path in the order of the edges, if the crawl found one
+    /// * 'errors' - A reference to the ErrorReporter a failed write is reported with
+    pub fn finish(&self, outcome: &str, path: Option<&[ArticleTitle]>, errors: &ErrorReporter) {
+        let mut graph = match self.file.lock() {
+            Ok(graph) => graph,
+            Err(error) => {
+                errors.report(format!("Error acquiring lock for graph export:\n{:?}", error));
+                return;
+            },
+        };
+
+        let path = path.unwrap_or_default();
+        let last_link = match path {
+            [.., before, goal] => Some((before, goal)),
+            _ => None,
+        };
+        let text = match self.format {
+            GraphFormat::Dot => {
+                // A strict graph merges the repeated edges, so the edges of the path are drawn once in red
+                let mut text = format!("  label={};\n", dot_id(outcome));
+                for pair in path.windows(2) {
+                    text.push_str(&format!("  {} -> {} [color=red, penwidth=2];\n", dot_id(pair[0].as_str()),
+                                           dot_id(pair[1].as_str())));
+                }
+                text
+            },
+            GraphFormat::Csv => match last_link {
+                Some((before, goal)) => format!("{},{}\n", csv_field(before.as_str()), csv_field(goal.as_str())),
+                None => return,
+            },
+            GraphFormat::GraphMl => {
+                let mut text = String::new();
+                if let Some((before, goal)) = last_link {
+                    if graph.nodes.insert(goal.clone()) {
+                        text.push_str(&format!("    <node id=\"{}\"/>\n", xml_escape(goal.as_str())));
+                    }
+                    text.push_str(&format!("    <edge source=\"{}\" target=\"{}\"/>\n",
+                                           xml_escape(before.as_str()), xml_escape(goal.as_str())));
+                }
+                text.push_str(&format!("    <data key=\"outcome\">{}</data>\n", xml_escape(outcome)));
+                if !path.is_empty() {
+                    let titles: Vec<&str> = path.iter().map(ArticleTitle::as_str).collect();
+                    text.push_str(&format!("    <data key=\"path\">{}</data>\n", xml_escape(&titles.join("|"))));
+                }
+                text
+            },
+        };
+        if let Err(error) = append(&mut graph.file, &text, self.format.trailer()) {
+            errors.report(format!("Error writing to graph export:\n{:?}", error));
+        }
+    }
+}
+
+/// A function for writing text into the file before its closing part, and writing the closing part again after it
+///
+/// # Arguments
+///
+/// * 'file' - A mutable reference to the file, which ends with the closing part
+/// * 'text' - A string slice with the text to append
+/// * 'trailer' - A string slice with the closing part of the format
+///
+/// # Returns
+///
+/// * io::Result<()> - Result containing possible errors
+fn append(file: &mut File, text: &str, trailer: &str) -> io::Result<()> {
+    file.seek(SeekFrom::End(-(trailer.len() as i64)))?;
+    file.write_all(text.as_bytes())?;
+    file.write_all(trailer.as_bytes())
+}
+
+/// Returns the text as a quoted DOT identifier
+fn dot_id(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns the text as a CSV field, quoted if it contains a comma, a quote or a line break
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Returns the text with the characters special to XML escaped
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::OnceLock;
+
+use mediawiki::title::Title;
+use regex::Regex;
+
+use super::article_title::ArticleTitle;
+use super::title_url;
+use super::wiki_client::WikiClient;
+
+// The links between articles in the HTML rendered by Parsoid, such as <a rel="mw:WikiLink" href="./Title">text</a>.
+// Links to other wikis have a rel of their own, so they aren't matched
+static WIKI_LINK: OnceLock<Option<Regex>> = OnceLock::new();
+// The tags within the text of a link, such as <i> in <a ...><i>Title</i></a>
+static TAG: OnceLock<Option<Regex>> = OnceLock::new();
+
+/// A function for locating the REST API of a wiki from the url of its Action API. The REST API of MediaWiki itself is
+/// served next to the Action API, so it's found on every wiki that isn't too old for it, unlike the RESTBase API of
+/// the Wikimedia wikis
+///
+/// # Arguments
+///
+/// * 'api_url' - A string slice with the url of the Action API, such as https://en.wikipedia.org/w/api.php
+///
+/// # Returns
+///
+/// * Option<String> - The url of the REST API, such as https://en.wikipedia.org/w/rest.php/v1, or None if the url
+///   doesn't end with api.php
+pub fn rest_url(api_url: &str) -> Option<String> {
+    let script_path = api_url.strip_suffix("api.php")?;
+    Some(format!("{}rest.php/v1", script_path))
+}
+
+/// Returns the title encoded for a path of the REST API, with underscores in place of spaces
+pub fn encode_title(title: &ArticleTitle) -> String {
+    title.as_str().replace(' ', "_").bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'(' | b')' | b'!' | b',' | b'\''
+            | b'*' | b':' => (byte as char).to_string(),
+        _ => format!("%{:02X}", byte),
+    }).collect()
+}
+
+/// An async function for reading the links of an article from its HTML, for wikis whose Action API doesn't list
+/// links or for following only the links whose text matches the anchor pattern of the client. Only the links to
+/// articles of the main namespace are kept, each once, in the order they are in the article. Costs one request per
+/// article
+///
+/// # Arguments
+///
+/// * 'article' - A reference to the title of the article
+/// * 'client' - A reference to a WikiClient with the HTML fallback allowed
+///
+/// # Returns
+///
+/// * Result<Vec<ArticleTitle>, Box<dyn Error>> - The links of the article, empty if the article doesn't exist
+pub async fn fetch_links(article: &ArticleTitle, client: &WikiClient) -> Result<Vec<ArticleTitle>, Box<dyn Error>> {
+    match client.get_html(article).await? {
+        Some(html) => Ok(parse_links(&html, client.api(), client.anchor_pattern())),
+        None => Ok(vec!()),
+    }
+}
+
+/// A function for finding the links to articles of the main namespace from the HTML of an article. With an anchor
+/// pattern, an article linked several times is kept if the text of any of its links matches
+///
+/// # Arguments
+///
+/// * 'html' - A string slice with the HTML rendered by Parsoid
+/// * 'api' - A reference to the mediawiki::api::Api whose site info tells the namespaces of the wiki
+/// * 'anchor_pattern' - An option containing the pattern the text of a link has to match, if there is one
+///
+/// # Returns
+///
+/// * Vec<ArticleTitle> - The linked articles, each once
+fn parse_links(html: &str, api: &mediawiki::api::Api, anchor_pattern: Option<&Regex>) -> Vec<ArticleTitle> {
+    let pattern = WIKI_LINK.get_or_init(|| {
+        // The attributes can come in either order
+        Regex::new(concat!(r#"(?s)<a\s[^>]*?rel="mw:WikiLink"[^>]*?\shref="\./([^"?#]*)[^>]*>(.*?)</a>"#,
+                           r#"|<a\s[^>]*?href="\./([^"?#]*)[^"]*"[^>]*?\srel="mw:WikiLink"[^>]*>(.*?)</a>"#)).ok()
+    });
+    let pattern = match pattern {
+        Some(pattern) => pattern,
+        None => return vec!(),
+    };
+    let mut seen = HashSet::new();
+    pattern.captures_iter(html)
+        .filter_map(|captures| {
+            let path = captures.get(1).or_else(|| captures.get(3))?;
+            let text = captures.get(2).or_else(|| captures.get(4)).map_or("", |text| text.as_str());
+            Some((path.as_str(), text))
+        })
+        .filter(|(_, text)| anchor_pattern.is_none_or(|anchor_pattern| anchor_pattern.is_match(&anchor_text(text))))
+        .filter_map(|(path, _)| title_url::percent_decode(path))
+        .map(|path| path.replace('_', " "))
+        .filter(|title| !title.is_empty() && Title::new_from_full(title, api).namespace_id() == 0)
+        .map(|title| ArticleTitle::new(&title))
+        .filter(|title| seen.insert(title.clone()))
+        .collect()
+}
+
+/// Returns the text of a link as it's shown, without the tags within it and with the entities escaped by Parsoid
+/// turned back into characters
+fn anchor_text(html: &str) -> String {
+    let text = match TAG.get_or_init(|| Regex::new("<[^>]*>").ok()) {
+        Some(tag) => tag.replace_all(html, ""),
+        None => html.into(),
+    };
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&#39;", "'")
+        .replace("&nbsp;", "\u{a0}").replace("&amp;", "&")
+}
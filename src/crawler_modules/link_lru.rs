@@ -0,0 +1,98 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::article_title::ArticleTitle;
+use super::wiki_api::LinkDirection;
+
+// The amount of articles whose links are kept in memory by default
+pub const DEFAULT_MEMORY_CACHE_SIZE: usize = 1000;
+
+/// A bounded in-memory map of articles to their links, dropping the links used the longest time ago once it's full.
+/// Kept in front of the database of a WikiCache, so that the links of articles needed again and again during a
+/// session don't have to be read from the database every time
+pub struct LinkLru {
+    capacity: usize,
+    // The links of each article, along with the last time they were used at
+    entries: HashMap<(LinkDirection, ArticleTitle), (Vec<ArticleTitle>, u64)>,
+    // The articles ordered by the last time their links were used at, the least recently used first
+    order: BTreeMap<u64, (LinkDirection, ArticleTitle)>,
+    clock: u64,
+}
+
+impl LinkLru {
+    /// A constructor for LinkLru
+    ///
+    /// # Arguments
+    ///
+    /// * 'capacity' - The largest amount of articles whose links are kept
+    ///
+    /// # Returns
+    ///
+    /// * LinkLru - A new empty LinkLru
+    pub fn new(capacity: usize) -> LinkLru {
+        LinkLru { capacity, entries: HashMap::new(), order: BTreeMap::new(), clock: 0 }
+    }
+
+    /// A function for getting the links of an article, marking them as the most recently used
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    /// * 'direction' - The LinkDirection of the links
+    ///
+    /// # Returns
+    ///
+    /// * Option<Vec<ArticleTitle>> - A copy of the links, or None if they aren't kept
+    pub fn get(&mut self, title: &ArticleTitle, direction: LinkDirection) -> Option<Vec<ArticleTitle>> {
+        let key = (direction, title.clone());
+        let used_at = self.tick();
+        let (links, last_used) = self.entries.get_mut(&key)?;
+        let previous = std::mem::replace(last_used, used_at);
+        let links = links.clone();
+        self.order.remove(&previous);
+        self.order.insert(used_at, key);
+        Some(links)
+    }
+
+    /// A function for keeping the links of an article, dropping the least recently used links if there's no room
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    /// * 'direction' - The LinkDirection of the links
+    /// * 'links' - A slice of the links of the article
+    pub fn insert(&mut self, title: &ArticleTitle, direction: LinkDirection, links: &[ArticleTitle]) {
+        if self.capacity == 0 {
+            return;
+        }
+        let key = (direction, title.clone());
+        let used_at = self.tick();
+        if let Some((_, previous)) = self.entries.insert(key.clone(), (links.to_vec(), used_at)) {
+            self.order.remove(&previous);
+        }
+        self.order.insert(used_at, key);
+        while self.entries.len() > self.capacity {
+            match self.order.pop_first() {
+                Some((_, oldest)) => self.entries.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+
+    /// A function for dropping the links of an article
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    /// * 'direction' - The LinkDirection of the links
+    pub fn remove(&mut self, title: &ArticleTitle, direction: LinkDirection) {
+        if let Some((_, last_used)) = self.entries.remove(&(direction, title.clone())) {
+            self.order.remove(&last_used);
+        }
+    }
+
+    /// Returns the next point in the order the links are used in
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
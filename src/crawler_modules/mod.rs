@@ -1,4 +1,44 @@
+pub mod account;
+pub mod api_errors;
+pub mod api_warnings;
+pub mod article_title;
+pub mod autocomplete;
+pub mod cache;
+pub mod capabilities;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod configs;
 pub mod crawler;
+pub mod demo;
+pub mod display;
+pub mod existence;
+#[cfg(feature = "fixture-server")]
+pub mod fixture_server;
+pub mod fuzzy_match;
+pub mod graph_export;
+pub mod html_links;
+pub mod link_lru;
+pub mod neighborhood;
+pub mod output_sink;
+pub mod progress;
+pub mod provenance;
+pub mod rate_limiter;
+pub mod replay;
+pub mod report_diff;
+#[cfg(feature = "s3")]
+pub mod s3_sink;
+pub mod sampling;
+pub mod sdow;
+pub mod skip_log;
+pub mod statistics;
+pub mod stop_condition;
+pub mod title_filter;
+pub mod title_url;
+pub mod transcript;
 pub mod user_interface;
-pub mod wiki_api;
\ No newline at end of file
+pub mod verify;
+pub mod visited;
+pub mod warm_up;
+pub mod watch;
+pub mod wiki_api;
+pub mod wiki_client;
\ No newline at end of file
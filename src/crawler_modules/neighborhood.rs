@@ -0,0 +1,202 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use super::article_title::ArticleTitle;
+use super::graph_export::csv_field;
+use super::wiki_api::{self, LinkDirection};
+use super::wiki_client::WikiClient;
+
+const CSV_HEADER: &str = "title,depth\n";
+// The characters kept as they are in the urls of the articles, like MediaWiki keeps them
+const URL_SAFE: &[u8] = b"-._~;@$!*(),/:";
+
+/// An enum representing the formats the articles around an article can be written in
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NeighborhoodFormat {
+    /// A browsable HTML page linking to the articles, grouped by depth
+    Html,
+    /// A table of the articles with a title and a depth column
+    Csv,
+}
+
+impl NeighborhoodFormat {
+    /// Returns the format with the given name, or None if there is no such format
+    pub fn parse(name: &str) -> Option<NeighborhoodFormat> {
+        match name {
+            "html" => Some(NeighborhoodFormat::Html),
+            "csv" => Some(NeighborhoodFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// Returns the media type of the format, for the output sinks
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            NeighborhoodFormat::Html => "text/html; charset=utf-8",
+            NeighborhoodFormat::Csv => "text/csv",
+        }
+    }
+}
+
+/// A struct holding the articles within a number of clicks of an article, grouped by the least amount of clicks
+/// they are reached with
+#[derive(Clone, PartialEq, Debug)]
+pub struct Neighborhood {
+    /// The article the clicks start from
+    pub origin: ArticleTitle,
+    /// The articles reached with each amount of clicks in alphabetical order, the first one holding just the origin
+    pub depths: Vec<Vec<ArticleTitle>>,
+}
+
+impl Neighborhood {
+    /// Returns the amount of articles around the origin, not counting the origin itself
+    pub fn len(&self) -> usize {
+        self.depths.iter().skip(1).map(Vec::len).sum()
+    }
+
+    /// Returns true if no article can be reached from the origin
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A function for writing the neighborhood in the given format
+    ///
+    /// # Arguments
+    ///
+    /// * 'format' - The NeighborhoodFormat to write
+    /// * 'article_path' - An option containing the url of the articles of the wiki with $1 in place of the title,
+    ///   such as https://en.wikipedia.org/wiki/$1, or None if the articles shouldn't be linked
+    ///
+    /// # Returns
+    ///
+    /// * String - The written neighborhood
+    pub fn write(&self, format: NeighborhoodFormat, article_path: Option<&str>) -> String {
+        match format {
+            NeighborhoodFormat::Html => self.to_html(article_path),
+            NeighborhoodFormat::Csv => self.to_csv(),
+        }
+    }
+
+    /// Returns the articles as CSV with a row of the title and the depth of each article, starting with the origin
+    fn to_csv(&self) -> String {
+        let mut csv = CSV_HEADER.to_string();
+        for (depth, articles) in self.depths.iter().enumerate() {
+            for article in articles.iter() {
+                csv.push_str(&format!("{},{}\n", csv_field(article.as_str()), depth));
+            }
+        }
+        csv
+    }
+
+    /// Returns the articles as a standalone HTML page with a section of the articles of each depth, listed in
+    /// columns and linked to the wiki if an article path is given
+    fn to_html(&self, article_path: Option<&str>) -> String {
+        let link = |article: &ArticleTitle| match article_path {
+            Some(path) => format!("<a href=\"{}\">{}</a>", escape_html(&path.replace("$1", &encode_title(article))),
+                                  escape_html(article.as_str())),
+            None => escape_html(article.as_str()),
+        };
+        let clicks = |depth: usize| if depth == 1 { "1 click".to_string() } else { format!("{} clicks", depth) };
+        let max_depth = self.depths.len().saturating_sub(1);
+
+        let mut html = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>Within {} of {}</title>\n", clicks(max_depth),
+                               escape_html(self.origin.as_str())));
+        html.push_str("<style>\nbody { font-family: sans-serif; margin: 2em auto; max-width: 70em; }\n\
+                       ul { columns: 18em; }\n</style>\n</head>\n<body>\n");
+        html.push_str(&format!("<h1>Within {} of {}</h1>\n", clicks(max_depth), link(&self.origin)));
+        html.push_str(&format!("<p>{} articles</p>\n<ul>\n", self.len()));
+        for (depth, articles) in self.depths.iter().enumerate().skip(1) {
+            html.push_str(&format!("<li><a href=\"#depth-{}\">{}</a>: {} articles</li>\n", depth, clicks(depth),
+                                   articles.len()));
+        }
+        html.push_str("</ul>\n");
+        for (depth, articles) in self.depths.iter().enumerate().skip(1) {
+            html.push_str(&format!("<h2 id=\"depth-{}\">{} away</h2>\n<ul>\n", depth, clicks(depth)));
+            for article in articles.iter() {
+                html.push_str(&format!("<li>{}</li>\n", link(article)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// An async function for finding the articles within the given amount of clicks of an article, breadth first. The
+/// articles of the last depth aren't expanded, so listing the articles within two clicks fetches the links of the
+/// origin and of the articles it links to
+///
+/// # Arguments
+///
+/// * 'origin' - A reference to the title of the article the clicks start from
+/// * 'max_depth' - The largest amount of clicks
+/// * 'client' - A reference to a WikiClient with a logged in api session
+///
+/// # Returns
+///
+/// * Result<Neighborhood, Box<dyn Error>> - The articles around the origin, or an error if fetching links failed
+pub async fn explore(origin: &ArticleTitle, max_depth: usize, client: &WikiClient)
+    -> Result<Neighborhood, Box<dyn Error>> {
+    let mut seen: HashSet<ArticleTitle> = HashSet::from([origin.clone()]);
+    let mut depths = vec!(vec!(origin.clone()));
+
+    for depth in 1..=max_depth {
+        let mut reached: Vec<ArticleTitle> = vec!();
+        for batch in depths[depth - 1].chunks(client.batch_size().max(1)) {
+            let links = wiki_api::get_links(batch, LinkDirection::Outgoing, client).await?;
+            for article in batch.iter() {
+                for link in links.get(article).into_iter().flatten() {
+                    if seen.insert(link.clone()) {
+                        reached.push(link.clone());
+                    }
+                }
+            }
+        }
+        if reached.is_empty() {
+            break;
+        }
+        reached.sort();
+        eprintln!("Found {} articles at depth {}", reached.len(), depth);
+        depths.push(reached);
+    }
+    Ok(Neighborhood { origin: origin.clone(), depths })
+}
+
+/// A function for reading the url of the articles from the site info of the wiki
+///
+/// # Arguments
+///
+/// * 'client' - A reference to a WikiClient with an api session
+///
+/// # Returns
+///
+/// * Option<String> - The url with $1 in place of the title, such as https://en.wikipedia.org/wiki/$1, or None if
+///   the wiki didn't tell its server or article path
+pub fn article_path(client: &WikiClient) -> Option<String> {
+    let api = client.api();
+    let server = api.get_site_info_value("general", "server").as_str()?.to_string();
+    let path = api.get_site_info_value("general", "articlepath").as_str()?.to_string();
+    // Wikimedia wikis give their server without the protocol, such as //en.wikipedia.org
+    match server.starts_with("//") {
+        true => Some(format!("https:{}{}", server, path)),
+        false => Some(format!("{}{}", server, path)),
+    }
+}
+
+/// Returns the title encoded for the url of its article, with underscores in place of spaces
+fn encode_title(article: &ArticleTitle) -> String {
+    article.as_str().replace(' ', "_").bytes()
+        .map(|byte| match byte {
+            byte if byte.is_ascii_alphanumeric() || byte == b'_' || URL_SAFE.contains(&byte) => {
+                (byte as char).to_string()
+            },
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Returns the text with the characters having a meaning in HTML escaped
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::future::Future;
+use std::io::{self, Write};
+use std::pin::Pin;
+
+use mediawiki;
+use tokio;
+
+// The target of the reports that writes them to stdout instead of a file
+pub const STDOUT_TARGET: &str = "-";
+const WEBHOOK_PREFIXES: [&str; 2] = ["http://", "https://"];
+pub const S3_PREFIX: &str = "s3://";
+
+/// The future a sink writes with, resolving once the output has been delivered
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<(), Box<dyn Error + Send + Sync>>> + Send + 'a>>;
+
+/// A trait for the places the results and reports of a run can be delivered to. Each write delivers one whole
+/// output, such as a report, so that the file or the object of a sink always holds the latest one
+pub trait OutputSink: Send + Sync {
+    /// A function for delivering an output
+    ///
+    /// # Arguments
+    ///
+    /// * 'content' - A string slice with the output
+    /// * 'content_type' - A string slice with the media type of the output, such as application/json
+    ///
+    /// # Returns
+    ///
+    /// * SinkFuture - A future resolving once the output has been delivered, or to an error if it couldn't be
+    fn write<'a>(&'a self, content: &'a str, content_type: &'a str) -> SinkFuture<'a>;
+
+    /// Returns where the sink delivers the outputs, for telling the user
+    fn describe(&self) -> String;
+}
+
+/// A sink writing the outputs into a local file
+pub struct FileSink {
+    path: String,
+}
+
+impl OutputSink for FileSink {
+    fn write<'a>(&'a self, content: &'a str, _content_type: &'a str) -> SinkFuture<'a> {
+        Box::pin(async move {
+            tokio::fs::write(&self.path, content).await?;
+            Ok(())
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("'{}'", self.path)
+    }
+}
+
+/// A sink printing the outputs to stdout
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write<'a>(&'a self, content: &'a str, _content_type: &'a str) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let mut stdout = io::stdout();
+            writeln!(stdout, "{}", content.trim_end())?;
+            stdout.flush()?;
+            Ok(())
+        })
+    }
+
+    fn describe(&self) -> String {
+        "stdout".to_string()
+    }
+}
+
+/// A sink sending each output to a webhook as the body of a POST request
+pub struct WebhookSink {
+    url: String,
+    client: mediawiki::reqwest::Client,
+}
+
+impl OutputSink for WebhookSink {
+    fn write<'a>(&'a self, content: &'a str, content_type: &'a str) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let response = self.client.post(&self.url)
+                .header("Content-Type", content_type)
+                .body(content.to_string())
+                .send().await?;
+            if !response.status().is_success() {
+                return Err(format!("The webhook '{}' answered with status {}", self.url,
+                                   response.status().as_u16()).into());
+            }
+            Ok(())
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("the webhook '{}'", self.url)
+    }
+}
+
+/// A function for opening the sink of a target given on the command line: - for stdout, an http or https url for a
+/// webhook, an s3://bucket/key url for S3 compatible storage when built with the s3 feature, and a path of a file
+/// otherwise
+///
+/// # Arguments
+///
+/// * 'target' - A string slice with the target
+///
+/// # Returns
+///
+/// * Result<Box<dyn OutputSink>, Box<dyn Error>> - The sink, or an error if the target is malformed or needs a
+///   feature the crawler wasn't built with
+pub fn open(target: &str) -> Result<Box<dyn OutputSink>, Box<dyn Error>> {
+    if target == STDOUT_TARGET {
+        return Ok(Box::new(StdoutSink));
+    }
+    if WEBHOOK_PREFIXES.iter().any(|prefix| target.starts_with(prefix)) {
+        return Ok(Box::new(WebhookSink { url: target.to_string(), client: mediawiki::reqwest::Client::new() }));
+    }
+    if let Some(location) = target.strip_prefix(S3_PREFIX) {
+        return open_s3(location);
+    }
+    Ok(Box::new(FileSink { path: target.to_string() }))
+}
+
+#[cfg(feature = "s3")]
+fn open_s3(location: &str) -> Result<Box<dyn OutputSink>, Box<dyn Error>> {
+    Ok(Box::new(super::s3_sink::S3Sink::from_env(location)?))
+}
+
+#[cfg(not(feature = "s3"))]
+fn open_s3(_location: &str) -> Result<Box<dyn OutputSink>, Box<dyn Error>> {
+    Err("Writing to S3 needs the crawler to be built with --features s3".into())
+}
+
+/// An async function for delivering an output to a target, see open
+///
+/// # Arguments
+///
+/// * 'target' - A string slice with the target
+/// * 'content' - A string slice with the output
+/// * 'content_type' - A string slice with the media type of the output
+///
+/// # Returns
+///
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+pub async fn deliver(target: &str, content: &str, content_type: &str) -> Result<(), Box<dyn Error>> {
+    let sink = open(target)?;
+    if let Err(error) = sink.write(content, content_type).await {
+        return Err(format!("Couldn't deliver the output to {}: {}", sink.describe(), error).into());
+    }
+    Ok(())
+}
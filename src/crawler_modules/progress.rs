@@ -0,0 +1,257 @@
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use serde_json::{self, json};
+
+use super::crawler::ErrorReporter;
+use super::statistics::CrawlStats;
+
+// The target of --progress-json that writes the frames to stderr instead of a file
+pub const STDERR_TARGET: &str = "-";
+
+/// A struct holding the live state of the channel the workers send the analyzed links to the main thread through
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct BackPressure {
+    /// The amount of batches waiting in the channel for the main thread to fetch their links
+    pub queued_batches: usize,
+    /// The amount of workers waiting for room in the channel because it's full
+    pub blocked_senders: usize,
+    /// The amount of workers analyzing links
+    pub running_workers: usize,
+}
+
+impl BackPressure {
+    /// Returns what the crawl is waiting on: "api" while batches are waiting for the main thread to fetch their
+    /// links, "processing" while the main thread is waiting for the workers to send it more, or None if neither
+    pub fn bound(&self) -> Option<&'static str> {
+        if self.queued_batches > 0 {
+            Some("api")
+        } else if self.running_workers > 0 {
+            Some("processing")
+        } else {
+            None
+        }
+    }
+}
+
+/// An enum representing the state of a crawl in a ProgressSnapshot
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum CrawlState {
+    /// The crawl has been created but not started
+    Waiting,
+    Running,
+    Found,
+    NoPath,
+    BudgetExhausted,
+    Cancelled,
+    Aborted,
+    Failed,
+}
+
+impl CrawlState {
+    const ALL: [CrawlState; 8] = [CrawlState::Waiting, CrawlState::Running, CrawlState::Found, CrawlState::NoPath,
+                                  CrawlState::BudgetExhausted, CrawlState::Cancelled, CrawlState::Aborted,
+                                  CrawlState::Failed];
+
+    /// Returns the name of the state, the same as the outcome the crawl ends with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrawlState::Waiting => "waiting",
+            CrawlState::Running => "running",
+            CrawlState::Found => "found",
+            CrawlState::NoPath => "no_path",
+            CrawlState::BudgetExhausted => "budget_exhausted",
+            CrawlState::Cancelled => "cancelled",
+            CrawlState::Aborted => "aborted",
+            CrawlState::Failed => "failed",
+        }
+    }
+
+    /// Returns true once the crawl has ended
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, CrawlState::Waiting | CrawlState::Running)
+    }
+}
+
+/// A struct holding the state of a crawl at a single moment, for programs showing the progress of the crawl
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ProgressSnapshot {
+    /// The amount of articles the crawl has reached
+    pub visited: usize,
+    /// The deepest depth the crawl has expanded articles at
+    pub depth: usize,
+    /// The amount of discovered articles waiting to be expanded
+    pub frontier: usize,
+    /// The articles reached per second
+    pub rate: f64,
+    /// The estimated time until the waiting articles have been expanded at the pace the articles have been expanded
+    /// so far, at most the time left of the time limit of the crawl. None until articles have been expanded or once
+    /// the crawl has ended
+    pub eta: Option<Duration>,
+    pub elapsed: Duration,
+    pub state: CrawlState,
+}
+
+/// A struct holding the counters of a running crawl, kept up to date by the crawl without locks so that the progress
+/// can be read at any time without slowing the crawl down. Shared through an Arc, so that it can be kept around
+/// without holding on to the crawler
+#[derive(Debug, Default)]
+pub struct LiveProgress {
+    visited: AtomicUsize,
+    discovered: AtomicUsize,
+    expanded: AtomicUsize,
+    depth: AtomicUsize,
+    state: AtomicU8,
+    started: OnceLock<Instant>,
+    ended: OnceLock<Duration>,
+    time_limit: Option<Duration>,
+}
+
+impl LiveProgress {
+    /// A constructor for LiveProgress
+    ///
+    /// # Arguments
+    ///
+    /// * 'time_limit' - An option containing the time limit of the crawl, if it has one
+    ///
+    /// # Returns
+    ///
+    /// * LiveProgress - A new LiveProgress of a crawl that hasn't started
+    pub fn new(time_limit: Option<Duration>) -> LiveProgress {
+        LiveProgress { time_limit, ..LiveProgress::default() }
+    }
+
+    /// Marks the crawl as started, from which the rate and the elapsed time are counted
+    pub fn start(&self) {
+        let _ = self.started.set(Instant::now());
+        self.state.store(CrawlState::Running as u8, Ordering::Relaxed);
+    }
+
+    /// Marks the crawl as ended with the given state
+    pub fn finish(&self, state: CrawlState) {
+        let _ = self.ended.set(self.started.get().map_or(Duration::ZERO, Instant::elapsed));
+        self.state.store(state as u8, Ordering::Relaxed);
+    }
+
+    /// Sets the amount of articles the crawl has reached
+    pub fn set_visited(&self, visited: usize) {
+        self.visited.store(visited, Ordering::Relaxed);
+    }
+
+    /// Records newly discovered articles
+    pub fn record_discovered(&self, count: usize) {
+        self.discovered.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Records articles expanded at the given depth
+    pub fn record_expanded(&self, depth: usize, count: usize) {
+        self.expanded.fetch_add(count, Ordering::Relaxed);
+        self.depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Returns the current state of the crawl
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        let state = CrawlState::ALL.get(self.state.load(Ordering::Relaxed) as usize).copied()
+            .unwrap_or(CrawlState::Failed);
+        let elapsed = match (self.ended.get(), self.started.get()) {
+            (Some(ended), _) => *ended,
+            (None, Some(started)) => started.elapsed(),
+            (None, None) => Duration::ZERO,
+        };
+        let visited = self.visited.load(Ordering::Relaxed);
+        let expanded = self.expanded.load(Ordering::Relaxed);
+        let frontier = self.discovered.load(Ordering::Relaxed).saturating_sub(expanded);
+        let seconds = elapsed.as_secs_f64();
+        let rate = if seconds > 0.0 { visited as f64 / seconds } else { 0.0 };
+        let eta = (expanded > 0 && !state.is_finished()).then(|| {
+            let eta = elapsed.mul_f64(frontier as f64 / expanded as f64);
+            self.time_limit.map_or(eta, |limit| eta.min(limit.saturating_sub(elapsed)))
+        });
+        ProgressSnapshot { visited, depth: self.depth.load(Ordering::Relaxed), frontier, rate, eta, elapsed, state }
+    }
+}
+
+/// A writer of machine readable progress frames, one JSON object per line, for programs wrapping the crawler
+pub struct ProgressWriter {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl ProgressWriter {
+    /// A constructor for ProgressWriter
+    ///
+    /// # Arguments
+    ///
+    /// * 'target' - A string slice with the path of the file or named pipe to write into, or "-" for stderr
+    ///
+    /// # Returns
+    ///
+    /// * Result<ProgressWriter, Box<dyn Error>> - A new ProgressWriter, or an error if the target couldn't be opened
+    pub fn open(target: &str) -> Result<ProgressWriter, Box<dyn Error>> {
+        let writer: Box<dyn Write + Send> = if target == STDERR_TARGET {
+            Box::new(io::stderr())
+        } else {
+            // Appending instead of truncating, as truncating a named pipe fails on some systems
+            Box::new(OpenOptions::new().create(true).append(true).open(target)?)
+        };
+        Ok(ProgressWriter { writer: Mutex::new(writer) })
+    }
+
+    /// A function for writing a single progress frame. Every frame is flushed right away, so that the reading
+    /// program sees it as soon as possible
+    ///
+    /// # Arguments
+    ///
+    /// * 'frame' - A reference to the frame to write, see progress_frame
+    /// * 'errors' - A reference to the ErrorReporter a failed write is reported with
+    pub fn write_frame(&self, frame: &serde_json::Value, errors: &ErrorReporter) {
+        let mut writer = match self.writer.lock() {
+            Ok(writer) => writer,
+            Err(error) => {
+                errors.report(format!("Error acquiring lock for progress output:\n{:?}", error));
+                return;
+            },
+        };
+        if let Err(error) = writeln!(writer, "{}", frame).and_then(|_| writer.flush()) {
+            errors.report(format!("Error writing progress frame:\n{:?}", error));
+        }
+    }
+}
+
+/// A function for constructing a progress frame out of the current state of a crawl
+///
+/// # Arguments
+///
+/// * 'stats' - A reference to the statistics of the crawl so far
+/// * 'visited' - The amount of articles the crawl has reached
+/// * 'elapsed' - The Duration the crawl has been running for
+/// * 'finished' - Whether the crawl has found the goal
+/// * 'back_pressure' - A reference to the current BackPressure of the crawl
+///
+/// # Returns
+///
+/// * serde_json::Value - The frame, with the visited count, the deepest expanded depth, the amount of discovered
+///   articles waiting to be expanded, the rate of discovery per second and the state of the batch channel
+pub fn progress_frame(stats: &CrawlStats, visited: usize, elapsed: Duration, finished: bool,
+                      back_pressure: &BackPressure) -> serde_json::Value {
+    let expanded: usize = stats.depths.iter().map(|depth| depth.expanded).sum();
+    let depth = stats.depths.iter().rposition(|depth| depth.expanded > 0).unwrap_or(0);
+    let seconds = elapsed.as_secs_f64();
+    let rate = if seconds > 0.0 { visited as f64 / seconds } else { 0.0 };
+    json!({
+        "visited": visited,
+        "depth": depth,
+        "frontier": stats.total_discovered().saturating_sub(expanded),
+        "api_calls": stats.total_api_calls(),
+        "rate": (rate * 10.0).round() / 10.0,
+        "elapsed_seconds": (seconds * 10.0).round() / 10.0,
+        "finished": finished,
+        "queued_batches": back_pressure.queued_batches,
+        "blocked_senders": back_pressure.blocked_senders,
+        "bound": back_pressure.bound(),
+    })
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::article_title::ArticleTitle;
+
+/// A struct telling where in a crawl a batch of links was fetched: the how manyth batch the main thread expanded and
+/// the how manyth API call of the crawl the fetch had been completed with
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Discovery {
+    pub batch: usize,
+    pub api_call: usize,
+}
+
+/// A struct holding how a single article was reached
+#[derive(Clone, PartialEq, Debug)]
+pub struct Provenance {
+    /// The article the link was found from, or None for the article the crawl started from
+    pub parent: Option<ArticleTitle>,
+    pub depth: usize,
+    pub discovery: Discovery,
+}
+
+/// A map of every article a crawl reached to how it was reached, for answering afterwards why the crawl went the way
+/// it did. Kept only if asked for, as it holds the title of every reached article twice
+#[derive(Default)]
+pub struct ProvenanceMap {
+    articles: Mutex<HashMap<ArticleTitle, Provenance>>,
+}
+
+impl ProvenanceMap {
+    /// A function for recording the links claimed from an article. An article already in the map keeps the way it
+    /// was first reached
+    ///
+    /// # Arguments
+    ///
+    /// * 'parent' - An option containing the title of the article the links were found from, or None for the start
+    ///   of the crawl
+    /// * 'links' - A slice of the titles of the claimed links
+    /// * 'depth' - The depth of the links
+    /// * 'discovery' - The Discovery of the batch the links were fetched in
+    pub fn record(&self, parent: Option<&ArticleTitle>, links: &[ArticleTitle], depth: usize, discovery: Discovery) {
+        if links.is_empty() {
+            return;
+        }
+        let mut articles = match self.articles.lock() {
+            Ok(articles) => articles,
+            Err(error) => {
+                eprintln!("Error acquiring lock for crawl provenance:\n{:?}", error);
+                return;
+            },
+        };
+        for link in links.iter() {
+            articles.entry(link.clone())
+                .or_insert_with(|| Provenance { parent: parent.cloned(), depth, discovery });
+        }
+    }
+
+    /// Returns the amount of articles in the map
+    pub fn len(&self) -> usize {
+        self.articles.lock().map_or(0, |articles| articles.len())
+    }
+
+    /// Returns true if the map has no articles
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A function for following the parents of an article back to the start of the crawl. An article is always
+    /// recorded before the links found from it, so the parents lead to the start without loops
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    ///
+    /// # Returns
+    ///
+    /// * Option<Vec<(ArticleTitle, Provenance)>> - The articles from the start of the crawl to the given article
+    ///   along with how each was reached, or None if the crawl never reached the article
+    pub fn chain(&self, title: &ArticleTitle) -> Option<Vec<(ArticleTitle, Provenance)>> {
+        let articles = self.articles.lock().ok()?;
+        let mut chain = vec!();
+        let mut current = Some(title.clone());
+        while let Some(article) = current {
+            let provenance = articles.get(&article)?.clone();
+            current = provenance.parent.clone();
+            chain.push((article, provenance));
+        }
+        chain.reverse();
+        Some(chain)
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot;
+
+use super::crawler::ErrorReporter;
+
+/// A token bucket limiting the rate of the requests of an API session. Every clone of a WikiClient shares the same
+/// limiter, so the limit holds for all of the crawls and other operations using the session at once
+///
+/// Each crawl takes its requests from a lane of its own. When requests have to wait for tokens, the lanes take turns
+/// in getting them, so a crawl sending many requests at once can't starve the others
+pub struct RateLimiter {
+    // The time it takes to gain a single token
+    interval: Duration,
+    burst: f64,
+    state: Arc<Mutex<LimiterState>>,
+}
+
+/// The mutable state of a RateLimiter
+struct LimiterState {
+    tokens: f64,
+    refilled: Instant,
+    // The lanes with waiting requests in the order they get their next token in, each with its waiting requests
+    lanes: VecDeque<(usize, VecDeque<oneshot::Sender<()>>)>,
+    dispatching: bool,
+}
+
+impl LimiterState {
+    /// Adds the tokens gained since the last refill, up to the burst of the limiter
+    fn refill(&mut self, interval: Duration, burst: f64) {
+        let now = Instant::now();
+        let gained = now.duration_since(self.refilled).as_secs_f64() / interval.as_secs_f64();
+        self.tokens = (self.tokens + gained).min(burst);
+        self.refilled = now;
+    }
+
+    /// Hands a token to the first waiting request of the next lane, moving the lane to the back of the turns if it
+    /// still has requests waiting. Requests that stopped waiting are skipped without using up the token
+    fn hand_out(&mut self) {
+        while let Some((lane, mut waiting)) = self.lanes.pop_front() {
+            let mut handed = false;
+            while let Some(request) = waiting.pop_front() {
+                if request.send(()).is_ok() {
+                    handed = true;
+                    break;
+                }
+            }
+            if !waiting.is_empty() {
+                self.lanes.push_back((lane, waiting));
+            }
+            if handed {
+                self.tokens -= 1.0;
+                return;
+            }
+        }
+    }
+}
+
+impl RateLimiter {
+    /// A constructor for RateLimiter
+    ///
+    /// # Arguments
+    ///
+    /// * 'per_second' - The amount of requests allowed per second on average, more than 0
+    /// * 'burst' - The amount of requests that can be sent at once after a pause, at least 1
+    ///
+    /// # Returns
+    ///
+    /// * RateLimiter - A new limiter with a full bucket
+    pub fn new(per_second: f64, burst: u32) -> RateLimiter {
+        let burst = f64::from(burst.max(1));
+        RateLimiter {
+            interval: Duration::from_secs_f64(1.0 / per_second),
+            burst,
+            state: Arc::new(Mutex::new(LimiterState {
+                tokens: burst,
+                refilled: Instant::now(),
+                lanes: VecDeque::new(),
+                dispatching: false,
+            })),
+        }
+    }
+
+    /// An async function for waiting until a request of the given lane may be sent
+    ///
+    /// # Arguments
+    ///
+    /// * 'lane' - The id of the lane the request is sent from, see WikiClient::for_crawl
+    /// * 'errors' - A reference to the ErrorReporter of the client sending the request
+    pub async fn acquire(&self, lane: usize, errors: &ErrorReporter) {
+        let (sender, reciever) = oneshot::channel();
+        {
+            let mut state = match self.state.lock() {
+                Ok(state) => state,
+                Err(error) => {
+                    errors.report(format!("Error acquiring lock for the rate limiter:\n{:?}", error));
+                    return;
+                },
+            };
+            state.refill(self.interval, self.burst);
+            // Requests only skip the turns if nobody is waiting, so that a lane can't cut in front of the others
+            if state.lanes.is_empty() && state.tokens >= 1.0 {
+                state.tokens -= 1.0;
+                return;
+            }
+            match state.lanes.iter_mut().find(|(waiting_lane, _)| *waiting_lane == lane) {
+                Some((_, waiting)) => waiting.push_back(sender),
+                None => state.lanes.push_back((lane, VecDeque::from(vec!(sender)))),
+            }
+            if !state.dispatching {
+                state.dispatching = true;
+                tokio::spawn(dispatch(Arc::clone(&self.state), self.interval, self.burst, errors.clone()));
+            }
+        }
+        let _ = reciever.await;
+    }
+}
+
+/// An async function for handing out tokens to the waiting requests as they are gained, until nobody is waiting
+///
+/// # Arguments
+///
+/// * 'state' - An arc housing the state of the limiter
+/// * 'interval' - The time it takes to gain a single token
+/// * 'burst' - The largest amount of tokens the bucket holds
+/// * 'errors' - The ErrorReporter of the client whose request started the dispatching
+async fn dispatch(state: Arc<Mutex<LimiterState>>, interval: Duration, burst: f64, errors: ErrorReporter) {
+    loop {
+        let wait = {
+            let mut state = match state.lock() {
+                Ok(state) => state,
+                Err(error) => {
+                    errors.report(format!("Error acquiring lock for the rate limiter:\n{:?}", error));
+                    return;
+                },
+            };
+            state.refill(interval, burst);
+            while state.tokens >= 1.0 && !state.lanes.is_empty() {
+                state.hand_out();
+            }
+            if state.lanes.is_empty() {
+                state.dispatching = false;
+                return;
+            }
+            interval.mul_f64(1.0 - state.tokens)
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
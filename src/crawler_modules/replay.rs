@@ -0,0 +1,263 @@
+use std::error::Error;
+use std::fs;
+use std::io::{self, stdout, Write};
+use std::thread;
+use std::time::Duration;
+
+use serde_json;
+
+use super::display;
+
+/// An enum representing a single frame of a progress log written with --progress-json
+#[derive(Clone, PartialEq, Debug)]
+pub enum ReplayEvent {
+    /// The start of a crawl along with its articles
+    Start { origin: String, goal: String, direction: String },
+    /// The state of a running crawl, written every two seconds
+    Progress(ProgressState),
+    /// A path found by a crawl collecting several shortest paths
+    Path(Vec<String>),
+    /// The end of a crawl along with how it ended and the found path
+    End { outcome: String, path: Option<Vec<String>> },
+}
+
+/// A struct holding the state of a crawl a progress frame recorded
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ProgressState {
+    pub visited: u64,
+    pub depth: u64,
+    pub frontier: u64,
+    pub api_calls: u64,
+    pub rate: f64,
+    pub elapsed_seconds: f64,
+    pub finished: bool,
+    pub queued_batches: u64,
+    pub bound: Option<String>,
+}
+
+/// A struct holding the frames of a single recorded crawl
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RecordedCrawl {
+    pub events: Vec<ReplayEvent>,
+}
+
+impl RecordedCrawl {
+    /// Returns the origin, the goal and the direction of the crawl, if they were recorded
+    pub fn articles(&self) -> Option<(&str, &str, &str)> {
+        self.events.iter().find_map(|event| match event {
+            ReplayEvent::Start { origin, goal, direction } => {
+                Some((origin.as_str(), goal.as_str(), direction.as_str()))
+            },
+            _ => None,
+        })
+    }
+
+    /// Returns the progress frames of the crawl in the order they were written
+    pub fn progress(&self) -> impl Iterator<Item = &ProgressState> {
+        self.events.iter().filter_map(|event| match event {
+            ReplayEvent::Progress(state) => Some(state),
+            _ => None,
+        })
+    }
+
+    /// Returns how the crawl ended and the found path, if the end of the crawl was recorded
+    pub fn end(&self) -> Option<(&str, Option<&Vec<String>>)> {
+        self.events.iter().find_map(|event| match event {
+            ReplayEvent::End { outcome, path } => Some((outcome.as_str(), path.as_ref())),
+            _ => None,
+        })
+    }
+}
+
+/// A function for reading the crawls out of a progress log written with --progress-json. The log is appended to by
+/// every crawl, so a new crawl starts at each start frame. Older logs have no start frames, in which case a new
+/// crawl starts whenever the elapsed time goes back. Lines that aren't frames are skipped
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the progress log
+///
+/// # Returns
+///
+/// * Result<Vec<RecordedCrawl>, Box<dyn Error>> - The recorded crawls, or an error if the file couldn't be read or
+///   had no frames
+pub fn load_crawls(file: &str) -> Result<Vec<RecordedCrawl>, Box<dyn Error>> {
+    let contents = fs::read_to_string(file)?;
+    let mut crawls: Vec<RecordedCrawl> = vec!();
+    let mut last_elapsed = 0.0;
+    for line in contents.lines() {
+        let event = match serde_json::from_str::<serde_json::Value>(line).ok().as_ref().and_then(parse_event) {
+            Some(event) => event,
+            None => continue,
+        };
+        let elapsed = match &event {
+            ReplayEvent::Progress(state) => Some(state.elapsed_seconds),
+            _ => None,
+        };
+        let starts_crawl = match crawls.last() {
+            Some(crawl) => matches!(event, ReplayEvent::Start { .. })
+                || (crawl.articles().is_none() && elapsed.is_some_and(|elapsed| elapsed < last_elapsed)),
+            None => true,
+        };
+        if starts_crawl {
+            crawls.push(RecordedCrawl::default());
+        }
+        if let Some(elapsed) = elapsed {
+            last_elapsed = elapsed;
+        }
+        if let Some(crawl) = crawls.last_mut() {
+            crawl.events.push(event);
+        }
+    }
+
+    if crawls.is_empty() {
+        return Err(Box::new(io::Error::new(io::ErrorKind::InvalidData,
+                                           format!("'{}' doesn't contain any progress frames", file))));
+    }
+    Ok(crawls)
+}
+
+/// A function for parsing a single line of a progress log into an event
+///
+/// # Arguments
+///
+/// * 'frame' - A reference to the JSON object of the line
+///
+/// # Returns
+///
+/// * Option<ReplayEvent> - The event, or None if the object isn't a frame
+fn parse_event(frame: &serde_json::Value) -> Option<ReplayEvent> {
+    let titles = |value: &serde_json::Value| -> Option<Vec<String>> {
+        Some(value.as_array()?.iter().filter_map(|title| title.as_str().map(String::from)).collect())
+    };
+    if let (Some(origin), Some(goal)) = (frame["origin"].as_str(), frame["goal"].as_str()) {
+        let direction = frame["direction"].as_str().unwrap_or("outgoing").to_string();
+        return Some(ReplayEvent::Start { origin: origin.to_string(), goal: goal.to_string(), direction });
+    }
+    if let Some(outcome) = frame["outcome"].as_str() {
+        return Some(ReplayEvent::End { outcome: outcome.to_string(), path: titles(&frame["path"]) });
+    }
+    if frame["length"].is_u64() {
+        return titles(&frame["path"]).map(ReplayEvent::Path);
+    }
+
+    let count = |name: &str| frame[name].as_u64().unwrap_or(0);
+    Some(ReplayEvent::Progress(ProgressState {
+        visited: frame["visited"].as_u64()?,
+        depth: count("depth"),
+        frontier: count("frontier"),
+        api_calls: count("api_calls"),
+        rate: frame["rate"].as_f64().unwrap_or(0.0),
+        elapsed_seconds: frame["elapsed_seconds"].as_f64().unwrap_or(0.0),
+        finished: frame["finished"].as_bool().unwrap_or(false),
+        queued_batches: count("queued_batches"),
+        bound: frame["bound"].as_str().map(String::from),
+    }))
+}
+
+/// A function for drawing the recorded crawls again on the status line, waiting between the frames for as long as
+/// the crawl did, divided by the speed
+///
+/// # Arguments
+///
+/// * 'crawls' - A slice of the recorded crawls
+/// * 'speed' - How many times faster than recorded the crawls are replayed, more than 0
+pub fn replay(crawls: &[RecordedCrawl], speed: f64) {
+    for (index, crawl) in crawls.iter().enumerate() {
+        println!("\n{}", crawl_heading(index, crawl));
+        let mut elapsed = 0.0;
+        let mut line_width = 0;
+        for event in crawl.events.iter() {
+            match event {
+                ReplayEvent::Start { .. } => (),
+                ReplayEvent::Progress(state) => {
+                    let wait = (state.elapsed_seconds - elapsed).max(0.0) / speed;
+                    thread::sleep(Duration::from_secs_f64(wait));
+                    elapsed = state.elapsed_seconds;
+                    // The status can get shorter as the queue drains, so the line is padded over the longest one
+                    let terminal_width = display::terminal_width().saturating_sub(1);
+                    let line = display::truncate_to_width(&status_line(state), terminal_width);
+                    line_width = line_width.max(display::text_width(&line)).min(terminal_width);
+                    print!("\r{}", display::pad_to_width(&line, line_width));
+                    let _ = stdout().flush();
+                },
+                ReplayEvent::Path(path) => println!("\nFound path ({} links): {}", path.len().saturating_sub(1),
+                                                    path.join(" -> ")),
+                ReplayEvent::End { outcome, path } => {
+                    println!();
+                    print_end(outcome, path.as_ref());
+                },
+            }
+        }
+        if crawl.end().is_none() {
+            println!("\nThe log ends before the crawl did.");
+        }
+    }
+}
+
+/// A function for summarizing the recorded crawls without replaying them
+///
+/// # Arguments
+///
+/// * 'crawls' - A slice of the recorded crawls
+pub fn print_summary(crawls: &[RecordedCrawl]) {
+    for (index, crawl) in crawls.iter().enumerate() {
+        println!("\n{}", crawl_heading(index, crawl));
+        let last = crawl.progress().last().cloned().unwrap_or_default();
+        let peak_frontier = crawl.progress().map(|state| state.frontier).max().unwrap_or(0);
+        let api_bound = crawl.progress().filter(|state| state.bound.as_deref() == Some("api")).count();
+        let frames = crawl.progress().count();
+
+        println!("{:<20}{:.1} seconds", "Duration", last.elapsed_seconds);
+        println!("{:<20}{}", "Articles analyzed", last.visited);
+        println!("{:<20}{}", "Deepest depth", last.depth);
+        println!("{:<20}{}", "API calls", last.api_calls);
+        println!("{:<20}{}", "Largest frontier", peak_frontier);
+        println!("{:<20}{:.1} articles per second", "Average rate", last.rate);
+        if let Some(percentage) = (api_bound * 100).checked_div(frames) {
+            println!("{:<20}{}% of the frames", "API-bound", percentage);
+        }
+        match crawl.end() {
+            Some((outcome, path)) => print_end(outcome, path),
+            None => println!("The log ends before the crawl did."),
+        }
+    }
+}
+
+/// Returns the heading of a recorded crawl, with its articles if they were recorded
+fn crawl_heading(index: usize, crawl: &RecordedCrawl) -> String {
+    match crawl.articles() {
+        Some((origin, goal, direction)) => format!("Crawl {}: from '{}' to '{}' following {} links", index + 1,
+                                                   origin, goal, direction),
+        None => format!("Crawl {}:", index + 1),
+    }
+}
+
+/// Returns the status line drawn for a progress frame
+fn status_line(state: &ProgressState) -> String {
+    let mut line = format!("[{:>6.1}s] Analyzed {} articles, depth {}, {} in the frontier, {} API calls",
+                           state.elapsed_seconds, state.visited, state.depth, state.frontier, state.api_calls);
+    match state.bound.as_deref() {
+        Some("api") => line.push_str(&format!(" [{} batches queued, API-bound]", state.queued_batches)),
+        Some(_) => line.push_str(" [processing-bound]"),
+        None => (),
+    }
+    line
+}
+
+/// A function for printing how a recorded crawl ended
+///
+/// # Arguments
+///
+/// * 'outcome' - A string slice naming how the crawl ended, such as found or cancelled
+/// * 'path' - An option containing the found path, if the crawl found one
+fn print_end(outcome: &str, path: Option<&Vec<String>>) {
+    match (outcome, path) {
+        ("found", Some(path)) => println!("Found a path of {} links: {}", path.len().saturating_sub(1),
+                                          path.join(" -> ")),
+        ("no_path", _) => println!("The crawl found no path."),
+        ("budget_exhausted", _) => println!("The crawl budget ran out before a path was found."),
+        ("cancelled", _) => println!("The crawl was cancelled before a path was found."),
+        _ => println!("The crawl failed."),
+    }
+}
@@ -0,0 +1,199 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+
+use serde_json;
+
+/// A struct pairing up the reports of the same crawl from two report files
+pub struct CrawlDiff {
+    pub origin: String,
+    pub goal: String,
+    pub direction: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// A function for reading the crawl reports out of a file written with --json. Files with a single crawl and files
+/// with several crawls, such as the ones written by compare, are both supported
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the report file
+///
+/// # Returns
+///
+/// * Result<Vec<serde_json::Value>, Box<dyn Error>> - The crawl reports of the file, or an error if the file couldn't
+///   be read or had no crawl reports
+pub fn load_reports(file: &str) -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
+    let contents = fs::read_to_string(file)?;
+    let reports = match serde_json::from_str::<serde_json::Value>(&contents)? {
+        serde_json::Value::Array(reports) => reports,
+        report => vec!(report),
+    };
+
+    let reports: Vec<serde_json::Value> = reports.into_iter()
+        .filter(|report| report["statistics"].is_object())
+        .collect();
+    if reports.is_empty() {
+        return Err(Box::new(io::Error::new(io::ErrorKind::InvalidData,
+                                           format!("'{}' doesn't contain any crawl reports", file))));
+    }
+    Ok(reports)
+}
+
+/// A function for pairing up the crawls of two report files by their origin, goal and direction
+///
+/// # Arguments
+///
+/// * 'before' - A Vec of the reports of the earlier run
+/// * 'after' - A Vec of the reports of the later run
+///
+/// # Returns
+///
+/// * Vec<CrawlDiff> - The paired crawls, in the order they appear in the files
+pub fn pair_reports(before: Vec<serde_json::Value>, after: Vec<serde_json::Value>) -> Vec<CrawlDiff> {
+    let key = |report: &serde_json::Value| -> (String, String, String) {
+        let field = |name: &str| report[name].as_str().unwrap_or_default().to_string();
+        (field("origin"), field("goal"), field("direction"))
+    };
+
+    let mut after: Vec<Option<serde_json::Value>> = after.into_iter().map(Some).collect();
+    let mut diffs = vec!();
+    for report in before {
+        let report_key = key(&report);
+        let matching = after.iter_mut()
+            .find(|other| other.as_ref().is_some_and(|other| key(other) == report_key))
+            .and_then(Option::take);
+        let (origin, goal, direction) = report_key;
+        diffs.push(CrawlDiff { origin, goal, direction, before: Some(report), after: matching });
+    }
+    for report in after.into_iter().flatten() {
+        let (origin, goal, direction) = key(&report);
+        diffs.push(CrawlDiff { origin, goal, direction, before: None, after: Some(report) });
+    }
+    diffs
+}
+
+/// A function for printing what changed between two runs of the same crawls
+///
+/// # Arguments
+///
+/// * 'diffs' - A slice of the paired crawls, see pair_reports
+pub fn print_diff(diffs: &[CrawlDiff]) {
+    for diff in diffs.iter() {
+        println!("\nThe {} crawl from '{}' to '{}':", diff.direction, diff.origin, diff.goal);
+        let (before, after) = match (&diff.before, &diff.after) {
+            (Some(before), Some(after)) => (before, after),
+            (Some(_), None) => {
+                println!("Only in the first file.");
+                continue;
+            },
+            (None, _) => {
+                println!("Only in the second file.");
+                continue;
+            },
+        };
+
+        print_path_change(&before["path"], &after["path"]);
+
+        let (before, after) = (&before["statistics"], &after["statistics"]);
+        println!("{:<14}{:<12}{:<12}Change", "", "First", "Second");
+        print_change("Discovered", &before["discovered"], &after["discovered"], 0);
+        print_change("API calls", &before["api_calls"], &after["api_calls"], 0);
+        print_change("Seconds", &before["elapsed_seconds"], &after["elapsed_seconds"], 1);
+
+        let empty = vec!();
+        let before_depths = before["depths"].as_array().unwrap_or(&empty);
+        let after_depths = after["depths"].as_array().unwrap_or(&empty);
+        println!("\n{:<8}{:<24}API calls", "Depth", "Discovered");
+        for depth in 0..before_depths.len().max(after_depths.len()) {
+            let value = |depths: &Vec<serde_json::Value>, name: &str| match depths.get(depth) {
+                Some(stats) => stats[name].to_string(),
+                None => String::from("-"),
+            };
+            let discovered = format!("{} -> {}", value(before_depths, "discovered"), value(after_depths, "discovered"));
+            let api_calls = format!("{} -> {}", value(before_depths, "api_calls"), value(after_depths, "api_calls"));
+            println!("{:<8}{:<24}{}", depth, discovered, api_calls);
+        }
+
+        print_warning_changes(&before["warnings"], &after["warnings"]);
+    }
+}
+
+/// A function for printing how the found path of a crawl changed
+///
+/// # Arguments
+///
+/// * 'before' - A reference to the path of the earlier run, null if no path was found
+/// * 'after' - A reference to the path of the later run, null if no path was found
+fn print_path_change(before: &serde_json::Value, after: &serde_json::Value) {
+    let format = |path: &serde_json::Value| -> Option<(usize, String)> {
+        let titles: Vec<&str> = path.as_array()?.iter().filter_map(serde_json::Value::as_str).collect();
+        Some((titles.len().saturating_sub(1), titles.join(" -> ")))
+    };
+
+    match (format(before), format(after)) {
+        (Some(before), Some(after)) if before == after => {
+            println!("Path unchanged ({} links): {}", before.0, before.1);
+        },
+        (Some(before), Some(after)) => {
+            if before.0 == after.0 {
+                println!("Path rerouted, still {} links:", after.0);
+            } else {
+                println!("Path changed from {} to {} links:", before.0, after.0);
+            }
+            println!("  First:  {}", before.1);
+            println!("  Second: {}", after.1);
+        },
+        (Some(before), None) => println!("The first run found a path ({} links), the second didn't: {}",
+                                         before.0, before.1),
+        (None, Some(after)) => println!("The second run found a path ({} links), the first didn't: {}",
+                                        after.0, after.1),
+        (None, None) => println!("Neither run found a path."),
+    }
+}
+
+/// A function for printing a single statistic of both runs and the relative change between them
+///
+/// # Arguments
+///
+/// * 'name' - A string slice with the name of the statistic
+/// * 'before' - A reference to the value of the earlier run
+/// * 'after' - A reference to the value of the later run
+/// * 'decimals' - The amount of decimals to print the values with
+fn print_change(name: &str, before: &serde_json::Value, after: &serde_json::Value, decimals: usize) {
+    let (before, after) = match (before.as_f64(), after.as_f64()) {
+        (Some(before), Some(after)) => (before, after),
+        _ => return,
+    };
+    let change = if before == 0.0 {
+        String::from("-")
+    } else {
+        format!("{:+.1}%", (after - before) * 100.0 / before)
+    };
+    let format = |value: f64| format!("{:.*}", decimals, value);
+    println!("{:<14}{:<12}{:<12}{}", name, format(before), format(after), change);
+}
+
+/// A function for printing the API warnings that appeared or disappeared between the runs
+///
+/// # Arguments
+///
+/// * 'before' - A reference to the warnings of the earlier run
+/// * 'after' - A reference to the warnings of the later run
+fn print_warning_changes(before: &serde_json::Value, after: &serde_json::Value) {
+    let messages = |warnings: &serde_json::Value| -> Vec<String> {
+        warnings.as_array().map_or(vec!(), |warnings| warnings.iter()
+            .map(|warning| format!("{}: {}", warning["module"].as_str().unwrap_or_default(),
+                                   warning["message"].as_str().unwrap_or_default()))
+            .collect())
+    };
+    let (before, after) = (messages(before), messages(after));
+
+    for message in after.iter().filter(|message| !before.contains(message)) {
+        println!("New warning: {}", message);
+    }
+    for message in before.iter().filter(|message| !after.contains(message)) {
+        println!("Warning gone: {}", message);
+    }
+}
@@ -0,0 +1,156 @@
+use std::env;
+use std::error::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac, NewMac};
+use mediawiki;
+use sha2::{Digest, Sha256};
+
+use super::output_sink::{OutputSink, SinkFuture};
+use super::wiki_api;
+
+// The characters kept as they are when encoding the key of an object into the path of a request
+const UNRESERVED: &[u8] = b"-._~/";
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// A sink uploading each output as an object into S3 compatible storage, such as AWS S3 or MinIO. The object is
+/// written with a PUT request signed with AWS Signature Version 4, addressed by path so that it works with the
+/// storages that don't serve the buckets from subdomains
+///
+/// The storage is configured through the environment: the credentials with AWS_ACCESS_KEY_ID,
+/// AWS_SECRET_ACCESS_KEY and the optional AWS_SESSION_TOKEN, the region with AWS_REGION (default us-east-1) and
+/// the url of the storage with S3_ENDPOINT (default the AWS S3 endpoint of the region)
+pub struct S3Sink {
+    endpoint: String,
+    host: String,
+    region: String,
+    bucket: String,
+    key: String,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+    client: mediawiki::reqwest::Client,
+}
+
+impl S3Sink {
+    /// A constructor for S3Sink reading the storage from the environment
+    ///
+    /// # Arguments
+    ///
+    /// * 'location' - A string slice with the bucket and the key of the object, such as reports/crawl.json
+    ///
+    /// # Returns
+    ///
+    /// * Result<S3Sink, Box<dyn Error>> - A new S3Sink, or an error if the location is malformed or the credentials
+    ///   are missing
+    pub fn from_env(location: &str) -> Result<S3Sink, Box<dyn Error>> {
+        let (bucket, key) = match location.split_once('/') {
+            Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => (bucket, key),
+            _ => return Err(format!("Invalid S3 location 's3://{}', expected s3://bucket/key", location).into()),
+        };
+        let variable = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        let (access_key, secret_key) = match (variable("AWS_ACCESS_KEY_ID"), variable("AWS_SECRET_ACCESS_KEY")) {
+            (Some(access_key), Some(secret_key)) => (access_key, secret_key),
+            _ => return Err("Writing to S3 needs AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY to be set".into()),
+        };
+        let region = variable("AWS_REGION").unwrap_or_else(|| DEFAULT_REGION.to_string());
+        let endpoint = variable("S3_ENDPOINT")
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region))
+            .trim_end_matches('/')
+            .to_string();
+        let host = match endpoint.split_once("://") {
+            Some((_, rest)) => rest.split('/').next().unwrap_or_default().to_string(),
+            None => return Err(format!("Invalid S3_ENDPOINT '{}', expected an url such as https://host", endpoint)
+                .into()),
+        };
+        Ok(S3Sink { endpoint, host, region, bucket: bucket.to_string(), key: key.to_string(), access_key, secret_key,
+                    session_token: variable("AWS_SESSION_TOKEN"), client: mediawiki::reqwest::Client::new() })
+    }
+
+    /// A function for signing an upload with AWS Signature Version 4
+    ///
+    /// # Arguments
+    ///
+    /// * 'path' - A string slice with the encoded path of the object
+    /// * 'payload_hash' - A string slice with the hex encoded SHA-256 hash of the uploaded content
+    /// * 'timestamp' - A string slice with the time of the request, such as 20210304T050607Z
+    ///
+    /// # Returns
+    ///
+    /// * String - The value of the Authorization header
+    fn authorization(&self, path: &str, payload_hash: &str, timestamp: &str) -> String {
+        let mut headers = vec!(("host", self.host.as_str()), ("x-amz-content-sha256", payload_hash),
+                               ("x-amz-date", timestamp));
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.as_str()));
+        }
+        let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+        let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<&str>>().join(";");
+        let canonical_request = format!("PUT\n{}\n\n{}\n{}\n{}", path, canonical_headers, signed_headers,
+                                        payload_hash);
+
+        let date = &timestamp[..8];
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", timestamp, scope,
+                                     hex(&Sha256::digest(canonical_request.as_bytes())));
+        let mut signing_key = format!("AWS4{}", self.secret_key).into_bytes();
+        for part in [date, self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac(&signing_key, part.as_bytes());
+        }
+        format!("AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}", self.access_key, scope,
+                signed_headers, hex(&hmac(&signing_key, string_to_sign.as_bytes())))
+    }
+}
+
+impl OutputSink for S3Sink {
+    fn write<'a>(&'a self, content: &'a str, content_type: &'a str) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let path = format!("/{}/{}", self.bucket, encode_key(&self.key));
+            let payload_hash = hex(&Sha256::digest(content.as_bytes()));
+            let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+            // The timestamp of the API without its separators, such as 20210304T050607Z
+            let timestamp = wiki_api::format_timestamp(seconds as i64).replace(['-', ':'], "");
+
+            let mut request = self.client.put(format!("{}{}", self.endpoint, path))
+                .header("Authorization", self.authorization(&path, &payload_hash, &timestamp))
+                .header("x-amz-content-sha256", payload_hash.as_str())
+                .header("x-amz-date", timestamp.as_str())
+                .header("Content-Type", content_type);
+            if let Some(token) = &self.session_token {
+                request = request.header("x-amz-security-token", token.as_str());
+            }
+            let response = request.body(content.to_string()).send().await?;
+            if !response.status().is_success() {
+                return Err(format!("The storage answered with status {}: {}", response.status().as_u16(),
+                                   response.text().await.unwrap_or_default()).into());
+            }
+            Ok(())
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("'s3://{}/{}'", self.bucket, self.key)
+    }
+}
+
+/// Returns the HMAC-SHA256 of the data with the given key
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_varkey(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Returns the bytes as lowercase hex
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Returns the key of an object percent encoded for the path of a request, keeping the slashes between its parts
+fn encode_key(key: &str) -> String {
+    key.bytes()
+        .map(|byte| match byte {
+            byte if byte.is_ascii_alphanumeric() || UNRESERVED.contains(&byte) => (byte as char).to_string(),
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
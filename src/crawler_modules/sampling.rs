@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::article_title::ArticleTitle;
+
+// The seed of the samples of deterministic crawls, so that the same links always produce the same sample
+const DETERMINISTIC_SEED: u64 = 0;
+
+/// A struct drawing the random sample of the articles a crawl estimating the distance expands. Every article found
+/// past the start of the crawl is expanded with the same chance, so that about the same fraction of each depth is
+/// expanded
+pub struct FrontierSample {
+    fraction: f64,
+    state: AtomicU64,
+}
+
+impl FrontierSample {
+    /// A constructor for FrontierSample
+    ///
+    /// # Arguments
+    ///
+    /// * 'fraction' - The chance of an article being expanded, between 0 and 1
+    /// * 'deterministic' - Whether the sample should be the same on every run, instead of being seeded with the
+    ///   current time
+    ///
+    /// # Returns
+    ///
+    /// * FrontierSample - A new FrontierSample
+    pub fn new(fraction: f64, deterministic: bool) -> FrontierSample {
+        let seed = match deterministic {
+            true => DETERMINISTIC_SEED,
+            false => SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos() as u64),
+        };
+        FrontierSample { fraction, state: AtomicU64::new(seed) }
+    }
+
+    /// Returns the chance of an article being expanded
+    pub fn fraction(&self) -> f64 {
+        self.fraction
+    }
+
+    /// A function for drawing the articles of a batch to expand
+    ///
+    /// # Arguments
+    ///
+    /// * 'batch' - A Vec of the titles of the articles in the batch
+    ///
+    /// # Returns
+    ///
+    /// * (Vec<ArticleTitle>, usize) - The drawn articles, in the same order, and the amount of the articles left out
+    pub fn draw(&self, batch: Vec<ArticleTitle>) -> (Vec<ArticleTitle>, usize) {
+        let size = batch.len();
+        let drawn: Vec<ArticleTitle> = batch.into_iter().filter(|_| self.next_fraction() < self.fraction).collect();
+        let left_out = size - drawn.len();
+        (drawn, left_out)
+    }
+
+    /// Returns the next draw of the generator as a fraction between 0 and 1, using the SplitMix64 generator, whose
+    /// state only ever grows by a constant so that it can be shared without a lock
+    fn next_fraction(&self) -> f64 {
+        const GAMMA: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut value = self.state.fetch_add(GAMMA, Ordering::Relaxed).wrapping_add(GAMMA);
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^= value >> 31;
+        (value >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// A struct holding the range the distance between two articles was estimated to be in, out of a path found by
+/// expanding only a sample of the articles
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DistanceEstimate {
+    /// The length of the found path in links, which the distance is at most
+    pub longest: usize,
+    /// The shortest length the distance is estimated to have
+    pub shortest: usize,
+    /// The chance of a single path shorter than the estimated range having been found by the sample. As articles
+    /// usually have several paths of each length between them, the actual confidence of the range is higher
+    pub confidence: f64,
+}
+
+impl DistanceEstimate {
+    /// A function for estimating the distance between two articles out of the path a sampled crawl found. The
+    /// links of the start of the crawl are always fetched, so a path of one link is never missed. A shorter path
+    /// than the found one is missed only if one of the articles on it was left out of the sample, each of them
+    /// expanded with the chance of the sample
+    ///
+    /// # Arguments
+    ///
+    /// * 'links' - The length of the found path in links
+    /// * 'fraction' - The chance each article had of being expanded
+    ///
+    /// # Returns
+    ///
+    /// * DistanceEstimate - The range the distance is estimated to be in
+    pub fn new(links: usize, fraction: f64) -> DistanceEstimate {
+        if links <= 2 {
+            return DistanceEstimate { longest: links, shortest: links, confidence: 1.0 };
+        }
+        // A path two links shorter than the found one goes through one article less than it past the start
+        DistanceEstimate { longest: links, shortest: links - 1, confidence: fraction.powi(links as i32 - 3) }
+    }
+
+    /// Returns the estimate as a sentence, such as Probably 3 to 4 links, with a confidence of at least 50%
+    pub fn describe(&self) -> String {
+        if self.shortest == self.longest {
+            return format!("Exactly {} links", self.longest);
+        }
+        if self.confidence >= 1.0 {
+            return format!("{} to {} links", self.shortest, self.longest);
+        }
+        format!("Probably {} to {} links, with a confidence of at least {:.0}%", self.shortest, self.longest,
+                self.confidence * 100.0)
+    }
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::io;
+
+use serde_json::{self, json};
+
+use super::article_title::ArticleTitle;
+use super::wiki_api;
+use super::wiki_client::WikiClient;
+
+/// An async function for building a report of the found paths in the format the Six Degrees of Wikipedia project
+/// answers its path queries with, so that its frontend can render the paths. The paths are lists of page ids, and
+/// the title, the url and the description of each page on them are given under pages. The first sentence of the
+/// introduction is used as the description if the wiki has the TextExtracts extension, and left out otherwise
+///
+/// # Arguments
+///
+/// * 'origin' - A reference to the title of the article the paths start from
+/// * 'goal' - A reference to the title of the article the paths end at
+/// * 'paths' - A slice of the found paths, empty if no path was found
+/// * 'client' - A reference to a WikiClient with a logged in api session
+///
+/// # Returns
+///
+/// * Result<serde_json::Value, Box<dyn Error>> - The report, or an error if the information of the pages couldn't
+///   be fetched
+pub async fn sdow_report(origin: &ArticleTitle, goal: &ArticleTitle, paths: &[&Vec<ArticleTitle>],
+                         client: &WikiClient) -> Result<serde_json::Value, Box<dyn Error>> {
+    let mut titles: Vec<ArticleTitle> = vec!();
+    for title in paths.iter().flat_map(|path| path.iter()) {
+        if !titles.contains(title) {
+            titles.push(title.clone());
+        }
+    }
+    let info = wiki_api::page_info(&titles, client).await?;
+    let descriptions = wiki_api::first_sentences(&titles, client).await?;
+
+    let mut pages = serde_json::Map::new();
+    for title in titles.iter() {
+        let page = match info.get(title) {
+            Some(page) => page,
+            None => return Err(Box::new(io::Error::new(io::ErrorKind::NotFound, format!(
+                "The page id of '{}' couldn't be found", title)))),
+        };
+        let mut entry = json!({ "title": title.as_str(), "url": page.url });
+        if let Some(description) = descriptions.get(title) {
+            entry["description"] = json!(description);
+        }
+        pages.insert(page.id.to_string(), entry);
+    }
+    let paths: Vec<Vec<u64>> = paths.iter()
+        .map(|path| path.iter().filter_map(|title| info.get(title).map(|page| page.id)).collect())
+        .collect();
+
+    // The titles are validated and their redirects followed before crawling, so the crawl never starts from one
+    Ok(json!({
+        "sourcePageTitle": origin.as_str(),
+        "targetPageTitle": goal.as_str(),
+        "isSourceRedirected": false,
+        "isTargetRedirected": false,
+        "paths": paths,
+        "pages": pages,
+    }))
+}
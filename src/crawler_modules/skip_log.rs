@@ -0,0 +1,97 @@
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::sync::Mutex;
+
+use serde_json::json;
+
+use super::article_title::ArticleTitle;
+use super::crawler::ErrorReporter;
+
+/// An enum representing the reason a link was dropped from the crawl
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SkipReason {
+    /// The linked article had already been reached through another article
+    AlreadyVisited,
+    /// The linked article has no version in the language given to --must-exist-in
+    MissingLanguage,
+    /// The linked article is on the list given to --block-file
+    Blocked,
+    /// The linked article isn't on the list given to --allow-file
+    NotAllowed,
+    /// The depth of the linked article already had as many articles queued as --max-frontier-per-depth allows
+    FrontierFull,
+}
+
+impl SkipReason {
+    /// Returns the name the reason is written to the skip log with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::AlreadyVisited => "already_visited",
+            SkipReason::MissingLanguage => "missing_language",
+            SkipReason::Blocked => "blocked",
+            SkipReason::NotAllowed => "not_allowed",
+            SkipReason::FrontierFull => "frontier_full",
+        }
+    }
+}
+
+/// A log of the links dropped during a crawl, written into a file as one JSON object per line
+pub struct SkipLog {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl SkipLog {
+    /// A constructor for SkipLog. New entries are appended to the file if it already exists
+    ///
+    /// # Arguments
+    ///
+    /// * 'file' - A string slice with the path of the log file
+    ///
+    /// # Returns
+    ///
+    /// * Result<SkipLog, Box<dyn Error>> - A new SkipLog instance, or an error if the file couldn't be opened
+    pub fn open(file: &str) -> Result<SkipLog, Box<dyn Error>> {
+        let file = OpenOptions::new().create(true).append(true).open(file)?;
+        Ok(SkipLog { writer: Mutex::new(BufWriter::new(file)) })
+    }
+
+    /// A function for recording a dropped link
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the dropped link
+    /// * 'reason' - The SkipReason the link was dropped for
+    /// * 'parent' - A reference to the title of the article the link was found from
+    /// * 'errors' - A reference to the ErrorReporter a failed write is reported with
+    pub fn record(&self, title: &ArticleTitle, reason: SkipReason, parent: &ArticleTitle, errors: &ErrorReporter) {
+        let entry = json!({
+            "title": title.as_str(),
+            "reason": reason.as_str(),
+            "parent": parent.as_str(),
+        });
+        let mut writer = match self.writer.lock() {
+            Ok(writer) => writer,
+            Err(error) => {
+                errors.report(format!("Error acquiring lock for skip log:\n{:?}", error));
+                return;
+            },
+        };
+        if let Err(error) = writeln!(writer, "{}", entry) {
+            errors.report(format!("Error writing to skip log:\n{:?}", error));
+        }
+    }
+
+    /// A function for writing the buffered entries into the file
+    ///
+    /// # Arguments
+    ///
+    /// * 'errors' - A reference to the ErrorReporter a failed write is reported with
+    pub fn flush(&self, errors: &ErrorReporter) {
+        if let Ok(mut writer) = self.writer.lock() {
+            if let Err(error) = writer.flush() {
+                errors.report(format!("Error writing to skip log:\n{:?}", error));
+            }
+        }
+    }
+}
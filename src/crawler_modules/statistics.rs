@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::Duration;
+
+use serde_json::{self, json};
+
+use super::api_warnings::ApiWarning;
+use super::article_title::ArticleTitle;
+use super::output_sink;
+
+/// A struct holding the statistics of a single BFS depth of a crawl
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct DepthStats {
+    pub discovered: usize,
+    pub expanded: usize,
+    pub api_calls: usize,
+    /// The amount of newly found articles left unqueued because the depth was already full
+    pub left_out: usize,
+    /// The amount of articles left unexpanded because they weren't drawn into the sample of an approximate crawl
+    pub sampled_out: usize,
+}
+
+/// A struct holding the statistics of a whole crawl, collected while crawling
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct CrawlStats {
+    pub depths: Vec<DepthStats>,
+    pub elapsed: Duration,
+    pub warnings: Vec<(ApiWarning, usize)>,
+    /// Whether the crawl ran anonymously, with the lower limits of an anonymous user, because logging in failed
+    pub degraded: bool,
+}
+
+impl CrawlStats {
+    /// Returns a mutable reference to the stats of the given depth, adding any missing depths
+    fn depth_mut(&mut self, depth: usize) -> &mut DepthStats {
+        if self.depths.len() <= depth {
+            self.depths.resize(depth + 1, DepthStats::default());
+        }
+        &mut self.depths[depth]
+    }
+
+    /// Records articles that were seen for the first time at the given depth
+    ///
+    /// # Arguments
+    ///
+    /// * 'depth' - The BFS depth the articles were found at
+    /// * 'count' - The amount of new articles
+    pub fn record_discovered(&mut self, depth: usize, count: usize) {
+        self.depth_mut(depth).discovered += count;
+    }
+
+    /// Records newly found articles that were left unqueued at the given depth because it was already full
+    ///
+    /// # Arguments
+    ///
+    /// * 'depth' - The BFS depth the articles were found at
+    /// * 'count' - The amount of left out articles
+    pub fn record_left_out(&mut self, depth: usize, count: usize) {
+        self.depth_mut(depth).left_out += count;
+    }
+
+    /// Records articles that were left unexpanded at the given depth because they weren't drawn into the sample
+    ///
+    /// # Arguments
+    ///
+    /// * 'depth' - The BFS depth of the articles
+    /// * 'count' - The amount of articles left unexpanded
+    pub fn record_sampled_out(&mut self, depth: usize, count: usize) {
+        self.depth_mut(depth).sampled_out += count;
+    }
+
+    /// Records articles whose links were fetched at the given depth
+    ///
+    /// # Arguments
+    ///
+    /// * 'depth' - The BFS depth of the expanded articles
+    /// * 'count' - The amount of expanded articles
+    /// * 'api_calls' - The amount of API requests the expansion took
+    pub fn record_expanded(&mut self, depth: usize, count: usize, api_calls: usize) {
+        let depth_stats = self.depth_mut(depth);
+        depth_stats.expanded += count;
+        depth_stats.api_calls += api_calls;
+    }
+
+    /// Records the API warnings returned during the crawl
+    ///
+    /// # Arguments
+    ///
+    /// * 'before' - A reference to the warning counts of the client before the crawl
+    /// * 'after' - A reference to the warning counts of the client after the crawl
+    pub fn record_warnings(&mut self, before: &HashMap<ApiWarning, usize>, after: &HashMap<ApiWarning, usize>) {
+        self.warnings = after.iter()
+            .map(|(warning, count)| (warning.clone(), count - before.get(warning).copied().unwrap_or(0)))
+            .filter(|(_, count)| *count > 0)
+            .collect();
+        self.warnings.sort();
+    }
+
+    /// Returns the total amount of articles discovered during the crawl
+    pub fn total_discovered(&self) -> usize {
+        self.depths.iter().map(|depth| depth.discovered).sum()
+    }
+
+    /// Returns true if the crawl expanded every article it found, so that a found path is the shortest one and a
+    /// missing path means that there is none
+    pub fn is_complete(&self) -> bool {
+        self.depths.iter().all(|depth| depth.left_out == 0 && depth.sampled_out == 0)
+    }
+
+    /// Returns the total amount of API requests made during the crawl
+    pub fn total_api_calls(&self) -> usize {
+        self.depths.iter().map(|depth| depth.api_calls).sum()
+    }
+
+    /// Constructs a JSON representation of the statistics
+    ///
+    /// # Returns
+    ///
+    /// * serde_json::Value - A JSON object with the totals and the per depth breakdown
+    pub fn to_json(&self) -> serde_json::Value {
+        let depths: Vec<serde_json::Value> = self.depths.iter().enumerate().map(|(depth, stats)| json!({
+            "depth": depth,
+            "discovered": stats.discovered,
+            "expanded": stats.expanded,
+            "api_calls": stats.api_calls,
+            "left_out": stats.left_out,
+            "sampled_out": stats.sampled_out,
+        })).collect();
+
+        let warnings: Vec<serde_json::Value> = self.warnings.iter().map(|(warning, count)| json!({
+            "module": warning.module,
+            "message": warning.message,
+            "kind": warning.kind().as_str(),
+            "count": count,
+        })).collect();
+
+        json!({
+            "discovered": self.total_discovered(),
+            "api_calls": self.total_api_calls(),
+            "elapsed_seconds": self.elapsed.as_secs_f64(),
+            "depths": depths,
+            "warnings": warnings,
+            "degraded": self.degraded,
+            "complete": self.is_complete(),
+        })
+    }
+}
+
+/// A function for printing the statistics of a crawl as a summary with a per depth table
+///
+/// # Arguments
+///
+/// * 'stats' - A reference to the statistics of the crawl
+pub fn print_summary(stats: &CrawlStats) {
+    println!("\nDiscovered {} articles with {} API calls in {:.1} seconds.",
+             stats.total_discovered(), stats.total_api_calls(), stats.elapsed.as_secs_f64());
+    println!("{:<8}{:<13}{:<11}API calls", "Depth", "Discovered", "Expanded");
+    for (depth, depth_stats) in stats.depths.iter().enumerate() {
+        println!("{:<8}{:<13}{:<11}{}", depth, depth_stats.discovered, depth_stats.expanded, depth_stats.api_calls);
+    }
+
+    if !stats.warnings.is_empty() {
+        println!("\nThe API returned the following warnings during the crawl:");
+        for (warning, count) in stats.warnings.iter() {
+            println!("{}x [{}] {}: {}", count, warning.kind().as_str(), warning.module, warning.message);
+        }
+    }
+    if stats.depths.iter().any(|depth| depth.left_out > 0) {
+        println!("\nThe crawl was incomplete: the following depths had more articles than --max-frontier-per-depth \
+                  allows, so a shorter path may have been missed.");
+        for (depth, depth_stats) in stats.depths.iter().enumerate().filter(|(_, stats)| stats.left_out > 0) {
+            println!("Depth {}: {} articles left out", depth, depth_stats.left_out);
+        }
+    }
+    if stats.depths.iter().any(|depth| depth.sampled_out > 0) {
+        println!("\nThe crawl was approximate: only a sample of the following depths was expanded, so a shorter path \
+                  may have been missed.");
+        for (depth, depth_stats) in stats.depths.iter().enumerate().filter(|(_, stats)| stats.sampled_out > 0) {
+            println!("Depth {}: {} articles left unexpanded", depth, depth_stats.sampled_out);
+        }
+    }
+    if stats.degraded {
+        println!("\nThe crawl ran anonymously because logging in failed, so it was limited like an anonymous user.");
+    }
+}
+
+/// A function for constructing the JSON report of a single crawl
+///
+/// # Arguments
+///
+/// * 'origin' - A reference to the title of the origin article
+/// * 'goal' - A reference to the title of the goal article
+/// * 'direction' - A string slice naming the direction the crawl followed links in
+/// * 'path' - An option containing the found path, if there was one
+/// * 'stats' - A reference to the statistics of the crawl
+///
+/// # Returns
+///
+/// * serde_json::Value - The constructed report
+pub fn crawl_report(origin: &ArticleTitle, goal: &ArticleTitle, direction: &str, path: Option<&Vec<ArticleTitle>>,
+                    stats: &CrawlStats) -> serde_json::Value {
+    let path = path.map(|path| path.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>());
+    json!({
+        "origin": origin.as_str(),
+        "goal": goal.as_str(),
+        "direction": direction,
+        "path": path,
+        "statistics": stats.to_json(),
+    })
+}
+
+/// An async function for delivering a JSON report to its target, such as a file, see output_sink::open
+///
+/// # Arguments
+///
+/// * 'target' - A string slice with the target of the report
+/// * 'report' - A reference to the report to write
+///
+/// # Returns
+///
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+pub async fn write_report(target: &str, report: &serde_json::Value) -> Result<(), Box<dyn Error>> {
+    output_sink::deliver(target, &serde_json::to_string_pretty(report)?, "application/json").await
+}
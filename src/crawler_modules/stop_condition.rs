@@ -0,0 +1,135 @@
+use std::error::Error;
+
+use regex::Regex;
+
+use super::article_title::ArticleTitle;
+use super::wiki_api;
+use super::wiki_client::WikiClient;
+
+// The amount of characters shown on both sides of the matched text
+const CONTEXT_CHARS: usize = 60;
+
+/// An enum representing a condition that ends a crawl at an article because of what the article says, instead of
+/// at a link to a goal article. The condition is checked for every article the crawl expands, before its links are
+/// fetched
+pub enum StopCondition {
+    /// The introduction of the article contains the phrase, ignoring case
+    Phrase(String),
+    /// The introduction of the article matches the regular expression
+    Pattern(Regex),
+}
+
+impl StopCondition {
+    /// A constructor for a condition matching a phrase
+    ///
+    /// # Arguments
+    ///
+    /// * 'phrase' - A string slice with the phrase to look for
+    ///
+    /// # Returns
+    ///
+    /// * StopCondition - A new condition matching the phrase in any case
+    pub fn phrase(phrase: &str) -> StopCondition {
+        StopCondition::Phrase(phrase.to_lowercase())
+    }
+
+    /// A constructor for a condition matching a regular expression
+    ///
+    /// # Arguments
+    ///
+    /// * 'pattern' - A string slice with the regular expression
+    ///
+    /// # Returns
+    ///
+    /// * Result<StopCondition, Box<dyn Error>> - A new condition, or an error if the regular expression was invalid
+    pub fn pattern(pattern: &str) -> Result<StopCondition, Box<dyn Error>> {
+        Ok(StopCondition::Pattern(Regex::new(pattern)?))
+    }
+
+    /// Returns a description of the condition for the messages shown to the user
+    pub fn describe(&self) -> String {
+        match self {
+            StopCondition::Phrase(phrase) => format!("the phrase '{}'", phrase),
+            StopCondition::Pattern(pattern) => format!("the pattern '{}'", pattern.as_str()),
+        }
+    }
+
+    /// A function for finding the condition in a text
+    ///
+    /// # Arguments
+    ///
+    /// * 'text' - A string slice with the text
+    ///
+    /// # Returns
+    ///
+    /// * Option<String> - The matched part of the text along with some of the text around it, or None if the text
+    ///   doesn't meet the condition
+    pub fn find_in(&self, text: &str) -> Option<String> {
+        let (start, end) = match self {
+            // Lowercasing can change the byte lengths of some characters, so the phrase is searched for character by
+            // character instead of in a lowercased copy of the text
+            StopCondition::Phrase(phrase) => {
+                let phrase: Vec<char> = phrase.chars().collect();
+                let characters: Vec<(usize, char)> = text.char_indices().collect();
+                let start = (0..=characters.len().checked_sub(phrase.len())?).find(|&index| {
+                    phrase.iter().zip(&characters[index..])
+                        .all(|(wanted, (_, character))| character.to_lowercase().eq(wanted.to_lowercase()))
+                })?;
+                let byte_at = |index: usize| characters.get(index).map_or(text.len(), |(byte, _)| *byte);
+                (byte_at(start), byte_at(start + phrase.len()))
+            },
+            StopCondition::Pattern(pattern) => {
+                let found = pattern.find(text)?;
+                (found.start(), found.end())
+            },
+        };
+        Some(snippet(text, start, end))
+    }
+
+    /// An async function for finding the first of the given articles whose introduction meets the condition. The
+    /// introductions are fetched in batches
+    ///
+    /// # Arguments
+    ///
+    /// * 'articles' - A slice of the titles of the articles, in the order they should be checked in
+    /// * 'client' - A reference to a WikiClient with a logged in api session
+    ///
+    /// # Returns
+    ///
+    /// * Result<Option<(ArticleTitle, String)>, Box<dyn Error>> - The first matching article with the matched text,
+    ///   None if no article matched, or an error if the introductions couldn't be fetched
+    pub async fn first_match(&self, articles: &[ArticleTitle], client: &WikiClient)
+        -> Result<Option<(ArticleTitle, String)>, Box<dyn Error>> {
+        let texts = wiki_api::introductions(articles, client).await?;
+        Ok(articles.iter()
+            .find_map(|article| Some((article.clone(), self.find_in(texts.get(article)?)?))))
+    }
+}
+
+/// A function for cutting the matched part out of a text along with some of the text around it
+///
+/// # Arguments
+///
+/// * 'text' - A string slice with the text
+/// * 'start' - The byte index the match starts at
+/// * 'end' - The byte index the match ends at
+///
+/// # Returns
+///
+/// * String - The match with the text around it on a single line, with ... marking the cut ends
+fn snippet(text: &str, start: usize, end: usize) -> String {
+    let before: String = text[..start].chars().rev().take(CONTEXT_CHARS).collect::<Vec<char>>().into_iter().rev()
+        .collect();
+    let after: String = text[end..].chars().take(CONTEXT_CHARS).collect();
+    let mut snippet = String::new();
+    if before.len() < start {
+        snippet.push_str("...");
+    }
+    snippet.push_str(&before);
+    snippet.push_str(&text[start..end]);
+    snippet.push_str(&after);
+    if after.len() < text.len() - end {
+        snippet.push_str("...");
+    }
+    snippet.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
@@ -0,0 +1,177 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs;
+use std::io;
+
+use super::article_title::{ArticleTitle, TitleCase};
+use super::skip_log::SkipReason;
+
+/// A list of titles and glob patterns read from a file. Exact titles are kept in a set, so that long lists of them
+/// stay fast to check
+///
+/// The lines are kept as they were read, so that the list can be normalized again once the capitalization rules of
+/// the wiki are known, see with_case
+struct TitleList {
+    lines: Vec<String>,
+    titles: HashSet<ArticleTitle>,
+    patterns: Vec<Vec<char>>,
+}
+
+impl TitleList {
+    /// A function for reading a title list from a file with one title or glob pattern per line. Empty lines and
+    /// lines starting with # are skipped. Both are normalized like titles, so the first letter is capitalized until
+    /// the list is normalized for a case sensitive wiki
+    ///
+    /// # Arguments
+    ///
+    /// * 'file' - A string slice with the path of the file
+    ///
+    /// # Returns
+    ///
+    /// * Result<TitleList, Box<dyn Error>> - The list, or an error if the file couldn't be read or had no titles
+    fn load(file: &str) -> Result<TitleList, Box<dyn Error>> {
+        let lines: Vec<String> = fs::read_to_string(file)?.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !ArticleTitle::new(line).is_empty())
+            .map(String::from)
+            .collect();
+
+        if lines.is_empty() {
+            return Err(Box::new(io::Error::new(io::ErrorKind::InvalidData,
+                                               format!("'{}' doesn't contain any titles", file))));
+        }
+        Ok(TitleList::normalize(lines, TitleCase::FirstLetter))
+    }
+
+    /// A function for normalizing the lines of a list into its titles and patterns
+    ///
+    /// # Arguments
+    ///
+    /// * 'lines' - A vector of the lines of the list
+    /// * 'case' - The TitleCase of the wiki the list is used on
+    ///
+    /// # Returns
+    ///
+    /// * TitleList - The normalized list
+    fn normalize(lines: Vec<String>, case: TitleCase) -> TitleList {
+        let mut titles = HashSet::new();
+        let mut patterns = vec!();
+        for title in lines.iter().map(|line| ArticleTitle::with_case(line, case)) {
+            if title.as_str().contains(['*', '?']) {
+                patterns.push(title.as_str().chars().collect());
+            } else {
+                titles.insert(title);
+            }
+        }
+        TitleList { lines, titles, patterns }
+    }
+
+    /// Returns true if the title is on the list or matches one of its patterns
+    fn contains(&self, title: &ArticleTitle) -> bool {
+        if self.titles.contains(title) {
+            return true;
+        }
+        let title: Vec<char> = title.as_str().chars().collect();
+        self.patterns.iter().any(|pattern| glob_matches(pattern, &title))
+    }
+}
+
+/// A filter dropping the links to the articles on a block list, or to the articles missing from an allow list, so
+/// that crawls can leave out sensitive topics or stay within a curated set of articles
+pub struct TitleFilter {
+    blocked: Option<TitleList>,
+    allowed: Option<TitleList>,
+}
+
+impl TitleFilter {
+    /// A constructor for TitleFilter, reading the lists from the given files
+    ///
+    /// # Arguments
+    ///
+    /// * 'block_file' - An option containing the path of the file with the blocked titles, if there is one
+    /// * 'allow_file' - An option containing the path of the file with the allowed titles, if there is one
+    ///
+    /// # Returns
+    ///
+    /// * Result<TitleFilter, Box<dyn Error>> - A new TitleFilter, or an error if a file couldn't be read or had no
+    ///   titles
+    pub fn load(block_file: Option<&str>, allow_file: Option<&str>) -> Result<TitleFilter, Box<dyn Error>> {
+        let blocked = block_file.map(TitleList::load).transpose()?;
+        let allowed = allow_file.map(TitleList::load).transpose()?;
+        Ok(TitleFilter { blocked, allowed })
+    }
+
+    /// A function for normalizing the lists anew by the capitalization rules of the wiki, which aren't known yet when
+    /// the lists are loaded
+    ///
+    /// # Arguments
+    ///
+    /// * 'case' - The TitleCase of the wiki the filter is used on
+    ///
+    /// # Returns
+    ///
+    /// * TitleFilter - A new TitleFilter with the same lists normalized for the wiki
+    pub fn with_case(&self, case: TitleCase) -> TitleFilter {
+        let renormalize = |list: &TitleList| TitleList::normalize(list.lines.clone(), case);
+        TitleFilter { blocked: self.blocked.as_ref().map(renormalize), allowed: self.allowed.as_ref().map(renormalize) }
+    }
+
+    /// A function for checking whether the crawl may go through an article
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    ///
+    /// # Returns
+    ///
+    /// * Option<SkipReason> - The reason the article is filtered out, or None if the crawl may go through it
+    pub fn skip_reason(&self, title: &ArticleTitle) -> Option<SkipReason> {
+        if self.blocked.as_ref().is_some_and(|blocked| blocked.contains(title)) {
+            Some(SkipReason::Blocked)
+        } else if self.allowed.as_ref().is_some_and(|allowed| !allowed.contains(title)) {
+            Some(SkipReason::NotAllowed)
+        } else {
+            None
+        }
+    }
+}
+
+/// A function for matching a title against a glob pattern, where * matches any amount of characters and ? matches
+/// exactly one
+///
+/// # Arguments
+///
+/// * 'pattern' - A slice of the characters of the pattern
+/// * 'title' - A slice of the characters of the title
+///
+/// # Returns
+///
+/// * bool - True if the whole title matches the pattern
+fn glob_matches(pattern: &[char], title: &[char]) -> bool {
+    let (mut pattern_index, mut title_index) = (0, 0);
+    // The position of the latest * and the title position it's currently matched up to, for backtracking
+    let mut star: Option<(usize, usize)> = None;
+
+    while title_index < title.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                star = Some((pattern_index, title_index));
+                pattern_index += 1;
+            },
+            Some(&character) if character == '?' || character == title[title_index] => {
+                pattern_index += 1;
+                title_index += 1;
+            },
+            _ => match star {
+                // Let the latest * swallow one more character and try again from there
+                Some((star_index, matched)) => {
+                    star = Some((star_index, matched + 1));
+                    pattern_index = star_index + 1;
+                    title_index = matched + 1;
+                },
+                None => return false,
+            },
+        }
+    }
+    pattern[pattern_index..].iter().all(|&character| character == '*')
+}
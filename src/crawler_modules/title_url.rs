@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::fmt;
+
+use super::article_title::ArticleTitle;
+
+/// A struct representing an article given as the url of its page, such as
+/// https://en.wikipedia.org/wiki/Rust_(programming_language)
+#[derive(Clone, PartialEq, Debug)]
+pub struct TitleUrl {
+    /// The title of the article
+    pub title: ArticleTitle,
+    /// The host of the wiki the article is on, such as en.wikipedia.org. Mobile hosts are given as the desktop host
+    pub host: String,
+    /// The API path of the wiki, assuming the default layout of mediawiki sites
+    pub api_path: String,
+}
+
+/// An error telling the program to connect to another wiki and start over, returned when the user chooses to switch
+/// to the wiki of an article url
+#[derive(Debug)]
+pub struct WikiSwitch {
+    /// The API path of the wiki to connect to
+    pub api_path: String,
+}
+
+impl fmt::Display for WikiSwitch {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Switching to the wiki at '{}'", self.api_path)
+    }
+}
+
+impl Error for WikiSwitch {}
+
+/// A function for reading the article out of a page url. Both the short form /wiki/Title and the long form
+/// /w/index.php?title=Title are understood, with or without the scheme
+///
+/// # Arguments
+///
+/// * 'input' - A string slice with the input of the user
+///
+/// # Returns
+///
+/// * Option<TitleUrl> - The article and its wiki, or None if the input isn't the url of an article
+pub fn parse_title_url(input: &str) -> Option<TitleUrl> {
+    let input = input.trim();
+    let (scheme, rest) = match input.split_once("://") {
+        Some((scheme, rest)) if scheme == "https" || scheme == "http" => (scheme, rest),
+        Some(_) => return None,
+        None => ("https", input),
+    };
+    let (host, path) = rest.split_once('/')?;
+    if !host.contains('.') || host.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let path = path.split('#').next().unwrap_or_default();
+    let title = if let Some(title) = path.strip_prefix("wiki/") {
+        percent_decode(title.split('?').next().unwrap_or_default())?
+    } else {
+        let (_, query) = path.split_once('?')?;
+        let title = query.split('&').find_map(|param| param.strip_prefix("title="))?;
+        // Form encoded values use + for spaces
+        percent_decode(&title.replace('+', " "))?
+    };
+    let title = ArticleTitle::new(&title);
+    if title.is_empty() {
+        return None;
+    }
+
+    let host = desktop_host(host);
+    Some(TitleUrl { title, api_path: format!("{}://{}/w/api.php", scheme, host), host })
+}
+
+/// A function for reading the host out of an url
+///
+/// # Arguments
+///
+/// * 'url' - A string slice with the url
+///
+/// # Returns
+///
+/// * Option<String> - The host, with mobile hosts given as the desktop host, or None if the url has no host
+pub fn host_of(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = rest.split('/').next()?;
+    if host.is_empty() { None } else { Some(desktop_host(host)) }
+}
+
+/// A function for turning the host of the mobile site of a wiki, such as en.m.wikipedia.org, into the host of the
+/// desktop site, which the API is served at
+///
+/// # Arguments
+///
+/// * 'host' - A string slice with the host
+///
+/// # Returns
+///
+/// * String - The desktop host in lowercase
+fn desktop_host(host: &str) -> String {
+    let mut labels: Vec<String> = host.split('.').map(str::to_lowercase).collect();
+    if labels.len() > 2 && labels[1] == "m" {
+        labels.remove(1);
+    }
+    labels.join(".")
+}
+
+/// A function for decoding the percent encoded characters of an url
+///
+/// # Arguments
+///
+/// * 'encoded' - A string slice with the encoded text
+///
+/// # Returns
+///
+/// * Option<String> - The decoded text, or None if it wasn't validly encoded UTF-8
+pub fn percent_decode(encoded: &str) -> Option<String> {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
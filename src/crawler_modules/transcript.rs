@@ -0,0 +1,89 @@
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::wiki_api;
+
+// The transcript of the session, if one was asked for with --transcript. The prompts are read all over the user
+// interface, so the file is kept here instead of being passed to each of them
+static TRANSCRIPT: Mutex<Option<File>> = Mutex::new(None);
+
+/// An enum representing the kinds of entries written into a transcript
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Entry {
+    /// The start of a session, along with the arguments it was started with
+    Start,
+    /// A question the user was asked
+    Prompt,
+    /// What the user answered to the question before it
+    Answer,
+    /// What an article name given by the user was validated as
+    Validation,
+    /// How an operation asked for by the user ended
+    Result,
+}
+
+impl Entry {
+    /// Returns the label the entry is written with
+    fn label(&self) -> &'static str {
+        match self {
+            Entry::Start => "start",
+            Entry::Prompt => "prompt",
+            Entry::Answer => "answer",
+            Entry::Validation => "validation",
+            Entry::Result => "result",
+        }
+    }
+}
+
+/// A function for starting to record the session into a transcript file. The file is appended to, so that the
+/// transcripts of several sessions can be kept in the same file
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the transcript file
+/// * 'arguments' - A string slice with the arguments the program was started with
+///
+/// # Returns
+///
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+pub fn open(file: &str, arguments: &str) -> Result<(), Box<dyn Error>> {
+    let file = OpenOptions::new().create(true).append(true).open(file)?;
+    match TRANSCRIPT.lock() {
+        Ok(mut transcript) => *transcript = Some(file),
+        Err(error) => return Err(Box::new(std::io::Error::other(format!(
+            "Error acquiring lock for the transcript:\n{:?}", error)))),
+    }
+    record(Entry::Start, arguments);
+    Ok(())
+}
+
+/// A function for writing an entry into the transcript with the current time, if the session is recorded. The lines
+/// after the first one of a text are indented, so that every entry starts with its time
+///
+/// # Arguments
+///
+/// * 'entry' - The kind of the entry
+/// * 'text' - A string slice with the text of the entry
+pub fn record(entry: Entry, text: &str) {
+    let mut transcript = match TRANSCRIPT.lock() {
+        Ok(transcript) => transcript,
+        Err(error) => {
+            eprintln!("Error acquiring lock for the transcript:\n{:?}", error);
+            return;
+        },
+    };
+    let file = match transcript.as_mut() {
+        Some(file) => file,
+        None => return,
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs() as i64);
+    // The continuation lines line up with the text after the time and the label
+    let text = text.trim().replace('\n', &format!("\n{:34}", ""));
+    if let Err(error) = writeln!(file, "{}  {:<10}  {}", wiki_api::format_timestamp(now), entry.label(), text) {
+        eprintln!("Error writing to the transcript:\n{:?}", error);
+    }
+}
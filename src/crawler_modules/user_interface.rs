@@ -1,14 +1,49 @@
-use super::{configs, crawler, wiki_api};
+use super::{account, autocomplete, cache, capabilities, configs, crawler, demo, display, existence, neighborhood,
+            output_sink, replay, report_diff, sdow, statistics, transcript, verify, warm_up, watch, wiki_api};
+use super::crawler::CrawlOutcome;
+use super::article_title::{ArticleTitle, TitleCase};
+use super::cache::{CachedPath, WikiCache};
+use super::graph_export::GraphWriter;
+use super::neighborhood::NeighborhoodFormat;
+use super::progress::ProgressWriter;
+use super::provenance::ProvenanceMap;
+use super::sampling::DistanceEstimate;
+use super::skip_log::SkipLog;
+use super::stop_condition::StopCondition;
+use super::title_url::{self, WikiSwitch};
+use super::visited::VisitedSet;
+use super::wiki_api::{LinkDirection, Suggestion};
+use super::wiki_client::WikiClient;
 use std::fs;
 use std::env;
 use std::io;
 use std::io::{stdout, Write};
 use std::error::Error;
 use std::path::Path;
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use mediawiki;
+use rustyline::Editor;
+use rustyline::history::DefaultHistory;
 
 pub const SECRETS: &str = "./secrets.txt";
+const HUB_COUNT: u8 = 10;
+const REDIRECT_TOP_COUNT: usize = 5;
+// The most requests per second sent when continuing anonymously after logging in failed
+const DEGRADED_RATE: f64 = 1.0;
+// How many times more links the origin must have than the goal has backlinks for a reverse crawl to be suggested
+const DIRECTION_SWAP_RATIO: usize = 100;
+// The exit codes of the commands running a single crawl, telling scripts how the crawl ended, see exit_code
+pub const EXIT_FOUND: i32 = 0;
+pub const EXIT_NO_PATH: i32 = 2;
+pub const EXIT_BUDGET_EXHAUSTED: i32 = 3;
+pub const EXIT_CRAWL_FAILED: i32 = 4;
+pub const EXIT_CANCELLED: i32 = 130;
+
+// The cancel flag of the crawl currently running, set by Ctrl+C. Without a running crawl Ctrl+C exits the program
+static RUNNING_CRAWL: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
 
 /// A struct containing the username and password of the bot account to use with the crawler
 #[derive(PartialEq, Debug)]
@@ -39,9 +74,9 @@ impl BotLoginData {
         };
 
         // https://stackoverflow.com/questions/37547225/split-a-string-and-return-vecstring
-        let file_rows: Vec<String> = file_contents.split("\n").map(|s| s.to_string()).collect();
+        let file_rows: Vec<String> = file_contents.split('\n').map(|s| s.to_string()).collect();
 
-        let username = match file_rows.get(0) {
+        let username = match file_rows.first() {
             Some(string) => string.trim().to_string(),
             None => return None,
         };
@@ -63,35 +98,545 @@ impl BotLoginData {
 /// 
 /// # Returns
 /// 
-/// * Result<(), Box<dyn Error>> - Result containing possible errors
-pub async fn run(args: env::Args) -> Result<(), Box<dyn Error>> {
-    let config = configs::Config::new(args);
+/// * Result<i32, Box<dyn Error>> - Result containing the code the program should exit with, see exit_code, or
+///   possible errors
+pub async fn run(args: env::Args) -> Result<i32, Box<dyn Error>> {
+    let config = configs::Config::new(args)?;
+    if let Some(file) = &config.transcript {
+        transcript::open(file, &env::args().skip(1).collect::<Vec<String>>().join(" "))?;
+    }
+
+    // Cache operations only touch the local database, so there's no need to log in for them
+    if let configs::Command::Cache(cache_command) = &config.command {
+        return run_cache_command(cache_command, &config).map(|_| EXIT_FOUND);
+    }
+    if let configs::Command::Diff { first, second } = &config.command {
+        return diff_reports(first, second).map(|_| EXIT_FOUND);
+    }
+    if let configs::Command::ReplayView { file, speed, summary } = &config.command {
+        return replay_log(file, *speed, *summary).map(|_| EXIT_FOUND);
+    }
+
+    listen_for_cancel();
+
+    // The demo wiki is served like a fixture. Every run of the demo crawls the whole path, so the cache is left out,
+    // and the report is shown unless it was asked to be written elsewhere
+    #[cfg(feature = "fixture-server")]
+    if let configs::Command::Demo { .. } = config.command {
+        let api_path = super::fixture_server::start_demo().await?;
+        println!("Serving the demo wiki at '{}'", api_path);
+        let json_output = config.json_output.clone().or_else(|| Some(output_sink::STDOUT_TARGET.to_string()));
+        return Session::open(configs::Config { api_path, cache_file: None, json_output, ..config }, None).await?
+            .run_command().await;
+    }
+
+    // The fixture server has no accounts, so it's used without logging in
+    #[cfg(feature = "fixture-server")]
+    if let Some(fixture) = config.api_fixture.clone() {
+        let api_path = super::fixture_server::start(&fixture).await?;
+        println!("Serving the fixture '{}' at '{}'", fixture, api_path);
+        return Session::open(configs::Config { api_path, ..config }, None).await?.run_command().await;
+    }
+
     let login_data = match BotLoginData::get_login_from_file(Path::new(SECRETS)) {
         Some(result) => result,
-        None => return Err(Box::new(io::Error::new(io::ErrorKind::Other, 
+        None => return Err(Box::new(io::Error::other(
                                                "Fatal error: didn't find bot login credentials in secret file!"))),
     };
 
-    start_cli(config, login_data).await
+    // Choosing to switch to the wiki of an article url starts the program over, connected to that wiki
+    let mut config = config;
+    loop {
+        let session = Session::open(config, Some(&login_data)).await?;
+        match session.run_command().await {
+            Err(error) => match error.downcast::<WikiSwitch>() {
+                Ok(switch) => {
+                    println!("\nSwitching to the wiki at '{}'...", switch.api_path);
+                    config = session.into_config();
+                    config.api_path = switch.api_path;
+                },
+                Err(error) => return Err(error),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// A struct owning everything the operations of a single run share: the api session with its caches and rate
+/// limiter, and the config of the program. The menu loop and the commands run their operations through it
+struct Session {
+    client: WikiClient,
+    config: configs::Config,
+}
+
+impl Session {
+    /// An async constructor for Session, initializing the api session and the cache, and listing the articles of
+    /// the goal category if one was given
+    /// 
+    /// # Arguments
+    /// 
+    /// * 'config' - A Config struct with the config data of the progarm
+    /// * 'login_data' - An option containing the login data of the bot account to be used, or None to use the api
+    ///   without logging in
+    /// 
+    /// # Returns
+    /// 
+    /// * Result<Session, Box<dyn Error>> - The opened session, or an error if the api or the cache couldn't be opened
+    async fn open(mut config: configs::Config, login_data: Option<&BotLoginData>) -> Result<Session, Box<dyn Error>> {
+        println!("Opening api connection...");
+        let mut api = mediawiki::api::Api::new(&config.api_path).await?;
+        let mut degraded = false;
+        if let Some(login_data) = login_data {
+            match api.login(&login_data.username, &login_data.password).await {
+                Ok(()) => api.load_current_user_info().await?,
+                Err(error) => {
+                    eprintln!("Logging in as '{}' failed: {}", &login_data.username, error);
+                    if !confirm_anonymous().await {
+                        return Err(Box::new(error));
+                    }
+                    degraded = true;
+                },
+            }
+        }
+        let mut client = WikiClient::new(api);
+        match (login_data, degraded) {
+            (Some(login_data), false) => {
+                client.set_login(&login_data.username, &login_data.password);
+                client.set_account(account::detect(&client).await);
+                print_login(&login_data.username, client.account());
+            },
+            (_, true) => client.set_degraded(),
+            _ => (),
+        }
+        let account_rate = client.account().read_limit().map(account::RateLimit::per_second);
+        let max_rate = [config.max_rate, account_rate, degraded.then_some(DEGRADED_RATE)].iter()
+            .flatten()
+            .copied()
+            .min_by(|first, second| first.total_cmp(second));
+        if let Some(rate) = max_rate {
+            if max_rate == account_rate && max_rate != config.max_rate {
+                println!("Sending at most {:.2} requests per second to stay within the rate limits of the account",
+                         rate);
+            }
+            client.set_rate_limit(rate);
+        }
+        client.set_capabilities(capabilities::detect(&client).await);
+        let batch_size = if client.account().high_limits {
+            client.capabilities().high_max_titles
+        } else {
+            client.capabilities().max_titles
+        };
+        client.set_batch_size(batch_size);
+        print_capabilities(client.capabilities(), client.batch_size());
+        // The lists were read before connecting, so they are normalized only once the rules of the wiki are known
+        let title_case = client.capabilities().title_case;
+        config.title_filter = config.title_filter.map(|filter| Arc::new(filter.with_case(title_case)));
+        if config.html_fallback {
+            if !client.allow_html_fallback() {
+                return Err(Box::new(io::Error::new(io::ErrorKind::Unsupported, format!(
+                    "Can't locate the REST API of the wiki from '{}', which --allow-html-fallback needs",
+                    config.api_path))));
+            }
+            if !client.capabilities().links {
+                client.fall_back_to_html("The wiki doesn't allow querying the links of articles");
+            }
+        }
+        if let Some(timestamp) = &config.as_of {
+            client.set_as_of(timestamp.clone());
+            println!("Fetching the links as of {}, which takes two queries per article", timestamp);
+        }
+        if let Some(pattern) = &config.anchor_pattern {
+            if !client.set_anchor_pattern(Arc::clone(pattern)) {
+                return Err(Box::new(io::Error::new(io::ErrorKind::Unsupported, format!(
+                    "Can't locate the REST API of the wiki from '{}', which --anchor-pattern needs",
+                    config.api_path))));
+            }
+            println!("Following only the links whose text matches '{}', read from the HTML of the articles at one \
+                      request per article", pattern.as_str());
+        }
+        // Watching is about noticing changes in the link graph, so it must always see fresh data
+        let watching = matches!(config.command, configs::Command::Watch { .. });
+        if let (Some(file), false) = (&config.cache_file, watching) {
+            let wiki_id = client.wiki_id();
+            let cache = WikiCache::open(file, &wiki_id)?;
+            cache.set_memory_size(config.memory_cache_size);
+            client.set_cache(cache);
+            println!("Using cache '{}' for wiki '{}'", file, wiki_id);
+        }
+
+        if config.undirected && !client.capabilities().backlinks {
+            return Err(Box::new(io::Error::new(io::ErrorKind::Unsupported,
+                                               "The wiki doesn't support backlinks, which --undirected needs")));
+        }
+        if let Some(category) = &config.goal_category {
+            println!("Listing the articles in '{}'...", category);
+            let members = wiki_api::category_members(category, config.category_limit, &client).await?;
+            if members.is_empty() {
+                return Err(Box::new(io::Error::new(io::ErrorKind::NotFound,
+                                                   format!("'{}' doesn't have any articles", category))));
+            }
+            if members.len() >= config.category_limit {
+                println!("Crawling to the first {} articles in '{}', raise --category-limit to include the rest",
+                         members.len(), category);
+            } else {
+                println!("Crawling to any of the {} articles in '{}'", members.len(), category);
+            }
+            config.category_members = Some(Arc::new(members.into_iter().collect()));
+        }
+        // Injected only once the session is open, so that the detection above sees the wiki as it is
+        #[cfg(feature = "chaos")]
+        if let Some(rate) = config.inject_failures {
+            let chaos = super::chaos::Chaos::new(rate, config.chaos_seed);
+            println!("Injecting failures into {:.1}% of the requests, with the seed {}", rate * 100.0, chaos.seed());
+            client.set_chaos(chaos);
+        }
+        Ok(Session { client, config })
+    }
+
+    /// Returns the api session shared by the operations
+    fn client(&self) -> &WikiClient {
+        &self.client
+    }
+
+    /// Returns the config of the program
+    fn config(&self) -> &configs::Config {
+        &self.config
+    }
+
+    /// Takes the config out of the session once it's no longer needed
+    fn into_config(self) -> configs::Config {
+        self.config
+    }
+
+    /// An async function for running the command given on the command line, or the interactive menu loop if no
+    /// command was given. If failures were injected into the requests, they are summed up once the command ends
+    /// 
+    /// # Returns
+    /// 
+    /// * Result<i32, Box<dyn Error>> - Result containing the code the program should exit with, or possible errors
+    async fn run_command(&self) -> Result<i32, Box<dyn Error>> {
+        let result = self.run_operation().await;
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = self.client.chaos() {
+            println!("\n{}", chaos.summary());
+        }
+        result
+    }
+
+    /// An async function for running the operation of the command, see run_command. The commands running a single
+    /// crawl exit with a code telling how it ended, and the rest exit with EXIT_FOUND unless they fail
+    /// 
+    /// # Returns
+    /// 
+    /// * Result<i32, Box<dyn Error>> - Result containing the code the program should exit with, or possible errors
+    async fn run_operation(&self) -> Result<i32, Box<dyn Error>> {
+        let (client, config) = (&self.client, &self.config);
+        let result = match &config.command {
+            configs::Command::Interactive => core_loop(self).await,
+            configs::Command::Compare { from, to } => {
+                if !client.capabilities().backlinks {
+                    return Err(Box::new(io::Error::new(io::ErrorKind::Unsupported,
+                                                       "The wiki doesn't support backlinks, which compare needs")));
+                }
+                let from = input_title(from, client, config).await?;
+                let to = input_title(to, client, config).await?;
+                println!("\nValidating given articles' existence...\n");
+                match validate_pair(&from, &to, client, config).await? {
+                    Some((origin, goal)) => return compare_between(&origin, &goal, client, config).await,
+                    None => Ok(()),
+                }
+            },
+            configs::Command::Cache(cache_command) => run_cache_command(cache_command, config),
+            configs::Command::Diff { first, second } => diff_reports(first, second),
+            configs::Command::ReplayView { file, speed, summary } => replay_log(file, *speed, *summary),
+            configs::Command::Watch { from, to, every, log } => {
+                let from = input_title(from, client, config).await?;
+                let to = input_title(to, client, config).await?;
+                println!("\nValidating given articles' existence...\n");
+                match validate_pair(&from, &to, client, config).await? {
+                    Some((origin, goal)) => watch_pair(&origin, &goal, *every, log, client, config).await,
+                    None => Ok(()),
+                }
+            },
+            configs::Command::Degree { title } => match self.validate(title).await? {
+                Some(article) => print_degree(&article, client).await,
+                None => Ok(()),
+            },
+            configs::Command::Backlinks { title, limit } => match self.validate(title).await? {
+                Some(article) => print_backlinks(&article, *limit, client, config).await,
+                None => Ok(()),
+            },
+            configs::Command::Cycle { title } => match self.validate(title).await? {
+                Some(article) => return find_cycle(&article, self).await,
+                None => Ok(()),
+            },
+            configs::Command::Find { from, text, regex } => {
+                if !client.capabilities().extracts {
+                    return Err(Box::new(io::Error::new(io::ErrorKind::Unsupported,
+                                                       "The wiki doesn't support text extracts, which find needs")));
+                }
+                let condition = if *regex { StopCondition::pattern(text)? } else { StopCondition::phrase(text) };
+                match self.validate(from).await? {
+                    Some(article) => return find_text(&article, condition, client, config).await,
+                    None => Ok(()),
+                }
+            },
+            configs::Command::Verify { from, to, max_length } => {
+                let from = input_title(from, client, config).await?;
+                let to = input_title(to, client, config).await?;
+                println!("\nValidating given articles' existence...\n");
+                match validate_pair(&from, &to, client, config).await? {
+                    Some((origin, goal)) => return verify_path(&origin, &goal, *max_length, client).await,
+                    None => Ok(()),
+                }
+            },
+            configs::Command::WarmCache { hubs, top, every } => warm_cache(hubs.as_deref(), *top, *every, client).await,
+            configs::Command::Exists { file, output } => check_existence(file, output.as_deref(), client).await,
+            configs::Command::Neighborhood { title, depth, format, output } => match self.validate(title).await? {
+                Some(article) => list_neighborhood(&article, *depth, *format, output.as_deref(), client).await,
+                None => Ok(()),
+            },
+            configs::Command::Demo { menu } => {
+                run_demo(self).await?;
+                match menu {
+                    true => core_loop(self).await,
+                    false => {
+                        println!("\nRun 'demo --menu' to go on to the interactive menu on the demo wiki, or give the \
+                                  api path of a wiki to crawl it instead.");
+                        Ok(())
+                    },
+                }
+            },
+        };
+        result.map(|_| EXIT_FOUND)
+    }
+
+    /// An async function for validating an article given by the user, as a name or as the url of its page
+    /// 
+    /// # Arguments
+    /// 
+    /// * 'input' - A string slice with the article name or page url given by the user
+    /// 
+    /// # Returns
+    /// 
+    /// * Result<Option<ArticleTitle>, Box<dyn Error>> - Result containing the validated title, or None if the user
+    ///   should be returned to the menu
+    async fn validate(&self, input: &str) -> Result<Option<ArticleTitle>, Box<dyn Error>> {
+        let article = input_title(input, &self.client, &self.config).await?;
+        if article.is_empty() {
+            println!("Please input an article name.");
+            return Ok(None);
+        }
+        println!("\nValidating given article's existence...\n");
+        validate_title(&article, &self.client, &self.config).await
+    }
+
+    /// An async function for crawling between two validated articles, reading the path from the cache if it's there
+    /// 
+    /// # Arguments
+    /// 
+    /// * 'origin' - A reference to the validated title of the origin article
+    /// * 'goal' - A reference to the validated title of the goal article
+    /// * 'direction' - The LinkDirection the crawl follows links in
+    /// 
+    /// # Returns
+    /// 
+    /// * Result<crawler::CrawlResult, Box<dyn Error>> - How the crawl ended and the crawl statistics
+    async fn crawl(&self, origin: &ArticleTitle, goal: &ArticleTitle, direction: LinkDirection)
+        -> Result<crawler::CrawlResult, Box<dyn Error>> {
+        crawl_between(origin, goal, direction, &self.client, &self.config).await
+    }
+
+    /// An async function for writing the result of a crawl into the report files asked for with --json and --sdow
+    /// 
+    /// # Arguments
+    /// 
+    /// * 'origin' - A reference to the title of the origin article of the crawl
+    /// * 'goal' - A reference to the title of the goal article of the crawl
+    /// * 'kind' - A string slice naming the kind of the crawl in the JSON report
+    /// * 'result' - A reference to the CrawlResult of the crawl
+    /// * 'hops' - An option containing the direction of each link on the found path, if the crawl was undirected
+    /// 
+    /// # Returns
+    /// 
+    /// * Result<(), Box<dyn Error>> - Result containing possible errors
+    async fn export(&self, origin: &ArticleTitle, goal: &ArticleTitle, kind: &str, result: &crawler::CrawlResult,
+                    hops: Option<&[LinkDirection]>) -> Result<(), Box<dyn Error>> {
+        if let Some(file) = &self.config.json_output {
+            let mut report = statistics::crawl_report(origin, goal, kind, result.path(), &result.stats);
+            if let Some(hops) = hops {
+                let hops: Vec<&str> = hops.iter().map(LinkDirection::as_str).collect();
+                report["hops"] = serde_json::json!(hops);
+            }
+            if let (Some(fraction), Some(path)) = (self.config.approximate, result.path()) {
+                let estimate = DistanceEstimate::new(path.len() - 1, fraction);
+                report["estimate"] = serde_json::json!({
+                    "shortest": estimate.shortest,
+                    "longest": estimate.longest,
+                    "confidence": estimate.confidence,
+                    "fraction": fraction,
+                });
+            }
+            if !result.other_paths.is_empty() {
+                let other_paths: Vec<Vec<&str>> = result.other_paths.iter()
+                    .map(|path| path.iter().map(ArticleTitle::as_str).collect())
+                    .collect();
+                report["other_paths"] = serde_json::json!(other_paths);
+            }
+            statistics::write_report(file, &report).await?;
+        }
+        write_sdow_report(origin, goal, result, &self.client, &self.config).await
+    }
+}
+
+/// A function for starting a background task that cancels the running crawl on Ctrl+C, so that the crawl can answer
+/// with how far it got. Ctrl+C outside of crawls exits the program as usual
+fn listen_for_cancel() {
+    tokio::spawn(async {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            let running = RUNNING_CRAWL.lock().ok().and_then(|running| running.clone());
+            match running {
+                Some(cancel) => cancel.store(true, Ordering::Relaxed),
+                None => process::exit(130),
+            }
+        }
+    });
+}
+
+/// A function for confirming the login to the user along with the rights and the rate limits of the account
+/// 
+/// # Arguments
+/// 
+/// * 'username' - A string slice with the name of the account
+/// * 'account' - A reference to the detected rights and rate limits of the account
+fn print_login(username: &str, account: &account::AccountInfo) {
+    if account.high_limits {
+        println!("Logged in as '{}', with the high API limits of the apihighlimits right", username);
+    } else {
+        println!("Logged in as '{}', without the apihighlimits right", username);
+    }
+    if account.no_rate_limit {
+        println!("The account is exempt from rate limits");
+    } else if !account.rate_limits.is_empty() {
+        let limits: Vec<String> = account.rate_limits.iter().map(account::RateLimit::to_string).collect();
+        println!("Rate limits of the account: {}", limits.join(", "));
+    }
+}
+
+/// A function for telling the user about the limits and missing features of the wiki
+/// 
+/// # Arguments
+/// 
+/// * 'capabilities' - A reference to the detected capabilities of the wiki
+/// * 'batch_size' - The amount of titles per query allowed for the account
+fn print_capabilities(capabilities: &capabilities::WikiCapabilities, batch_size: usize) {
+    println!("The wiki accepts {} titles per query for this account", batch_size);
+    if !capabilities.search {
+        println!("Search is disabled on the wiki, article names must be given exactly");
+    }
+    if !capabilities.backlinks {
+        println!("Backlinks are disabled on the wiki, reverse crawls aren't available");
+    }
+    if capabilities.title_case == TitleCase::CaseSensitive {
+        println!("Titles are case sensitive on the wiki, the first letter isn't capitalized");
+    }
+    if capabilities.variants {
+        println!("The wiki has variants of its language, titles written in any of them lead to the same article");
+    }
+}
+
+/// A function for running an operation on the persistent cache
+/// 
+/// # Arguments
+/// 
+/// * 'command' - A reference to the CacheCommand to run
+/// * 'config' - A reference to the Config struct with the path of the cache
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+fn run_cache_command(command: &configs::CacheCommand, config: &configs::Config) -> Result<(), Box<dyn Error>> {
+    let file = match &config.cache_file {
+        Some(file) => file,
+        None => return Err(Box::new(io::Error::new(io::ErrorKind::InvalidInput,
+                                                   "The cache command can't be used with --no-cache"))),
+    };
+
+    match command {
+        configs::CacheCommand::Stats => {
+            let wikis = cache::statistics(file)?;
+            println!("Cache '{}', {:.1} MB", file, cache::file_size(file) as f64 / 1_000_000.0);
+            println!("{:<24}{:<10}{:<8}{:<11}Hit rate", "Wiki", "Links", "Paths", "Redirects");
+            for wiki in wikis.iter() {
+                let lookups = wiki.hits + wiki.misses;
+                let hit_rate = match lookups {
+                    0 => String::from("-"),
+                    _ => format!("{:.1}%", wiki.hits as f64 * 100.0 / lookups as f64),
+                };
+                println!("{:<24}{:<10}{:<8}{:<11}{}", wiki.wiki, wiki.links, wiki.paths, wiki.redirects, hit_rate);
+            }
+        },
+        configs::CacheCommand::Redirects => {
+            let wikis = cache::redirect_statistics(file, REDIRECT_TOP_COUNT)?;
+            if wikis.is_empty() {
+                println!("No redirects have been cached yet.");
+            }
+            for wiki in wikis.iter() {
+                println!("{}: {} redirects to {} articles", wiki.wiki, wiki.aliases, wiki.targets);
+                for (target, aliases) in wiki.most_aliased.iter() {
+                    println!("  {:<40}{} redirects", target, aliases);
+                }
+            }
+        },
+        configs::CacheCommand::Clear => println!("Removed {} entries from the cache.", cache::clear(file)?),
+        configs::CacheCommand::Prune { older_than } => {
+            println!("Removed {} entries from the cache.", cache::prune(file, *older_than)?);
+        },
+        configs::CacheCommand::Export { file: output } => {
+            println!("Exported {} entries into '{}'.", cache::export(file, output)?, output);
+        },
+    }
+    Ok(())
 }
 
-/// An async function for initializing the api and starting the command line interface loop
+/// A function for comparing the crawls of two report files written with --json
 /// 
 /// # Arguments
 /// 
-/// * 'config' - A Config struct with the config data of the progarm
-/// * 'login_data' - A BotLoginData struct containing the login data of the bot account to be used
+/// * 'first' - A string slice with the path of the report of the earlier run
+/// * 'second' - A string slice with the path of the report of the later run
 /// 
 /// # Returns
 /// 
 /// * Result<(), Box<dyn Error>> - Result containing possible errors
-async fn start_cli(config: configs::Config, login_data: BotLoginData) -> Result<(), Box<dyn Error>> {
-    println!("Opening api connection and logging in...");
-    let mut api = mediawiki::api::Api::new(&config.api_path).await?;
-    api.login(&login_data.username, &login_data.password).await?;
-    println!("Logged in as '{}'", &login_data.username);
+fn diff_reports(first: &str, second: &str) -> Result<(), Box<dyn Error>> {
+    let diffs = report_diff::pair_reports(report_diff::load_reports(first)?, report_diff::load_reports(second)?);
+    println!("Comparing '{}' to '{}'", first, second);
+    report_diff::print_diff(&diffs);
+    Ok(())
+}
 
-    core_loop(api).await
+/// A function for replaying the crawls recorded into a progress log written with --progress-json, or summarizing
+/// them without the replay
+/// 
+/// # Arguments
+/// 
+/// * 'file' - A string slice with the path of the progress log
+/// * 'speed' - How many times faster than recorded the crawls are replayed
+/// * 'summary' - Whether to only summarize the crawls
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+fn replay_log(file: &str, speed: f64, summary: bool) -> Result<(), Box<dyn Error>> {
+    let crawls = replay::load_crawls(file)?;
+    println!("Read {} crawls from '{}'", crawls.len(), file);
+    if summary {
+        replay::print_summary(&crawls);
+    } else {
+        replay::replay(&crawls, speed);
+    }
+    Ok(())
 }
 
 /// An async function responsible for running the cli loop at the core of the program
@@ -99,41 +644,44 @@ async fn start_cli(config: configs::Config, login_data: BotLoginData) -> Result<
 /// 
 /// # Arguments
 /// 
-/// * 'api' - Mutable mediawiki::api::Api struct with a logged in bot account
+/// * 'session' - A reference to the Session the operations are run through
 /// 
 /// # Returns
 /// 
 /// * Result<(), Box<dyn Error>> - Result containing possible errors
-async fn core_loop(mut api: mediawiki::api::Api) -> Result<(), Box<dyn Error>> {
+async fn core_loop(session: &Session) -> Result<(), Box<dyn Error>> {
     let prompt = r#"
 Welcome to EddieWikiCrawler, a tool for finding the shortest path between two wikipedia articles.
     
 Choose your operation:
 1: Start a new crawl
+2: Compare forward and reverse crawls
+3: Find the shortest cycle through an article
 0: Exit
 Your choice: "#;
     loop {
-        let user_choice_string: String;
-        match get_user_input(prompt).await {
-            Some(string) => user_choice_string = string,
+        let user_choice_string = match get_user_input(prompt).await {
+            Some(string) => string,
             None => {
                 println!("Something went wrong while reading input! Please try again.");
                 continue;
             }
-        }
+        };
 
         match user_choice_string.parse::<u8>() {
             Err(_) => {
-                println!("Please type a number between 0 and 2!");
+                println!("Please type a number between 0 and 3!");
                 continue;
             },
             Ok(0) => {
                 println!("Exiting program...");
                 break
             },
-            Ok(1) => api = crawl(api).await?,
+            Ok(1) => crawl(session).await?,
+            Ok(2) => compare(session).await?,
+            Ok(3) => cycle(session).await?,
             Ok(_) => {
-                println!("Please type a number between 0 and 2!");
+                println!("Please type a number between 0 and 3!");
                 continue;
             }
         }
@@ -146,120 +694,1828 @@ Your choice: "#;
 /// 
 /// # Arguments
 /// 
-/// * 'api' - A logged in mediawiki::api::Api instance
+/// * 'session' - A reference to the Session the crawl is run through
 /// 
 /// # Returns
 /// 
-/// * Resulut<mediawiki::api::Api, Box<dyn Error>> - Result returning the borrowed api or containing error data
-async fn crawl(api: mediawiki::api::Api) 
-    -> Result<mediawiki::api::Api, Box<dyn Error>> {
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn crawl(session: &Session) -> Result<(), Box<dyn Error>> {
+    let (client, config) = (session.client(), session.config());
+    if let Some(category) = &config.goal_category {
+        return crawl_to_category(category, session).await;
+    }
 
-    let (origin, goal) = match query_names().await {
-        Some(tuple) => tuple,
+    // The validated pair is kept around so follow-up crawls don't need to prompt and validate again
+    let mut pair = query_validated_pair(client, config).await?;
+    while let Some((origin, goal)) = pair {
+        if origin == goal {
+            println!("Please input two different articles.");
+            return Ok(());
+        }
 
-        // Raising an error manually takes some serious work in rust, huh?
-        None => return Err(Box::new(io::Error::new(io::ErrorKind::Other,
-            "Error while getting article names from user."))),
-    };
+        let direction = choose_direction(&origin, &goal, client, config).await;
+        let result = session.crawl(&origin, &goal, direction).await?;
+        let hops = match result.path() {
+            Some(path) => path_hops(path, client, config).await,
+            None => None,
+        };
+        if let Some(path) = result.path() {
+            print_path(path, hops.as_deref(), client, config).await;
+            if let Some(fraction) = config.approximate {
+                println!("Estimated distance: {}.", DistanceEstimate::new(path.len() - 1, fraction).describe());
+            }
+        }
+        if !result.other_paths.is_empty() {
+            println!("\nThe other shortest paths found:");
+            for path in result.other_paths.iter() {
+                match path_hops(path, client, config).await {
+                    Some(other_hops) => println!("{}", format_hops(path, &other_hops)),
+                    None => println!("{}", format_path(path)),
+                }
+            }
+        }
+        statistics::print_summary(&result.stats);
 
-    println!("\nValidating given articles' existence...\n");
+        let kind = match direction {
+            _ if config.undirected => "undirected",
+            LinkDirection::Outgoing => "forward",
+            LinkDirection::Incoming => "reverse",
+        };
+        session.export(&origin, &goal, kind, &result, hops.as_deref()).await?;
+        pair = query_follow_up(origin, goal, result.provenance.as_deref(), direction, client, config).await?;
+    }
 
-    let origin = match wiki_api::validate_article(&origin, &api).await {
-        Ok(result) => match result {
-            Some(string) => string,
-            None => return Ok(api),
-        },
-        Err(error) => return Err(Box::new(error)),
-    };
+    Ok(())
+}
 
-    let goal = match wiki_api::validate_article(&goal, &api).await {
-        Ok(result) => match result {
-            Some(string) => string,
-            None => return Ok(api),
+/// An async func for crawling from a user given article to the closest article in the goal category
+/// 
+/// # Arguments
+/// 
+/// * 'category' - A string slice with the title of the goal category
+/// * 'session' - A reference to the Session the crawl is run through
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn crawl_to_category(category: &str, session: &Session) -> Result<(), Box<dyn Error>> {
+    let (client, config) = (session.client(), session.config());
+    let mut editor = autocomplete::title_editor(client, config.search_namespace);
+    let name = match read_article_name("Give the name of the starting article: ", &mut editor).await {
+        Some(string) => string,
+        None => {
+            println!("Something went wrong while reading input!");
+            return Ok(());
         },
-        Err(error) => return Err(Box::new(error)),
     };
-
-    if origin == goal {
-        println!("Please input two different articles.");
-        return Ok(api);
+    let origin = match session.validate(&name).await? {
+        Some(origin) => origin,
+        None => return Ok(()),
+    };
+    if config.category_members.as_ref().is_some_and(|members| members.contains(&origin)) {
+        println!("'{}' is in '{}' already.", origin, category);
+        return Ok(());
     }
 
-    let crawler_arc = crawler::Crawler::new_arc(&origin, &goal);
-    let result_route = match crawler::start(crawler_arc, &api).await {
-        Some(path) => path,
-        None => {
-            eprintln!("Error: something went wrong while traversing the path backwards to complete an answer.");
-            return Ok(api);
-        },
+    // The category only names the crawl, the crawl ends at the first link to any of its articles
+    let goal = client.title(category);
+    let result = session.crawl(&origin, &goal, LinkDirection::Outgoing).await?;
+    let hops = match result.path() {
+        Some(path) => path_hops(path, client, config).await,
+        None => None,
     };
-    pretty_print_path(result_route);
-    Ok(api)
+    if let Some(path) = result.path() {
+        println!("The closest article in '{}' is '{}', {} links away:", category, path[path.len() - 1],
+                 path.len() - 1);
+        print_path(path, hops.as_deref(), client, config).await;
+    }
+    if !result.other_paths.is_empty() {
+        println!("\nThe other shortest paths found:");
+        for path in result.other_paths.iter() {
+            match path_hops(path, client, config).await {
+                Some(other_hops) => println!("{}", format_hops(path, &other_hops)),
+                None => println!("{}", format_path(path)),
+            }
+        }
+    }
+    statistics::print_summary(&result.stats);
+    session.export(&origin, &goal, "category", &result, hops.as_deref()).await
 }
 
-/// A function for formatting the path while printing it to the user
+/// An async func for running a forward and a reverse crawl with user given articles and comparing them.
+/// Should be called from the core loop
 /// 
 /// # Arguments
 /// 
-/// * 'path' - A Vec of String instances containing the articles in the path from origin to goal
-fn pretty_print_path(path: Vec<String>) -> () {
-    if path.len() < 2 {
-        println!("Error: path should contain at least two articles!");
+/// * 'session' - A reference to the Session the crawls are run through
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn compare(session: &Session) -> Result<(), Box<dyn Error>> {
+    let (client, config) = (session.client(), session.config());
+    if config.undirected {
+        println!("Comparing crawls in both directions can't be done with --undirected.");
+        return Ok(());
+    }
+    if config.goal_category.is_some() {
+        println!("A reverse crawl can't start from every article of a category, so compare can't be used with \
+                  --to-category.");
+        return Ok(());
+    }
+    if !client.capabilities().backlinks {
+        println!("The wiki doesn't support backlinks, so reverse crawls can't be run.");
+        return Ok(());
     }
 
-    print!("{}", path[0]);
+    let (origin, goal) = match query_validated_pair(client, config).await? {
+        Some(pair) => pair,
+        None => return Ok(()),
+    };
 
-    for article in &path[1..] {
-        print!(" -> {}", article);
+    if origin == goal {
+        println!("Please input two different articles.");
+        return Ok(());
     }
-    print!{"\n"};
+
+    compare_between(&origin, &goal, client, config).await.map(|_| ())
 }
 
-/// A function for getting two article names from the user
+/// An async func for finding the shortest cycle through a user given article. Should be called from the core loop
+/// 
+/// # Arguments
+/// 
+/// * 'session' - A reference to the Session the crawl is run through
 /// 
 /// # Returns
 /// 
-/// * Option<(String, String)> - An option tuple of the recieved strings, None in the case of error
-async fn query_names() -> Option<(String, String)> {
-    let start_article = match get_user_input("Give the name of the starting article: ").await {
-        Some(string) => {
-            string
-        },
-        None => {
-            println!("Something went wrong while reading input!");
-            return None;
-        },
-    };
-
-    let goal_article = match get_user_input("Give the name of the finishing article: ").await {
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn cycle(session: &Session) -> Result<(), Box<dyn Error>> {
+    if session.config().undirected {
+        println!("Every link is a cycle when followed both ways, so cycles can't be searched with --undirected.");
+        return Ok(());
+    }
+    if session.config().goal_category.is_some() {
+        println!("Cycles end where they start, so they can't be searched with --to-category.");
+        return Ok(());
+    }
+    let name = match get_user_input("Give the name of the article: ").await {
         Some(string) => string,
         None => {
             println!("Something went wrong while reading input!");
-            return None;
+            return Ok(());
         },
     };
 
-    Some((start_article, goal_article))
+    match session.validate(&name).await? {
+        Some(article) => find_cycle(&article, session).await.map(|_| ()),
+        None => Ok(()),
+    }
 }
 
-// https://users.rust-lang.org/t/how-to-get-user-input/5176/8
+/// An async function for crawling the shortest cycle leading from a validated article back to itself
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the validated title of the article
+/// * 'session' - A reference to the Session the crawl is run through
+/// 
+/// # Returns
+/// 
+/// * Result<i32, Box<dyn Error>> - Result containing the exit code telling how the crawl ended, or possible errors
+async fn find_cycle(article: &ArticleTitle, session: &Session) -> Result<i32, Box<dyn Error>> {
+    let result = session.crawl(article, article, LinkDirection::Outgoing).await?;
+    if let Some(path) = result.path() {
+        println!("The shortest cycle through '{}' is {} links long:", article, path.len() - 1);
+        print_path(path, None, session.client(), session.config()).await;
+    }
+    statistics::print_summary(&result.stats);
+    session.export(article, article, "cycle", &result, None).await?;
+    Ok(exit_code(&result.outcome))
+}
 
-/// A function for simply recieving user input. Basically functions like python's input()
+/// An async function for writing the paths found by a crawl in the format of the Six Degrees of Wikipedia project
+/// into the file given with --sdow, if one was given
 /// 
 /// # Arguments
 /// 
-/// 'prompt' - A string slice to prompt the user with while querying input
+/// * 'origin' - A reference to the title of the origin article of the crawl
+/// * 'goal' - A reference to the title of the goal article of the crawl
+/// * 'result' - A reference to the CrawlResult of the crawl
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
 /// 
 /// # Returns
 /// 
-/// * Option<String> - An Option containing the recieved String or None in the case of error
-pub async fn get_user_input(prompt: &str) -> Option<String> {
-    print!("{}", prompt);
-    let _ = stdout().flush();
-    let mut input = String::new();
-    match io::stdin().read_line(&mut input) {
-        Ok(_) => {},
-        Err(_) => return None,
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn write_sdow_report(origin: &ArticleTitle, goal: &ArticleTitle, result: &crawler::CrawlResult,
+                           client: &WikiClient, config: &configs::Config) -> Result<(), Box<dyn Error>> {
+    let file = match &config.sdow_output {
+        Some(file) => file,
+        None => return Ok(()),
+    };
+    let paths: Vec<&Vec<ArticleTitle>> = result.path().into_iter().chain(result.other_paths.iter()).collect();
+    let report = sdow::sdow_report(origin, goal, &paths, client).await?;
+    statistics::write_report(file, &report).await
+}
+
+/// An async function for crawling from a validated article to the closest article whose introduction meets the given
+/// condition
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the article to start from
+/// * 'condition' - The StopCondition the crawl ends at
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+/// 
+/// # Returns
+/// 
+/// * Result<i32, Box<dyn Error>> - Result containing the exit code telling how the crawl ended, or possible errors
+async fn find_text(origin: &ArticleTitle, condition: StopCondition, client: &WikiClient, config: &configs::Config)
+    -> Result<i32, Box<dyn Error>> {
+    let skip_log = match &config.skip_log {
+        Some(file) => Some(Arc::new(SkipLog::open(file)?)),
+        None => None,
+    };
+
+    let description = condition.describe();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut options = crawl_options(config, skip_log.clone(), &cancel)?;
+    options.stop_condition = Some(Arc::new(condition));
+    options.max_paths = 1;
+    let graph = options.graph.clone();
+    println!("\nCrawling from '{}' to the closest article mentioning {}:", origin, description);
+    // The goal is never looked for, as links to it don't end crawls with a stop condition
+    let crawler_arc = crawler::Crawler::new_arc(origin, origin, LinkDirection::Outgoing, options);
+    set_running_crawl(Some(cancel));
+    let result = crawler::start(crawler_arc, client).await;
+    set_running_crawl(None);
+    if let Some(graph) = graph {
+        finish_graph(&graph, &result, LinkDirection::Outgoing);
     }
-    Some(input.trim().to_string())
+    if let Some(skip_log) = skip_log {
+        skip_log.flush(&crawler::ErrorReporter::default());
+    }
+
+    match &result.outcome {
+        CrawlOutcome::Found(path) => {
+            println!("'{}' is {} links away and mentions {}:", path[path.len() - 1], path.len() - 1, description);
+            if let Some(text) = &result.matched_text {
+                println!("  {}\n", text);
+            }
+            let hops = path_hops(path, client, config).await;
+            print_path(path, hops.as_deref(), client, config).await;
+        },
+        CrawlOutcome::NoPath => println!("No article reachable from '{}' mentions {}.", origin, description),
+        CrawlOutcome::BudgetExhausted(partial) => {
+            println!("No article mentioning {} was found before the crawl budget ran out.", description);
+            println!("The furthest the crawl got ({} links, INCOMPLETE):\n{} -> ...", partial.len() - 1,
+                     format_path(partial));
+        },
+        CrawlOutcome::Cancelled(partial) => {
+            println!("The crawl was cancelled before an article mentioning {} was found.", description);
+            println!("The furthest the crawl got ({} links, INCOMPLETE):\n{} -> ...", partial.len() - 1,
+                     format_path(partial));
+        },
+        CrawlOutcome::Aborted(error, partial) => {
+            eprintln!("Error: the crawl was aborted: {}", error);
+            println!("The furthest the crawl got ({} links, INCOMPLETE):\n{} -> ...", partial.len() - 1,
+                     format_path(partial));
+        },
+        CrawlOutcome::Failed(error) => eprintln!("Error: the crawl failed: {}", error),
+    }
+    statistics::print_summary(&result.stats);
+
+    if let Some(file) = &config.json_output {
+        let found = result.path().and_then(|path| path.last()).unwrap_or(origin);
+        let report = statistics::crawl_report(origin, found, "find", result.path(), &result.stats);
+        statistics::write_report(file, &report).await?;
+    }
+    Ok(exit_code(&result.outcome))
+}
+
+/// An async function for querying two article names from the user and validating them
+/// 
+/// # Arguments
+/// 
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the config data of the program
+/// 
+/// # Returns
+/// 
+/// * Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> - Result containing the validated pair, or None
+///   if the user should be returned to the menu
+async fn query_validated_pair(client: &WikiClient, config: &configs::Config)
+    -> Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> {
+
+    let (origin, goal) = match query_names(client, config).await? {
+        Some(tuple) => tuple,
+
+        // Raising an error manually takes some serious work in rust, huh?
+        None => return Err(Box::new(io::Error::other(
+            "Error while getting article names from user."))),
+    };
+
+    if origin.is_empty() || goal.is_empty() {
+        println!("Please input two article names.");
+        return Ok(None);
+    }
+
+    println!("\nValidating given articles' existence...\n");
+    let pair = match validate_pair(&origin, &goal, client, config).await? {
+        Some(pair) => pair,
+        None => return Ok(None),
+    };
+
+    if config.preview && pair.0 != pair.1 && !confirm_pair(&pair.0, &pair.1, client).await {
+        println!("Cancelling operation...");
+        return Ok(None);
+    }
+    Ok(Some(pair))
+}
+
+/// An async function for showing the user the first sentence and the link counts of both articles of a crawl and
+/// asking whether to start it, so that hopeless pairs can be abandoned before spending API calls on them
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the origin article
+/// * 'goal' - A reference to the validated title of the goal article
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * bool - True if the crawl should be started
+async fn confirm_pair(origin: &ArticleTitle, goal: &ArticleTitle, client: &WikiClient) -> bool {
+    let pair = [origin.clone(), goal.clone()];
+    let (sentences, origin_degree, goal_degree) = futures::join!(
+        wiki_api::first_sentences(&pair, client),
+        wiki_api::article_degree(origin, 1, client),
+        wiki_api::article_degree(goal, 1, client));
+    let sentences = sentences.unwrap_or_else(|error| {
+        eprintln!("Error fetching the introductions of the articles:\n{}", error);
+        Default::default()
+    });
+
+    for (role, article, degree) in [("Origin", origin, &origin_degree), ("Goal", goal, &goal_degree)] {
+        println!("{}: {}", role, article);
+        if let Some(sentence) = sentences.get(article) {
+            println!("  {}", sentence);
+        }
+        match degree {
+            Ok(wiki_api::Degree { outbound, inbound: Some(inbound), .. }) => {
+                println!("  {} outbound links, {} inbound links", outbound, inbound);
+            },
+            Ok(degree) => println!("  {} outbound links", degree.outbound),
+            Err(error) => eprintln!("Error counting the links of '{}':\n{}", article, error),
+        }
+    }
+
+    // A crawl can't leave an origin without links or reach a goal nothing links to
+    if origin_degree.is_ok_and(|degree| degree.outbound == 0) {
+        println!("\nThe origin doesn't link to any article, so no path can be found from it.");
+    }
+    if goal_degree.is_ok_and(|degree| degree.inbound == Some(0)) {
+        println!("\nNo article links to the goal, so no path can be found to it.");
+    }
+
+    loop {
+        match get_user_input("\nStart crawl? (y/n): ").await {
+            Some(answer) => match answer.to_lowercase().as_str() {
+                "y" | "yes" => return true,
+                "n" | "no" => return false,
+                _ => println!("Please answer y or n."),
+            },
+            None => return false,
+        }
+    }
+}
+
+/// An async function for choosing the direction of a crawl between two validated articles. A forward crawl fans out
+/// from the links of the origin and a reverse crawl from the backlinks of the goal, so when the origin has far more
+/// links than the goal has backlinks, the user is offered a reverse crawl instead, or it's chosen right away with
+/// --auto-direction. The reverse crawl finds the same path from the origin to the goal
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the origin article
+/// * 'goal' - A reference to the validated title of the goal article
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the config data of the program
+/// 
+/// # Returns
+/// 
+/// * LinkDirection - The direction to crawl in, Outgoing unless a reverse crawl was chosen
+async fn choose_direction(origin: &ArticleTitle, goal: &ArticleTitle, client: &WikiClient, config: &configs::Config)
+    -> LinkDirection {
+    if config.undirected || config.as_of.is_some() || config.anchor_pattern.is_some()
+        || !client.capabilities().backlinks {
+        return LinkDirection::Outgoing;
+    }
+    // A cached path answers a forward crawl without expanding any articles
+    let cache = client.cache().filter(|_| !config.restricts_paths() && config.path_count == 1);
+    if cache.is_some_and(|cache| cache.get_path(origin, goal, LinkDirection::Outgoing).is_some()) {
+        return LinkDirection::Outgoing;
+    }
+
+    let (origin_degree, goal_degree) = futures::join!(
+        wiki_api::article_degree(origin, 0, client),
+        wiki_api::article_degree(goal, 0, client));
+    let (outbound, inbound) = match (origin_degree, goal_degree) {
+        (Ok(origin_degree), Ok(wiki_api::Degree { inbound: Some(inbound), .. })) => (origin_degree.outbound, inbound),
+        _ => return LinkDirection::Outgoing,
+    };
+    // Nothing reaches a goal without backlinks in either direction
+    if inbound == 0 || inbound.saturating_mul(DIRECTION_SWAP_RATIO) > outbound {
+        return LinkDirection::Outgoing;
+    }
+
+    println!("\n'{}' has {} links, but only {} articles link to '{}', so crawling backwards from the goal along the \
+              backlinks is likely much cheaper.", origin, outbound, inbound, goal);
+    if config.auto_direction {
+        println!("Crawling backwards from '{}'...", goal);
+        return LinkDirection::Incoming;
+    }
+    loop {
+        match get_user_input("Crawl backwards instead? (y/n): ").await {
+            Some(answer) => match answer.to_lowercase().as_str() {
+                "y" | "yes" => return LinkDirection::Incoming,
+                "n" | "no" => return LinkDirection::Outgoing,
+                _ => println!("Please answer y or n."),
+            },
+            None => return LinkDirection::Outgoing,
+        }
+    }
+}
+
+/// An async function for turning the input of the user into an article title. The input can also be the url of the
+/// page of the article, in which case the title is read out of it. If the url is on another wiki than the session,
+/// the user is asked whether to switch to that wiki
+/// 
+/// # Arguments
+/// 
+/// * 'input' - A string slice with the article name or page url given by the user
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the config data of the program
+/// 
+/// # Returns
+/// 
+/// * Result<ArticleTitle, Box<dyn Error>> - Result containing the title, or a WikiSwitch error if the user chose to
+///   switch to the wiki of the url
+async fn input_title(input: &str, client: &WikiClient, config: &configs::Config)
+    -> Result<ArticleTitle, Box<dyn Error>> {
+
+    let url = match title_url::parse_title_url(input) {
+        Some(url) => url,
+        None => return Ok(client.title(input)),
+    };
+    // The title of the url is normalized by the rules of the wiki connected to, another wiki normalizes it anew
+    let title = client.title(url.title.as_str());
+    // The fixture server stands in for any wiki, so the host of the url doesn't matter with it
+    if config.api_fixture.is_some() || title_url::host_of(client.api().api_url()).as_deref() == Some(&url.host) {
+        return Ok(title);
+    }
+
+    println!("'{}' is on the wiki at '{}', but the program is connected to '{}'.", url.title.as_str(), url.host,
+             config.api_path);
+    loop {
+        let prompt = format!("Switch to '{}'? (y/n): ", url.host);
+        match get_user_input(&prompt).await {
+            Some(answer) => match answer.to_lowercase().as_str() {
+                "y" | "yes" => return Err(Box::new(WikiSwitch { api_path: url.api_path })),
+                "n" | "no" => return Ok(title),
+                _ => println!("Please answer y or n."),
+            },
+            None => return Ok(title),
+        }
+    }
+}
+
+/// An async function for asking the user whether to continue without logging in after logging in failed
+/// 
+/// # Returns
+/// 
+/// * bool - True if the program should continue anonymously, false if it should exit
+async fn confirm_anonymous() -> bool {
+    println!("\nThe program can continue without logging in, with the lower limits of an anonymous user and at most \
+              {} request per second.", DEGRADED_RATE);
+    loop {
+        match get_user_input("Continue anonymously? (y/n): ").await {
+            Some(answer) => match answer.to_lowercase().as_str() {
+                "y" | "yes" => return true,
+                "n" | "no" => return false,
+                _ => println!("Please answer y or n."),
+            },
+            None => return false,
+        }
+    }
+}
+
+/// An async function for validating an origin and a goal. Both are searched for at the same time, and the links of
+/// the origin are prefetched in the background while the user is still choosing the goal, so that the crawl can
+/// start right away
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the title of the origin article given by the user
+/// * 'goal' - A reference to the title of the goal article given by the user
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the search settings
+/// 
+/// # Returns
+/// 
+/// * Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> - Result containing the validated pair, or None
+///   if the user should be returned to the menu
+async fn validate_pair(origin: &ArticleTitle, goal: &ArticleTitle, client: &WikiClient, config: &configs::Config)
+    -> Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> {
+    let (origin_search, goal_search) = futures::join!(
+        search_article(origin, client, config),
+        search_article(goal, client, config));
+
+    let origin = match resolve_search(origin, origin_search, config).await? {
+        Some(title) => title,
+        None => return Ok(None),
+    };
+    wiki_api::prefetch_links(&origin, LinkDirection::Outgoing, client);
+
+    let goal = match resolve_search(goal, goal_search, config).await? {
+        Some(title) => title,
+        None => return Ok(None),
+    };
+
+    Ok(Some((origin, goal)))
+}
+
+/// An async function for running a single crawl between two validated articles
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the origin article
+/// * 'goal' - A reference to the validated title of the goal article
+/// * 'direction' - The LinkDirection the crawl follows links in
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+/// 
+/// # Returns
+/// 
+/// * Result<crawler::CrawlResult, Box<dyn Error>> - How the crawl ended and the crawl statistics. Errors if the
+///   skip log, the progress output or the visited titles database couldn't be opened
+async fn crawl_between(origin: &ArticleTitle, goal: &ArticleTitle, direction: LinkDirection, client: &WikiClient,
+                       config: &configs::Config) -> Result<crawler::CrawlResult, Box<dyn Error>> {
+    // A cached path is only one of the shortest paths, so crawls collecting several of them don't read it
+    let cache = client.cache().filter(|_| !config.restricts_paths() && config.path_count == 1);
+    if let Some(cached) = cache.and_then(|cache| cache.get_path(origin, goal, direction)) {
+        println!("Found a previously crawled path from the cache.");
+        let path = if config.verify_cache {
+            verify_cached_path(cached, direction, client, config).await?
+        } else {
+            Some(cached.path)
+        };
+        if let Some(path) = path {
+            let cached = format!("Read a path of {} links from '{}' to '{}' from the cache: {}", path.len() - 1,
+                                 origin, goal, format_path(&path));
+            transcript::record(transcript::Entry::Result, &cached);
+            return Ok(crawler::CrawlResult::without_crawl(path));
+        }
+        println!("Couldn't repair the cached path, crawling the whole path again.");
+    }
+    run_crawl(origin, goal, direction, client, config).await
+}
+
+/// An async function for checking whether the articles along a cached path have been edited since the path was
+/// cached, and crawling again only the links of the edited articles. Consecutive edited articles are crawled
+/// through as one segment
+/// 
+/// # Arguments
+/// 
+/// * 'cached' - The CachedPath read from the cache
+/// * 'direction' - The LinkDirection of the crawl that found the path
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+/// 
+/// # Returns
+/// 
+/// * Result<Option<Vec<ArticleTitle>>, Box<dyn Error>> - The verified or repaired path, or None if a segment
+///   couldn't be crawled again
+async fn verify_cached_path(cached: CachedPath, direction: LinkDirection, client: &WikiClient,
+                            config: &configs::Config) -> Result<Option<Vec<ArticleTitle>>, Box<dyn Error>> {
+    let CachedPath { path, found_at } = cached;
+    let cache = match client.cache() {
+        Some(cache) => cache,
+        None => return Ok(Some(path)),
+    };
+    let edited = wiki_api::last_edited(&path[..path.len() - 1], client).await?;
+
+    // The link between two articles is written in the first one, but the backlinks are cached under the second
+    let cache_key = |hop: usize| match direction {
+        LinkDirection::Outgoing => &path[hop],
+        LinkDirection::Incoming => &path[hop + 1],
+    };
+    let broken: Vec<usize> = (0..path.len() - 1)
+        .filter(|hop| {
+            let cached_at = cache.links_fetched_at(cache_key(*hop), direction).map_or(found_at, |fetched_at| {
+                fetched_at.min(found_at)
+            });
+            edited.get(&path[*hop]).is_none_or(|edited_at| *edited_at > cached_at)
+        })
+        .collect();
+
+    if broken.is_empty() {
+        println!("None of the articles on the cached path have been edited since.");
+        return Ok(Some(path));
+    }
+    println!("{} articles on the cached path have been edited since, checking their links again.", broken.len());
+    let stale: Vec<ArticleTitle> = broken.iter().map(|hop| cache_key(*hop).clone()).collect();
+    cache.remove_links(&stale, direction);
+
+    let mut repaired: Vec<ArticleTitle> = vec!();
+    let mut hop = 0;
+    while hop < path.len() - 1 {
+        if !broken.contains(&hop) {
+            repaired.push(path[hop].clone());
+            hop += 1;
+            continue;
+        }
+        let mut end = hop + 1;
+        while broken.contains(&end) {
+            end += 1;
+        }
+        let segment = match run_crawl(&path[hop], &path[end], direction, client, config).await?.into_path() {
+            Some(segment) => segment,
+            None => return Ok(None),
+        };
+        repaired.extend(segment[..segment.len() - 1].iter().cloned());
+        hop = end;
+    }
+
+    // A new segment can pass through an article already on the path, in which case the loop is cut out. The goal of
+    // a cycle is its origin, so it's added after the cutting
+    let mut shortened: Vec<ArticleTitle> = vec!();
+    for title in repaired {
+        if let Some(position) = shortened.iter().position(|other| other == &title) {
+            shortened.truncate(position);
+        }
+        shortened.push(title);
+    }
+    let goal = &path[path.len() - 1];
+    if let Some(position) = shortened.iter().position(|other| other == goal).filter(|position| *position > 0) {
+        shortened.truncate(position);
+    }
+    shortened.push(goal.clone());
+    cache.store_path(&shortened, direction);
+    Ok(Some(shortened))
+}
+
+/// An async function for crawling between two validated articles without reading the path from the cache. The
+/// found path is stored into the cache
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the origin article
+/// * 'goal' - A reference to the validated title of the goal article
+/// * 'direction' - The LinkDirection the crawl follows links in
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+/// 
+/// # Returns
+/// 
+/// * Result<crawler::CrawlResult, Box<dyn Error>> - How the crawl ended and the crawl statistics. Errors if the
+///   skip log, the progress output or the visited titles database couldn't be opened
+async fn run_crawl(origin: &ArticleTitle, goal: &ArticleTitle, direction: LinkDirection, client: &WikiClient,
+                   config: &configs::Config) -> Result<crawler::CrawlResult, Box<dyn Error>> {
+    let skip_log = match &config.skip_log {
+        Some(file) => Some(Arc::new(SkipLog::open(file)?)),
+        None => None,
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let options = crawl_options(config, skip_log.clone(), &cancel)?;
+    let graph = options.graph.clone();
+    let crawler_arc = crawler::Crawler::new_arc(origin, goal, direction, options);
+    set_running_crawl(Some(cancel));
+    let result = crawler::start(crawler_arc, client).await;
+    set_running_crawl(None);
+    if let Some(graph) = graph {
+        finish_graph(&graph, &result, direction);
+    }
+
+    match &result.outcome {
+        CrawlOutcome::Found(path) => {
+            if let Some(cache) = client.cache().filter(|_| !config.restricts_paths()) {
+                cache.store_path(path, direction);
+            }
+        },
+        CrawlOutcome::NoPath if !result.stats.is_complete() => println!(
+            "No path from '{}' to '{}' was found, but some articles were left out of the crawl, so one may still \
+             exist.", origin, goal),
+        CrawlOutcome::NoPath => println!("There is no path from '{}' to '{}', every article the crawl could reach \
+                                          was analyzed without finding the goal.", origin, goal),
+        CrawlOutcome::BudgetExhausted(partial) => {
+            println!("No path was found before the crawl budget ran out.");
+            print_partial(partial, origin, goal, direction);
+        },
+        CrawlOutcome::Cancelled(partial) => {
+            println!("The crawl was cancelled before a path was found.");
+            print_partial(partial, origin, goal, direction);
+        },
+        CrawlOutcome::Aborted(error, partial) => {
+            eprintln!("Error: the crawl was aborted: {}", error);
+            print_partial(partial, origin, goal, direction);
+        },
+        CrawlOutcome::Failed(error) => eprintln!("Error: the crawl failed: {}", error),
+    }
+    record_result(origin, goal, direction, &result);
+    if let Some(skip_log) = skip_log {
+        skip_log.flush(&crawler::ErrorReporter::default());
+    }
+    Ok(result)
+}
+
+/// A function for choosing the exit code of a command running a single crawl, so that scripts can tell the ways the
+/// crawl ended apart without reading its output
+/// 
+/// # Arguments
+/// 
+/// * 'outcome' - A reference to the CrawlOutcome of the crawl
+/// 
+/// # Returns
+/// 
+/// * i32 - The exit code for the outcome
+fn exit_code(outcome: &CrawlOutcome) -> i32 {
+    match outcome {
+        CrawlOutcome::Found(_) => EXIT_FOUND,
+        CrawlOutcome::NoPath => EXIT_NO_PATH,
+        CrawlOutcome::BudgetExhausted(_) => EXIT_BUDGET_EXHAUSTED,
+        CrawlOutcome::Cancelled(_) => EXIT_CANCELLED,
+        CrawlOutcome::Aborted(..) | CrawlOutcome::Failed(_) => EXIT_CRAWL_FAILED,
+    }
+}
+
+/// A function for recording how a crawl ended into the transcript
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the title of the origin article of the crawl
+/// * 'goal' - A reference to the title of the goal article of the crawl
+/// * 'direction' - The LinkDirection the crawl followed links in
+/// * 'result' - A reference to the CrawlResult of the crawl
+fn record_result(origin: &ArticleTitle, goal: &ArticleTitle, direction: LinkDirection,
+                 result: &crawler::CrawlResult) {
+    let crawl = format!("Crawl from '{}' to '{}' following {} links", origin, goal, direction.as_str());
+    let outcome = match &result.outcome {
+        CrawlOutcome::Found(path) => format!("found a path of {} links: {}", path.len() - 1, format_path(path)),
+        CrawlOutcome::NoPath => "found no path".to_string(),
+        CrawlOutcome::BudgetExhausted(_) => "ran out of its budget before finding a path".to_string(),
+        CrawlOutcome::Cancelled(_) => "was cancelled before finding a path".to_string(),
+        CrawlOutcome::Aborted(error, _) => format!("was aborted: {}", error),
+        CrawlOutcome::Failed(error) => format!("failed: {}", error),
+    };
+    transcript::record(transcript::Entry::Result, &format!("{} {} with {} API calls", crawl, outcome,
+                                                           result.stats.total_api_calls()));
+}
+
+/// A function for constructing the options of a crawl out of the config
+/// 
+/// # Arguments
+/// 
+/// * 'config' - A reference to the Config struct with the crawl settings
+/// * 'skip_log' - An option containing the opened skip log, if one was asked for
+/// * 'cancel' - A reference to the flag the crawl is cancelled with
+/// 
+/// # Returns
+/// 
+/// * Result<crawler::CrawlOptions, Box<dyn Error>> - The options, or an error if the progress output, the graph
+///   export or the visited titles database couldn't be opened
+fn crawl_options(config: &configs::Config, skip_log: Option<Arc<SkipLog>>, cancel: &Arc<AtomicBool>)
+    -> Result<crawler::CrawlOptions, Box<dyn Error>> {
+    let progress = match &config.progress_json {
+        Some(target) => Some(Arc::new(ProgressWriter::open(target)?)),
+        None => None,
+    };
+    let graph = match &config.graph_output {
+        Some(file) => Some(Arc::new(GraphWriter::open(file)?)),
+        None => None,
+    };
+
+    Ok(crawler::CrawlOptions {
+        visited: VisitedSet::new(config.visited_mode, config.cache_file.as_deref())?,
+        skip_log,
+        graph,
+        provenance: config.provenance.then(|| Arc::new(ProvenanceMap::default())),
+        deterministic: config.deterministic,
+        progress,
+        must_exist_in: config.must_exist_in.clone(),
+        title_filter: config.title_filter.clone(),
+        stop_condition: None,
+        max_paths: config.path_count,
+        max_frontier: config.max_frontier,
+        approximate: config.approximate,
+        undirected: config.undirected,
+        estimate_links: config.estimate_links,
+        goals: config.category_members.clone(),
+        loose_goal_match: config.loose_goal_match,
+        budget: crawler::CrawlBudget { time: config.time_limit, api_calls: config.max_api_calls },
+        display: config.display,
+        cancel: Arc::clone(cancel),
+        errors: None,
+        cleanup_grace: config.cleanup_grace,
+    })
+}
+
+/// A function for marking the outcome and the found path of a finished crawl in its exported graph
+/// 
+/// # Arguments
+/// 
+/// * 'graph' - A reference to the GraphWriter of the crawl
+/// * 'result' - A reference to the CrawlResult of the crawl
+/// * 'direction' - The LinkDirection the crawl followed links in
+fn finish_graph(graph: &GraphWriter, result: &crawler::CrawlResult, direction: LinkDirection) {
+    // The edges lead the way the crawl went, so the path of a crawl following incoming links is turned around
+    let mut path = result.path().cloned();
+    if let (Some(path), LinkDirection::Incoming) = (&mut path, direction) {
+        path.reverse();
+    }
+    graph.finish(result.outcome.as_str(), path.as_deref(), &crawler::ErrorReporter::default());
+}
+
+/// A function for setting the cancel flag of the crawl Ctrl+C should cancel
+/// 
+/// # Arguments
+/// 
+/// * 'cancel' - An option containing the cancel flag of the crawl starting, or None once it has ended
+fn set_running_crawl(cancel: Option<Arc<AtomicBool>>) {
+    match RUNNING_CRAWL.lock() {
+        Ok(mut running) => *running = cancel,
+        Err(error) => eprintln!("Error acquiring lock for the running crawl:\n{:?}", error),
+    }
+}
+
+/// A function for printing the partial path of a crawl that ended early, with the unexplored end of the path left
+/// open
+/// 
+/// # Arguments
+/// 
+/// * 'partial' - A slice of the titles on the partial path
+/// * 'origin' - A reference to the title of the origin article
+/// * 'goal' - A reference to the title of the goal article
+/// * 'direction' - The LinkDirection the crawl followed links in
+fn print_partial(partial: &[ArticleTitle], origin: &ArticleTitle, goal: &ArticleTitle, direction: LinkDirection) {
+    println!("The furthest the crawl got ({} links, INCOMPLETE):", partial.len() - 1);
+    match direction {
+        LinkDirection::Outgoing => println!("{} -> ... -> {}", format_path(partial), goal),
+        LinkDirection::Incoming => println!("{} -> ... -> {}", origin, format_path(partial)),
+    }
+}
+
+/// An async function for running a forward crawl and a backlink based reverse crawl between two validated
+/// articles and printing their paths and costs side by side
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the origin article
+/// * 'goal' - A reference to the validated title of the goal article
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+/// 
+/// # Returns
+/// 
+/// * Result<i32, Box<dyn Error>> - Result containing the exit code of the first crawl that didn't find a path, or
+///   EXIT_FOUND if both found one, or possible errors
+async fn compare_between(origin: &ArticleTitle, goal: &ArticleTitle, client: &WikiClient, config: &configs::Config)
+    -> Result<i32, Box<dyn Error>> {
+    let mut results = vec!();
+
+    for (name, direction) in [("Forward", LinkDirection::Outgoing), ("Reverse", LinkDirection::Incoming)].iter() {
+        println!("\n{} crawl from '{}' to '{}':", name, origin, goal);
+        let result = crawl_between(origin, goal, *direction, client, config).await?;
+        results.push((name, result));
+    }
+
+    println!("\n{:<12}{:<10}API calls", "Direction", "Length");
+    for (name, result) in results.iter() {
+        let length = match result.path() {
+            Some(path) => (path.len() - 1).to_string(),
+            None => String::from("-"),
+        };
+        println!("{:<12}{:<10}{}", name, length, result.stats.total_api_calls());
+    }
+
+    println!();
+    for (name, result) in results.iter() {
+        if let Some(path) = result.path() {
+            println!("{} path: {}", name, format_path(path));
+        }
+    }
+
+    if let Some(file) = &config.json_output {
+        let reports: Vec<serde_json::Value> = results.iter().map(|(name, result)| statistics::crawl_report(
+            origin, goal, &name.to_lowercase(), result.path(), &result.stats)).collect();
+        statistics::write_report(file, &serde_json::Value::Array(reports)).await?;
+    }
+    Ok(results.iter().map(|(_, result)| exit_code(&result.outcome)).find(|code| *code != EXIT_FOUND)
+        .unwrap_or(EXIT_FOUND))
+}
+
+/// An async function for crawling between two validated articles repeatedly, comparing each found path with the
+/// previously recorded one and reporting when the route or its length has changed. Runs until the program is
+/// stopped
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the origin article
+/// * 'goal' - A reference to the validated title of the goal article
+/// * 'every' - The Duration to wait between the checks
+/// * 'log' - A string slice with the path of the watch log the checks are recorded into
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn watch_pair(origin: &ArticleTitle, goal: &ArticleTitle, every: std::time::Duration, log: &str,
+                    client: &WikiClient, config: &configs::Config) -> Result<(), Box<dyn Error>> {
+    println!("Watching the path from '{}' to '{}', checking every {}. Press Ctrl+C to stop.",
+             origin, goal, watch::format_interval(every));
+
+    loop {
+        println!("\nChecking the path from '{}' to '{}'...", origin, goal);
+        let result = crawl_between(origin, goal, LinkDirection::Outgoing, client, config).await?;
+        if let Some(file) = &config.json_output {
+            let report = statistics::crawl_report(origin, goal, "forward", result.path(), &result.stats);
+            statistics::write_report(file, &report).await?;
+        }
+        if let CrawlOutcome::Cancelled(_) = result.outcome {
+            println!("Stopped watching.");
+            return Ok(());
+        }
+        // Every following check would fail the same way
+        if let CrawlOutcome::Aborted(error, _) = result.outcome {
+            return Err(error.into());
+        }
+
+        // A failed crawl says nothing about the link graph, so it isn't compared or recorded
+        match result.into_path().and_then(watch::WatchRecord::new) {
+            Some(record) => {
+                let previous = watch::last_record(log, origin, goal);
+                let change = watch::compare_paths(previous.as_ref(), &record.path);
+                println!("{}", format_path(&record.path));
+                match change {
+                    watch::PathChange::First => println!("First check of the pair, recorded the path."),
+                    watch::PathChange::Unchanged => println!("The path is unchanged."),
+                    watch::PathChange::Rerouted => println!("\x07The path has changed! It's as long as before \
+                                                             but goes through different articles."),
+                    watch::PathChange::LengthChanged { before, after } => println!(
+                        "\x07The path has changed! Its length went from {} to {} links.", before, after),
+                }
+                if let (true, Some(previous)) = (change.is_drift(), &previous) {
+                    println!("Previous path: {}", format_path(&previous.path));
+                }
+                watch::append_record(log, &record, change)?;
+            },
+            None => println!("The check failed, trying again at the next check."),
+        }
+
+        println!("Next check in {}.", watch::format_interval(every));
+        tokio::time::sleep(every).await;
+    }
+}
+
+/// An async function for fetching the links of the hub articles into the cache, again and again at the given
+/// interval if there is one. The most viewed articles are listed again for each warm-up, as they change from day to
+/// day, and a failed warm-up is retried at the next one
+/// 
+/// # Arguments
+/// 
+/// * 'hubs' - An option containing the path of the file listing the hub articles, or None for the most viewed
+///   articles of the wiki
+/// * 'top' - The most hub articles to warm up
+/// * 'every' - An option containing the Duration between the warm-ups, or None for a single warm-up
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn warm_cache(hubs: Option<&str>, top: usize, every: Option<std::time::Duration>, client: &WikiClient)
+    -> Result<(), Box<dyn Error>> {
+    if client.cache().is_none() {
+        return Err(Box::new(io::Error::new(io::ErrorKind::InvalidInput,
+                                           "The warm-cache command can't be used with --no-cache")));
+    }
+    if hubs.is_none() && !client.capabilities().most_viewed {
+        return Err(Box::new(io::Error::new(io::ErrorKind::Unsupported,
+            "The wiki doesn't list its most viewed articles, give the hub articles with --hubs instead")));
+    }
+    if let Some(every) = every {
+        println!("Warming up the cache every {}. Press Ctrl+C to stop.", watch::format_interval(every));
+    }
+    // A scheduled warm-up refreshes the links it fetched the previous time
+    let refresh_age = every.unwrap_or(warm_up::DEFAULT_REFRESH_AGE);
+
+    loop {
+        let warmed = async {
+            let mut articles = match hubs {
+                Some(file) => warm_up::read_hubs(file, client.capabilities().title_case)?,
+                None => {
+                    println!("\nListing the {} most viewed articles...", top);
+                    wiki_api::most_viewed(top, client).await?
+                },
+            };
+            articles.truncate(top);
+            println!("Warming up the cache with the links of {} hub articles...", articles.len());
+            warm_up::warm_up(&articles, refresh_age, client).await
+        }.await;
+
+        match (warmed, every) {
+            (Ok(summary), _) => println!("Fetched the links of {} hubs ({} links) with {} API calls, {} hubs were \
+                                          already cached within {}.", summary.fetched, summary.links,
+                                         summary.api_calls, summary.fresh, watch::format_interval(refresh_age)),
+            (Err(error), None) => return Err(error),
+            (Err(error), Some(_)) => eprintln!("Error warming up the cache, trying again at the next warm-up:\n{}",
+                                               error),
+        }
+
+        match every {
+            Some(every) => {
+                println!("Next warm-up in {}.", watch::format_interval(every));
+                tokio::time::sleep(every).await;
+            },
+            None => return Ok(()),
+        }
+    }
+}
+
+/// An async function for checking whether the titles listed in a file lead to articles, in batches of titles. The
+/// statuses are written as CSV into the output file along with a summary, or printed if there is no output file
+/// 
+/// # Arguments
+/// 
+/// * 'file' - A string slice with the path of the file listing the titles
+/// * 'output' - An option containing the path of the CSV file to write, or None for printing the CSV
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn check_existence(file: &str, output: Option<&str>, client: &WikiClient) -> Result<(), Box<dyn Error>> {
+    let given = existence::read_titles(file)?;
+    let titles: Vec<ArticleTitle> = given.iter().map(|title| client.title(title)).collect();
+    let calls_before = client.request_count();
+    let statuses = wiki_api::check_titles(&titles, client).await?;
+    let csv = existence::to_csv(&given, &statuses);
+    match output {
+        Some(output) => {
+            output_sink::deliver(output, &csv, "text/csv").await?;
+            println!("{} with {} API calls, written into '{}'", existence::summary(&statuses),
+                     client.request_count() - calls_before, output);
+        },
+        None => print!("{}", csv),
+    }
+    Ok(())
+}
+
+/// An async function for listing the articles within the given amount of clicks of an article, grouped by depth.
+/// The listing is written into the output along with a summary, or printed if there is no output
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the validated title of the article
+/// * 'depth' - The largest amount of clicks
+/// * 'format' - The NeighborhoodFormat to write the listing in
+/// * 'output' - An option containing the output target, or None for printing the listing
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn list_neighborhood(article: &ArticleTitle, depth: usize, format: NeighborhoodFormat, output: Option<&str>,
+                           client: &WikiClient) -> Result<(), Box<dyn Error>> {
+    let calls_before = client.request_count();
+    let found = neighborhood::explore(article, depth, client).await?;
+    let listing = found.write(format, neighborhood::article_path(client).as_deref());
+    match output {
+        Some(output) => {
+            output_sink::deliver(output, &listing, format.content_type()).await?;
+            println!("{} articles around '{}' up to depth {} with {} API calls, written into '{}'", found.len(),
+                     article, found.depths.len() - 1, client.request_count() - calls_before, output);
+        },
+        None => print!("{}", listing),
+    }
+    Ok(())
+}
+
+/// An async function for guiding the user through a crawl on the demo wiki: validating the given articles, crawling
+/// between them, showing the path and writing the report. The found path is checked against the known shortest path
+/// of the demo wiki, so that the demo doubles as a test of the whole program
+/// 
+/// # Arguments
+/// 
+/// * 'session' - A reference to the Session connected to the demo wiki
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors, an error if the crawl didn't find the shortest
+///   path
+async fn run_demo(session: &Session) -> Result<(), Box<dyn Error>> {
+    let (client, config) = (session.client(), session.config());
+    println!("\nWelcome to the demo of EddieWikiCrawler! The crawler finds the shortest path of links between two \
+              articles of a wiki. The demo runs on a small wiki bundled with the program, so no account or network is \
+              needed.");
+
+    println!("\nStep 1/4: validating the articles. The names given are looked up from the wiki, following redirects \
+              and searching for the closest match if they aren't exact.");
+    println!("Origin: {}\nGoal: {}", demo::ORIGIN, demo::GOAL);
+    let (origin, goal) = match validate_pair(&client.title(demo::ORIGIN), &client.title(demo::GOAL), client,
+                                             config).await? {
+        Some(pair) => pair,
+        None => return Err(Box::new(io::Error::other("The articles of the demo didn't validate"))),
+    };
+
+    println!("\nStep 2/4: crawling from '{}' to '{}'. The links of the articles are fetched in batches and analyzed \
+              by worker threads, one level of links at a time, while the status line shows how far the crawl is.",
+             origin, goal);
+    let result = session.crawl(&origin, &goal, LinkDirection::Outgoing).await?;
+
+    println!("\nStep 3/4: the result. The path is printed along with the statistics of the crawl.");
+    let path = match result.path() {
+        Some(path) => path,
+        None => return Err(Box::new(io::Error::other("The demo crawl didn't find a path"))),
+    };
+    print_path(path, None, client, config).await;
+    statistics::print_summary(&result.stats);
+    if path.len() - 1 != demo::SHORTEST_PATH_LINKS {
+        return Err(Box::new(io::Error::other(format!(
+            "The demo crawl found a path of {} links, but the shortest path is {} links", path.len() - 1,
+            demo::SHORTEST_PATH_LINKS))));
+    }
+
+    println!("\nStep 4/4: exporting. The result is written as a JSON report, into the target given with --json or \
+              shown here if none was given.");
+    session.export(&origin, &goal, "forward", &result, None).await
+}
+
+/// An async function for printing the amount of links to and from an article, to help choosing the direction of a
+/// crawl
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the validated title of the article
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn print_degree(article: &ArticleTitle, client: &WikiClient) -> Result<(), Box<dyn Error>> {
+    let degree = wiki_api::article_degree(article, HUB_COUNT, client).await?;
+
+    println!("Degree of '{}':", article);
+    println!("{:<16}{}", "Outbound links", degree.outbound);
+    match degree.inbound {
+        Some(inbound) => println!("{:<16}{}", "Inbound links", inbound),
+        None => println!("{:<16}- (the wiki doesn't support backlinks)", "Inbound links"),
+    }
+
+    if !degree.hubs.is_empty() {
+        println!("\nThe most linked articles linking here:");
+        for (index, hub) in degree.hubs.iter().enumerate() {
+            println!("{:>3}: {}", index + 1, hub);
+        }
+    }
+
+    // A forward crawl fans out from the links of its origin and a reverse crawl from the backlinks of its goal
+    if let Some(inbound) = degree.inbound {
+        println!("\nAs an origin, a forward crawl starts from {} links. As a goal, a reverse crawl starts from {} \
+                  backlinks.", degree.outbound, inbound);
+    }
+    Ok(())
+}
+
+/// An async function for checking exhaustively whether a path of at most the given length leads between two
+/// validated articles, and printing the shortest one if it does
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - A reference to the validated title of the article the paths start from
+/// * 'goal' - A reference to the validated title of the article the paths end at
+/// * 'max_length' - The largest amount of links on the paths checked
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<i32, Box<dyn Error>> - Result containing EXIT_FOUND if a path was found and EXIT_NO_PATH if not, or
+///   possible errors
+async fn verify_path(origin: &ArticleTitle, goal: &ArticleTitle, max_length: usize, client: &WikiClient)
+    -> Result<i32, Box<dyn Error>> {
+    if origin == goal {
+        println!("Please input two different articles.");
+        return Ok(EXIT_FOUND);
+    }
+    let calls_before = client.request_count();
+    let verification = verify::shortest_within(origin, goal, max_length, client).await?;
+    match &verification.path {
+        Some(path) => {
+            println!("The shortest path from '{}' to '{}' is {} links long:", origin, goal, path.len() - 1);
+            pretty_print_path(path, None);
+        },
+        None => println!("There is no path of at most {} links from '{}' to '{}'.", max_length, origin, goal),
+    }
+    println!("Looked at the links of {} articles with {} API calls.", verification.expanded,
+             client.request_count() - calls_before);
+    Ok(if verification.path.is_some() { EXIT_FOUND } else { EXIT_NO_PATH })
+}
+
+/// An async function for listing the articles linking to an article, and writing them into the JSON report file
+/// if one was given
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the validated title of the article
+/// * 'limit' - The maximum amount of backlinks to list, or None to list every one of them
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+/// 
+/// # Returns
+/// 
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+async fn print_backlinks(article: &ArticleTitle, limit: Option<usize>, client: &WikiClient,
+                         config: &configs::Config) -> Result<(), Box<dyn Error>> {
+    let backlinks = wiki_api::list_backlinks(article, limit, client).await?;
+
+    println!("Articles linking to '{}':", article);
+    for (index, backlink) in backlinks.iter().enumerate() {
+        println!("{:>6}: {}", index + 1, backlink);
+    }
+    match limit {
+        Some(limit) if backlinks.len() == limit => println!("Listed the first {} backlinks.", limit),
+        _ => println!("Found {} backlinks.", backlinks.len()),
+    }
+
+    if let Some(file) = &config.json_output {
+        let report = serde_json::json!({
+            "title": article.as_str(),
+            "backlinks": backlinks.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>(),
+        });
+        statistics::write_report(file, &report).await?;
+    }
+    Ok(())
+}
+
+/// An async function for offering the user shortcuts for crawling again with the articles of the previous crawl.
+/// With --provenance the user can also ask how the previous crawl reached an article, as many times as they like
+/// 
+/// # Arguments
+/// 
+/// * 'origin' - The title of the origin article of the previous crawl
+/// * 'goal' - The title of the goal article of the previous crawl
+/// * 'provenance' - An option containing how the previous crawl reached its articles, if it was recorded
+/// * 'direction' - The LinkDirection the previous crawl followed links in
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the config data of the program
+/// 
+/// # Returns
+/// 
+/// * Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> - Result containing the validated pair for the
+///   next crawl, or None if the user wants to return to the main menu
+async fn query_follow_up(origin: ArticleTitle, goal: ArticleTitle, provenance: Option<&ProvenanceMap>,
+                         direction: LinkDirection, client: &WikiClient, config: &configs::Config)
+    -> Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> {
+    let why = if config.provenance { "why TITLE: Show how the crawl reached an article\n" } else { "" };
+    let prompt = format!(r#"
+s: Swap and crawl from '{}' to '{}'
+c: Continue from '{}' to a new article
+{}Anything else: Back to main menu
+Your choice: "#, goal, origin, goal, why);
+
+    let choice = loop {
+        let choice = match get_user_input(&prompt).await {
+            Some(string) => string,
+            None => return Ok(None),
+        };
+        match choice.split_once(' ') {
+            Some((command, title)) if config.provenance && command.eq_ignore_ascii_case("why") => {
+                print_provenance(&input_title(title.trim(), client, config).await?, provenance, direction);
+            },
+            _ => break choice,
+        }
+    };
+
+    match choice.to_lowercase().as_str() {
+        "s" => Ok(Some((goal, origin))),
+        "c" => {
+            let mut editor = autocomplete::title_editor(client, config.search_namespace);
+            let new_goal = match read_article_name("Give the name of the finishing article: ", &mut editor).await {
+                Some(string) => input_title(&string, client, config).await?,
+                None => {
+                    println!("Something went wrong while reading input!");
+                    return Ok(None);
+                },
+            };
+            if new_goal.is_empty() {
+                return Ok(None);
+            }
+
+            println!("\nValidating given article's existence...\n");
+            wiki_api::prefetch_links(&goal, LinkDirection::Outgoing, client);
+            Ok(validate_title(&new_goal, client, config).await?.map(|new_goal| (goal, new_goal)))
+        },
+        _ => Ok(None),
+    }
+}
+
+/// A function for printing how a crawl reached an article: the articles the crawl went through to reach it, the depth
+/// of each and the batch and API call each was found in
+/// 
+/// # Arguments
+/// 
+/// * 'title' - A reference to the title of the article
+/// * 'provenance' - An option containing how the crawl reached its articles, or None if the path was read from the
+///   cache without crawling
+/// * 'direction' - The LinkDirection the crawl followed links in
+fn print_provenance(title: &ArticleTitle, provenance: Option<&ProvenanceMap>, direction: LinkDirection) {
+    let provenance = match provenance {
+        Some(provenance) => provenance,
+        None => {
+            println!("The path was read from the cache, so there's no crawl to explain.");
+            return;
+        },
+    };
+    let chain = match provenance.chain(title) {
+        Some(chain) => chain,
+        None => {
+            println!("The crawl never reached '{}' among the {} articles it reached.", title, provenance.len());
+            return;
+        },
+    };
+    // A crawl following incoming links reaches each article from an article it links to
+    let relation = match direction {
+        LinkDirection::Outgoing => "linked from",
+        LinkDirection::Incoming => "linking to",
+    };
+    println!("\n'{}' was reached at depth {}:", title, chain.len() - 1);
+    for (article, step) in chain.iter() {
+        match &step.parent {
+            Some(parent) => println!("  {}: '{}', {} '{}' in batch {} (API call {})", step.depth, article, relation,
+                                     parent, step.discovery.batch, step.discovery.api_call),
+            None => println!("  {}: '{}', where the crawl started", step.depth, article),
+        }
+    }
+}
+
+/// An async function for validating a title given by the user, turning failed validation into an error in
+/// strict title mode
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the search settings
+/// 
+/// # Returns
+/// 
+/// * Result<Option<ArticleTitle>, Box<dyn Error>> - A result with the validated title, or None if the user
+///   should be returned to the menu
+async fn validate_title(article: &ArticleTitle, client: &WikiClient, config: &configs::Config) 
+    -> Result<Option<ArticleTitle>, Box<dyn Error>> {
+    let search = search_article(article, client, config).await;
+    resolve_search(article, search, config).await
+}
+
+/// An enum representing the outcome of searching for a title given by the user
+enum TitleSearch {
+    /// The title was resolved without needing the user, None if no article matched it
+    Resolved(Option<ArticleTitle>),
+    /// The user needs to choose the intended article out of these suggestions
+    Ambiguous(Vec<Suggestion>),
+}
+
+/// An async function that takes a title and validates it by searching wikipedia for it. Doesn't prompt the user,
+/// so that several titles can be searched for at the same time
+/// 
+/// Resolves to the same title if it represents an article title verbatim, to the article a redirect with the title
+/// points to, or to the closest match if there is a clear one. Otherwise returns the suggestions for the user to
+/// choose from. Redirects resolved before are read from the cache without searching, and on wikis with variants of
+/// their language a title written in another variant is looked up in the variant of the articles first
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the search settings
+/// 
+/// # Returns
+/// 
+/// * Result<TitleSearch, mediawiki::media_wiki_error::MediaWikiError> - A result with the outcome of the search
+async fn search_article(article: &ArticleTitle, client: &WikiClient, config: &configs::Config) 
+    -> Result<TitleSearch, mediawiki::media_wiki_error::MediaWikiError> {
+
+    if let Some(target) = client.cache().and_then(|cache| cache.get_redirect(article)) {
+        println!("Input '{}' redirects to '{}'.", article, target);
+        return Ok(TitleSearch::Resolved(Some(target)));
+    }
+
+    if !client.capabilities().search {
+        let title = wiki_api::lookup_title(article, client).await?;
+        if title.is_none() {
+            println!("Input: '{}' didn't match any article. Cancelling operation...\n", article);
+        }
+        return Ok(TitleSearch::Resolved(title));
+    }
+
+    // The search matches titles written in any variant, but then none of the results match the title exactly
+    if client.converts_titles() {
+        if let Some(title) = wiki_api::lookup_title(article, client).await? {
+            if &title != article {
+                println!("Input '{}' leads to '{}'.", article, title);
+            }
+            return Ok(TitleSearch::Resolved(Some(title)));
+        }
+    }
+
+    let suggestions = wiki_api::search_titles(article, config.suggestion_count, config.search_namespace, client).await?;
+
+    if suggestions.is_empty() {
+        println!("Input: '{}' didn't match any articles. Cancelling operation...\n", article);
+        return Ok(TitleSearch::Resolved(None));
+    }
+
+    if let Some(exact) = wiki_api::find_exact(article, &suggestions) {
+        if &exact.title != article {
+            println!("Input '{}' redirects to '{}'.", article, exact.title);
+        }
+        return Ok(TitleSearch::Resolved(Some(exact.title.clone())));
+    }
+
+    if !config.fuzzy_search {
+        println!("Input: '{}' didn't match any article exactly. Cancelling operation...\n", article);
+        return Ok(TitleSearch::Resolved(None));
+    }
+
+    if let Some(close) = wiki_api::find_close_match(article, &suggestions) {
+        println!("Input '{}' didn't match any article exactly, assuming you meant '{}'.", article, close.title);
+        return Ok(TitleSearch::Resolved(Some(close.title.clone())));
+    }
+
+    Ok(TitleSearch::Ambiguous(suggestions))
+}
+
+/// An async function for finishing the validation of a searched title, letting the user choose between the
+/// suggestions if needed. Failed validation is turned into an error in strict title mode
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title given by the user
+/// * 'search' - The result of search_article for the title
+/// * 'config' - A reference to the Config struct with the search settings
+/// 
+/// # Returns
+/// 
+/// * Result<Option<ArticleTitle>, Box<dyn Error>> - A result with the validated title, or None if the user
+///   should be returned to the menu
+async fn resolve_search(article: &ArticleTitle,
+                        search: Result<TitleSearch, mediawiki::media_wiki_error::MediaWikiError>,
+                        config: &configs::Config) -> Result<Option<ArticleTitle>, Box<dyn Error>> {
+    let title = match search {
+        Ok(TitleSearch::Resolved(title)) => title,
+        Ok(TitleSearch::Ambiguous(suggestions)) => choose_suggestion(article, &suggestions).await,
+        Err(error) => {
+            transcript::record(transcript::Entry::Validation, &format!("Validating '{}' failed: {}", article, error));
+            return Err(Box::new(error));
+        },
+    };
+    let validation = match &title {
+        Some(title) if title == article => format!("'{}' is an article", article),
+        Some(title) => format!("'{}' was taken as the article '{}'", article, title),
+        None => format!("'{}' didn't match an article", article),
+    };
+    transcript::record(transcript::Entry::Validation, &validation);
+
+    if title.is_none() && config.strict_titles {
+        return Err(strict_title_error(article));
+    }
+    Ok(title)
+}
+
+/// A function for constructing the error returned when a title isn't exact in strict title mode
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title that didn't match any article exactly
+/// 
+/// # Returns
+/// 
+/// * Box<dyn Error> - The constructed error
+fn strict_title_error(article: &ArticleTitle) -> Box<dyn Error> {
+    Box::new(io::Error::new(io::ErrorKind::NotFound,
+        format!("'{}' is not an exact article title and --strict-titles is set", article)))
+}
+
+/// An async function for letting the user choose the intended article out of search suggestions
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title the user originally gave
+/// * 'suggestions' - A slice of the suggestions recieved from wiki_api::search_titles
+/// 
+/// # Returns
+/// 
+/// * Option<ArticleTitle> - An option containing the chosen title, or None if the user didn't choose any
+async fn choose_suggestion(article: &ArticleTitle, suggestions: &[Suggestion]) -> Option<ArticleTitle> {
+    let mut prompt = String::new();
+    prompt.push_str("\nDidn't find an article matching exact string '");
+    prompt.push_str(article.as_str());
+    prompt.push_str("', did you mean one of these articles:\n");
+    
+    let mut iterator: u8 = 0;
+    for suggestion in suggestions.iter() {
+        iterator += 1;
+        prompt.push_str(&iterator.to_string());
+        prompt.push_str(": ");
+        prompt.push_str(suggestion.title.as_str());
+        if let Some(word_count) = suggestion.word_count {
+            prompt.push_str(&format!(" ({} words)", word_count));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str("0: None of the above.\nPlease input a number representing your intent: ");
+
+    loop {
+        match get_user_input(&prompt).await {
+            Some(string) => match string.parse::<u8>() {
+                Ok(0) => {
+                    println!("Didn't find requested article.");
+                    break;
+                }
+                Ok(num) => {
+                    if num > iterator {
+                        println!("Invalid input.");
+                        continue
+                    }
+                    
+                    match suggestions.get(usize::from(num-1)) {
+                        Some(suggestion) => return Some(suggestion.title.clone()),
+                        None => {
+                            println!("Something went wrong while fetching string.")
+                        }
+                    }
+                },
+                Err(_) => {
+                    println!("Please give a whole number between 0 and {}", iterator);
+                }
+            }
+            None => {
+                println!("Something went wrong while reading input!");
+            }
+        };
+        println!("Please try again.\n");
+    }
+
+    println!("Cancelling operation...");
+    None
+}
+
+/// An async function for printing a found path, with every article annotated with its pageviews and quality class
+/// on a line of its own if annotations were asked for
+/// 
+/// # Arguments
+/// 
+/// * 'path' - A slice of the titles on the path from origin to goal
+/// * 'hops' - An option containing the direction of each link on the path, if the path was found by an undirected
+///   crawl
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the output settings
+async fn print_path(path: &[ArticleTitle], hops: Option<&[LinkDirection]>, client: &WikiClient,
+                    config: &configs::Config) {
+    if !config.annotate_paths || path.len() < 2 {
+        pretty_print_path(path, hops);
+        return;
+    }
+
+    let annotations = match wiki_api::annotate_titles(path, client).await {
+        Ok(annotations) => annotations,
+        Err(error) => {
+            eprintln!("Error fetching the annotations of the path:\n{}", error);
+            pretty_print_path(path, hops);
+            return;
+        },
+    };
+
+    for (index, article) in path.iter().enumerate() {
+        let arrow = match (index, hops) {
+            (0, _) => "  ",
+            (_, Some(hops)) => hop_arrow(hops[index - 1]),
+            _ => "->",
+        };
+        let annotation = annotations.get(article).cloned().unwrap_or_default();
+        let mut details = vec!();
+        if let Some(views) = annotation.monthly_views {
+            details.push(format!("{} views in 30 days", views));
+        }
+        if let Some(quality) = annotation.quality {
+            details.push(format!("{}-class", quality));
+        }
+        if details.is_empty() {
+            println!("{} {}", arrow, article);
+        } else {
+            println!("{} {} ({})", arrow, article, details.join(", "));
+        }
+    }
+}
+
+/// A function for formatting the path while printing it to the user
+/// 
+/// # Arguments
+/// 
+/// * 'path' - A slice of ArticleTitle instances containing the articles in the path from origin to goal
+/// * 'hops' - An option containing the direction of each link on the path, if the path was found by an undirected
+///   crawl
+fn pretty_print_path(path: &[ArticleTitle], hops: Option<&[LinkDirection]>) {
+    if path.len() < 2 {
+        println!("Error: path should contain at least two articles!");
+        return;
+    }
+
+    // Long paths are broken onto several lines, so that the articles don't get split over the edge of the terminal
+    println!("{}", display::wrap_parts(&path_parts(path, hops), display::terminal_width().saturating_sub(1)));
+}
+
+/// A function for splitting a path into the origin followed by an arrow and an article for each link
+/// 
+/// # Arguments
+/// 
+/// * 'path' - A slice of the titles on the path from origin to goal
+/// * 'hops' - An option containing the direction of each link on the path, if the path was found by an undirected
+///   crawl
+/// 
+/// # Returns
+/// 
+/// * Vec<String> - The parts of the path
+fn path_parts(path: &[ArticleTitle], hops: Option<&[LinkDirection]>) -> Vec<String> {
+    path.iter().enumerate().map(|(index, article)| match (index, hops) {
+        (0, _) => article.to_string(),
+        (_, Some(hops)) => format!("{} {}", hops.get(index - 1).map_or("->", |hop| hop_arrow(*hop)), article),
+        _ => format!("-> {}", article),
+    }).collect()
+}
+
+/// A function for formatting a path into a single line of article names separated by arrows
+/// 
+/// # Arguments
+/// 
+/// * 'path' - A slice of the titles on the path from origin to goal
+/// 
+/// # Returns
+/// 
+/// * String - The formatted path
+fn format_path(path: &[ArticleTitle]) -> String {
+    path_parts(path, None).join(" ")
+}
+
+/// A function for formatting a path found by an undirected crawl into a single line, with an arrow pointing the way
+/// of each link between the articles
+/// 
+/// # Arguments
+/// 
+/// * 'path' - A slice of the titles on the path from origin to goal
+/// * 'hops' - A slice of the directions of the links on the path
+/// 
+/// # Returns
+/// 
+/// * String - The formatted path
+fn format_hops(path: &[ArticleTitle], hops: &[LinkDirection]) -> String {
+    path_parts(path, Some(hops)).join(" ")
+}
+
+/// Returns the arrow pointing the way of a link on a path, from the article the link is on to the linked article
+fn hop_arrow(hop: LinkDirection) -> &'static str {
+    match hop {
+        LinkDirection::Outgoing => "→",
+        LinkDirection::Incoming => "←",
+    }
+}
+
+/// An async function for finding out the directions of the links on a path, if it was found by an undirected crawl
+/// 
+/// # Arguments
+/// 
+/// * 'path' - A slice of the titles on the path from origin to goal
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// * 'config' - A reference to the Config struct with the crawl settings
+/// 
+/// # Returns
+/// 
+/// * Option<Vec<LinkDirection>> - The direction of each link on the path, or None if the crawl wasn't undirected or
+///   the directions couldn't be fetched
+async fn path_hops(path: &[ArticleTitle], client: &WikiClient, config: &configs::Config)
+    -> Option<Vec<LinkDirection>> {
+    if !config.undirected {
+        return None;
+    }
+    match wiki_api::hop_directions(path, client).await {
+        Ok(hops) => Some(hops),
+        Err(error) => {
+            eprintln!("Error fetching the directions of the links on the path:\n{}", error);
+            None
+        },
+    }
+}
+
+/// A function for getting two article names from the user. Titles are autocompleted with the opensearch API
+/// when the terminal supports a line editor
+/// 
+/// # Arguments
+/// 
+/// * 'client' - A reference to a WikiClient used for autocompletion
+/// * 'config' - A reference to the Config struct with the search settings
+/// 
+/// # Returns
+/// 
+/// * Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> - Result containing an option tuple of the recieved
+///   titles, None in the case of error
+async fn query_names(client: &WikiClient, config: &configs::Config)
+    -> Result<Option<(ArticleTitle, ArticleTitle)>, Box<dyn Error>> {
+    let mut editor = autocomplete::title_editor(client, config.search_namespace);
+    if editor.is_some() {
+        println!("(Press tab to complete article names)");
+    }
+
+    let start_article = match read_article_name("Give the name of the starting article: ", &mut editor).await {
+        Some(string) => {
+            string
+        },
+        None => {
+            println!("Something went wrong while reading input!");
+            return Ok(None);
+        },
+    };
+
+    let goal_article = match read_article_name("Give the name of the finishing article: ", &mut editor).await {
+        Some(string) => string,
+        None => {
+            println!("Something went wrong while reading input!");
+            return Ok(None);
+        },
+    };
+
+    let start_article = input_title(&start_article, client, config).await?;
+    let goal_article = input_title(&goal_article, client, config).await?;
+    Ok(Some((start_article, goal_article)))
+}
+
+/// A function for reading a single article name, with the line editor if there is one
+/// 
+/// # Arguments
+/// 
+/// * 'prompt' - A string slice to prompt the user with while querying input
+/// * 'editor' - A mutable reference to an option containing the autocompleting line editor
+/// 
+/// # Returns
+/// 
+/// * Option<String> - An Option containing the recieved String or None in the case of error
+async fn read_article_name(prompt: &str, editor: &mut Option<Editor<autocomplete::TitleHelper, DefaultHistory>>)
+    -> Option<String> {
+    match editor {
+        Some(editor) => {
+            let name = autocomplete::read_title(editor, prompt);
+            record_prompt(prompt, name.as_deref());
+            name
+        },
+        None => get_user_input(prompt).await,
+    }
+}
+
+// https://users.rust-lang.org/t/how-to-get-user-input/5176/8
+
+/// A function for simply recieving user input. Basically functions like python's input()
+/// 
+/// # Arguments
+/// 
+/// 'prompt' - A string slice to prompt the user with while querying input
+/// 
+/// # Returns
+/// 
+/// * Option<String> - An Option containing the recieved String or None in the case of error
+pub async fn get_user_input(prompt: &str) -> Option<String> {
+    print!("{}", prompt);
+    let _ = stdout().flush();
+    let mut input = String::new();
+    let input = match io::stdin().read_line(&mut input) {
+        Ok(_) => Some(input.trim().to_string()),
+        Err(_) => None,
+    };
+    record_prompt(prompt, input.as_deref());
+    input
+}
+
+/// A function for recording a question and the answer of the user into the transcript. Only the last line of the
+/// question is recorded, as the lines before it are the same menu every time
+/// 
+/// # Arguments
+/// 
+/// * 'prompt' - A string slice with the question the user was asked
+/// * 'answer' - An option containing the answer, or None if it couldn't be read
+fn record_prompt(prompt: &str, answer: Option<&str>) {
+    let question = prompt.trim().lines().last().unwrap_or_default();
+    transcript::record(transcript::Entry::Prompt, question);
+    transcript::record(transcript::Entry::Answer, answer.unwrap_or("(no answer could be read)"));
 }
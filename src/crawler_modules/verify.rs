@@ -0,0 +1,171 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+use super::article_title::ArticleTitle;
+use super::wiki_api::{self, LinkDirection};
+use super::wiki_client::WikiClient;
+
+/// The result of checking for paths up to a length between two articles
+pub struct Verification {
+    /// The shortest path within the length, or None if there is none
+    pub path: Option<Vec<ArticleTitle>>,
+    /// The amount of articles whose links were looked at
+    pub expanded: usize,
+}
+
+/// An article on the branch the search is currently going down, along with its links not yet tried
+struct Frame {
+    title: ArticleTitle,
+    // The amount of links the paths may still take from this article
+    remaining: usize,
+    links: Vec<ArticleTitle>,
+    next: usize,
+}
+
+/// The links fetched during a verification, kept so that the deepening passes don't fetch them again
+struct LinkStore<'a> {
+    client: &'a WikiClient,
+    links: HashMap<ArticleTitle, Vec<ArticleTitle>>,
+}
+
+impl LinkStore<'_> {
+    /// An async function for making sure the links of the given articles are stored, fetching the missing ones in
+    /// batches. The client reads them from the cache if they are there
+    ///
+    /// # Arguments
+    ///
+    /// * 'articles' - A slice of the titles of the articles
+    ///
+    /// # Returns
+    ///
+    /// * Result<(), Box<dyn Error>> - Result containing possible errors
+    async fn load(&mut self, articles: &[ArticleTitle]) -> Result<(), Box<dyn Error>> {
+        let missing: Vec<ArticleTitle> = articles.iter()
+            .filter(|article| !self.links.contains_key(*article))
+            .cloned()
+            .collect::<HashSet<ArticleTitle>>()
+            .into_iter()
+            .collect();
+        for batch in missing.chunks(self.client.batch_size().max(1)) {
+            let mut fetched = wiki_api::get_links(batch, LinkDirection::Outgoing, self.client).await?;
+            for article in batch.iter() {
+                self.links.insert(article.clone(), fetched.remove(article).unwrap_or_default());
+            }
+        }
+        Ok(())
+    }
+
+    /// An async function for getting the links of an article, fetching them if they aren't stored yet
+    ///
+    /// # Arguments
+    ///
+    /// * 'article' - A reference to the title of the article
+    ///
+    /// # Returns
+    ///
+    /// * Result<Vec<ArticleTitle>, Box<dyn Error>> - The links of the article
+    async fn links_of(&mut self, article: &ArticleTitle) -> Result<Vec<ArticleTitle>, Box<dyn Error>> {
+        self.load(std::slice::from_ref(article)).await?;
+        Ok(self.links.get(article).cloned().unwrap_or_default())
+    }
+}
+
+/// An async function for checking exhaustively whether a path of at most the given length leads from an article to
+/// another, without the breadth first search of the crawler. The paths are searched depth first with a growing
+/// length limit, so the first path found is a shortest one. Articles already known to have no path to the goal
+/// within the links left are skipped. If the wiki has backlinks, the articles linking to the goal are fetched once,
+/// so the last link of each path is checked without fetching the links of the article before the goal
+///
+/// # Arguments
+///
+/// * 'origin' - A reference to the title of the article the paths start from
+/// * 'goal' - A reference to the title of the article the paths end at
+/// * 'max_length' - The largest amount of links on the paths checked
+/// * 'client' - A reference to a WikiClient with a logged in api session
+///
+/// # Returns
+///
+/// * Result<Verification, Box<dyn Error>> - The shortest path within the length if there is one, or an error if
+///   the links couldn't be fetched
+pub async fn shortest_within(origin: &ArticleTitle, goal: &ArticleTitle, max_length: usize, client: &WikiClient)
+    -> Result<Verification, Box<dyn Error>> {
+    let into_goal: Option<HashSet<ArticleTitle>> = if client.capabilities().backlinks {
+        let mut backlinks = wiki_api::get_links(std::slice::from_ref(goal), LinkDirection::Incoming, client).await?;
+        Some(backlinks.remove(goal).unwrap_or_default().into_iter().collect())
+    } else {
+        None
+    };
+    let mut store = LinkStore { client, links: HashMap::new() };
+
+    for length in 1..=max_length {
+        println!("Checking the paths of {} links...", length);
+        if let Some(path) = search(origin, goal, length, into_goal.as_ref(), &mut store).await? {
+            return Ok(Verification { path: Some(path), expanded: store.links.len() });
+        }
+    }
+    Ok(Verification { path: None, expanded: store.links.len() })
+}
+
+/// An async function for searching the paths of at most the given length depth first. The earlier passes have
+/// ruled out the shorter paths, so a found path is always of the given length and goes through an article at most
+/// once, which is why the articles already on the branch aren't checked for
+///
+/// # Arguments
+///
+/// * 'origin' - A reference to the title of the article the paths start from
+/// * 'goal' - A reference to the title of the article the paths end at
+/// * 'length' - The amount of links on the paths searched
+/// * 'into_goal' - An option containing the articles linking to the goal, if the wiki has backlinks
+/// * 'store' - A mutable reference to the LinkStore of the verification
+///
+/// # Returns
+///
+/// * Result<Option<Vec<ArticleTitle>>, Box<dyn Error>> - A path of the length, or None if there is none
+async fn search(origin: &ArticleTitle, goal: &ArticleTitle, length: usize, into_goal: Option<&HashSet<ArticleTitle>>,
+                store: &mut LinkStore<'_>) -> Result<Option<Vec<ArticleTitle>>, Box<dyn Error>> {
+    let path_through = |stack: &[Frame], last: &[&ArticleTitle]| -> Vec<ArticleTitle> {
+        stack.iter().map(|frame| frame.title.clone()).chain(last.iter().map(|title| (*title).clone())).collect()
+    };
+    if into_goal.is_some_and(|into_goal| into_goal.contains(origin)) {
+        return Ok(Some(vec!(origin.clone(), goal.clone())));
+    }
+
+    // The most links each article has been searched with without reaching the goal
+    let mut exhausted: HashMap<ArticleTitle, usize> = HashMap::new();
+    let links = store.links_of(origin).await?;
+    let mut stack = vec!(Frame { title: origin.clone(), remaining: length, links, next: 0 });
+
+    while let Some(frame) = stack.last_mut() {
+        if frame.next == frame.links.len() {
+            exhausted.insert(frame.title.clone(), frame.remaining);
+            stack.pop();
+            continue;
+        }
+        let link = frame.links[frame.next].clone();
+        frame.next += 1;
+        let remaining = frame.remaining - 1;
+
+        if &link == goal {
+            return Ok(Some(path_through(&stack, &[goal])));
+        }
+        if remaining == 0 || exhausted.get(&link).is_some_and(|searched| *searched >= remaining) {
+            continue;
+        }
+        if let Some(into_goal) = into_goal {
+            if into_goal.contains(&link) {
+                return Ok(Some(path_through(&stack, &[&link, goal])));
+            }
+            if remaining == 1 {
+                continue;
+            }
+        }
+
+        let links = store.links_of(&link).await?;
+        // The links of the links are needed next unless the backlinks of the goal answer for them
+        if remaining > 2 || (remaining == 2 && into_goal.is_none()) {
+            store.load(&links).await?;
+        }
+        stack.push(Frame { title: link, remaining, links, next: 0 });
+    }
+    Ok(None)
+}
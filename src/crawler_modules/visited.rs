@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::io;
+
+use super::article_title::ArticleTitle;
+use super::cache::VisitedStore;
+use super::crawler::ErrorReporter;
+
+/// An enum representing how a crawl remembers the articles it has already reached
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VisitedMode {
+    /// The full titles are kept in memory
+    Titles,
+    /// Only 64-bit hashes of the titles are kept in memory. An article whose hash collides with an already reached
+    /// article is wrongly skipped, which is very unlikely but possible
+    Hashed,
+    /// Only hashes are kept in memory and the titles are written into a temporary table of the cache database, which
+    /// settles whether a title with an already known hash is really new. Exact, but slower
+    Verified,
+}
+
+impl VisitedMode {
+    /// Parses the mode out of the value given to --low-memory
+    ///
+    /// # Arguments
+    ///
+    /// * 'name' - A string slice with the name of the mode
+    ///
+    /// # Returns
+    ///
+    /// * Option<VisitedMode> - The mode, or None if the name wasn't a low memory mode
+    pub fn parse(name: &str) -> Option<VisitedMode> {
+        match name {
+            "hashed" => Some(VisitedMode::Hashed),
+            "verified" => Some(VisitedMode::Verified),
+            _ => None,
+        }
+    }
+}
+
+/// The storage behind a VisitedSet, depending on its mode
+enum Storage {
+    Titles(HashSet<ArticleTitle>),
+    Hashes(HashSet<u64>),
+    VerifiedHashes(HashSet<u64>, VisitedStore),
+}
+
+/// A set of the articles a crawl has already reached
+pub struct VisitedSet {
+    storage: Storage,
+    len: usize,
+}
+
+impl VisitedSet {
+    /// A constructor for VisitedSet
+    ///
+    /// # Arguments
+    ///
+    /// * 'mode' - The VisitedMode deciding how the articles are remembered
+    /// * 'cache_file' - An option containing the path of the cache database, needed by the verified mode
+    ///
+    /// # Returns
+    ///
+    /// * Result<VisitedSet, Box<dyn Error>> - A new empty VisitedSet, or an error if the verified mode was asked
+    ///   for without a cache database or the database couldn't be opened
+    pub fn new(mode: VisitedMode, cache_file: Option<&str>) -> Result<VisitedSet, Box<dyn Error>> {
+        let storage = match (mode, cache_file) {
+            (VisitedMode::Titles, _) => Storage::Titles(HashSet::new()),
+            (VisitedMode::Hashed, _) => Storage::Hashes(HashSet::new()),
+            (VisitedMode::Verified, Some(file)) => Storage::VerifiedHashes(HashSet::new(), VisitedStore::open(file)?),
+            (VisitedMode::Verified, None) => return Err(Box::new(io::Error::new(io::ErrorKind::InvalidInput,
+                "--low-memory verified needs the cache database and can't be used with --no-cache"))),
+        };
+        Ok(VisitedSet { storage, len: 0 })
+    }
+
+    /// Sets the error channel the errors of the database of the verified mode are reported into
+    pub fn set_error_reporter(&mut self, errors: ErrorReporter) {
+        if let Storage::VerifiedHashes(_, store) = &mut self.storage {
+            store.set_error_reporter(errors);
+        }
+    }
+
+    /// Adds an article into the set
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    ///
+    /// # Returns
+    ///
+    /// * bool - True if the article wasn't in the set yet
+    pub fn insert(&mut self, title: &ArticleTitle) -> bool {
+        let inserted = match &mut self.storage {
+            Storage::Titles(titles) => titles.insert(title.clone()),
+            Storage::Hashes(hashes) => hashes.insert(hash_title(title)),
+            // A new hash is always a new title, but the title still has to be stored for checking later collisions
+            Storage::VerifiedHashes(hashes, store) => {
+                let new_hash = hashes.insert(hash_title(title));
+                store.insert(title) || new_hash
+            },
+        };
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    /// Returns true if the article is in the set. In the verified mode an article whose hash is known is taken to be
+    /// in the set without asking the database, so a colliding title can be wrongly reported as reached
+    ///
+    /// # Arguments
+    ///
+    /// * 'title' - A reference to the title of the article
+    pub fn contains(&self, title: &ArticleTitle) -> bool {
+        match &self.storage {
+            Storage::Titles(titles) => titles.contains(title),
+            Storage::Hashes(hashes) | Storage::VerifiedHashes(hashes, _) => hashes.contains(&hash_title(title)),
+        }
+    }
+
+    /// Returns the amount of articles in the set
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the set has no articles
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Default for VisitedSet {
+    /// Returns an empty set keeping the full titles in memory
+    fn default() -> VisitedSet {
+        VisitedSet { storage: Storage::Titles(HashSet::new()), len: 0 }
+    }
+}
+
+/// A function for hashing a title for the low memory modes. The hasher is created with fixed keys, so the same
+/// title always gets the same hash
+///
+/// # Arguments
+///
+/// * 'title' - A reference to the title
+///
+/// # Returns
+///
+/// * u64 - The hash of the title
+fn hash_title(title: &ArticleTitle) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    title.as_str().hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,101 @@
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::article_title::{ArticleTitle, TitleCase};
+use super::wiki_api::{self, LinkDirection};
+use super::wiki_client::WikiClient;
+
+pub const DEFAULT_HUB_COUNT: usize = 1000;
+pub const MAX_HUB_COUNT: usize = 5000;
+// The hubs change slowly, so warming up more often than this would mostly refetch the same links
+pub const MIN_WARM_UP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// The links of the hubs are fetched again once they are older than this, unless the warm-up is repeated more often
+pub const DEFAULT_REFRESH_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A struct summarizing a single warm-up of the cache
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct WarmUpSummary {
+    /// The amount of hub articles warmed up
+    pub hubs: usize,
+    /// The amount of hubs whose cached links were still fresh, and weren't fetched again
+    pub fresh: usize,
+    /// The amount of hubs whose links were fetched
+    pub fetched: usize,
+    /// The amount of links fetched in total
+    pub links: usize,
+    /// The amount of API calls the warm-up took
+    pub api_calls: usize,
+}
+
+/// A function for reading the hub articles from a file, one title per line. Empty lines and lines starting with #
+/// are skipped
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the file
+/// * 'case' - The TitleCase of the wiki the titles are normalized with
+///
+/// # Returns
+///
+/// * Result<Vec<ArticleTitle>, Box<dyn Error>> - The titles in the file, or an error if it couldn't be read or
+///   didn't have any titles
+pub fn read_hubs(file: &str, case: TitleCase) -> Result<Vec<ArticleTitle>, Box<dyn Error>> {
+    let hubs: Vec<ArticleTitle> = fs::read_to_string(file)?.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| ArticleTitle::with_case(line, case))
+        .collect();
+    if hubs.is_empty() {
+        return Err(Box::new(io::Error::new(io::ErrorKind::InvalidData,
+                                           format!("'{}' doesn't list any articles", file))));
+    }
+    Ok(hubs)
+}
+
+/// An async function for fetching the links of the hub articles into the cache of the client, so that crawls
+/// passing through them don't need to. Hubs whose links were cached less than the given age ago are left as they
+/// are, and the older ones are fetched again. Titles known to be redirects are warmed up as their targets
+///
+/// # Arguments
+///
+/// * 'hubs' - A slice of the titles of the hub articles
+/// * 'refresh_age' - The Duration after which the cached links of a hub are fetched again
+/// * 'client' - A reference to a WikiClient with a cache
+///
+/// # Returns
+///
+/// * Result<WarmUpSummary, Box<dyn Error>> - How many of the hubs were fetched, or an error if the client has no
+///   cache or the links couldn't be fetched
+pub async fn warm_up(hubs: &[ArticleTitle], refresh_age: Duration, client: &WikiClient)
+    -> Result<WarmUpSummary, Box<dyn Error>> {
+    let cache = match client.cache() {
+        Some(cache) => cache,
+        None => return Err(Box::new(io::Error::new(io::ErrorKind::InvalidInput,
+                                                   "Warming up the cache needs a cache"))),
+    };
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs() as i64);
+    let oldest_fresh = now - refresh_age.as_secs() as i64;
+
+    let mut targets: Vec<ArticleTitle> = hubs.iter()
+        .map(|hub| cache.get_redirect(hub).unwrap_or_else(|| hub.clone()))
+        .collect();
+    targets.sort();
+    targets.dedup();
+    let (fresh, stale): (Vec<ArticleTitle>, Vec<ArticleTitle>) = targets.into_iter()
+        .partition(|hub| cache.links_fetched_at(hub, LinkDirection::Outgoing)
+            .is_some_and(|fetched_at| fetched_at >= oldest_fresh));
+
+    // The stale links are removed first, as they would otherwise be read from the cache instead of fetched
+    cache.remove_links(&stale, LinkDirection::Outgoing);
+    let calls_before = client.request_count();
+    let links = wiki_api::get_links(&stale, LinkDirection::Outgoing, client).await?;
+    Ok(WarmUpSummary {
+        hubs: fresh.len() + stale.len(),
+        fresh: fresh.len(),
+        fetched: links.len(),
+        links: links.values().map(Vec::len).sum(),
+        api_calls: client.request_count() - calls_before,
+    })
+}
@@ -0,0 +1,181 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{self, json};
+
+use super::article_title::ArticleTitle;
+
+pub const DEFAULT_WATCH_LOG: &str = "./crawler_watch.jsonl";
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+// Checking more often than this would mostly re-crawl an unchanged link graph
+pub const MIN_WATCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// An enum representing how the shortest path of a watched pair changed since the previous check
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PathChange {
+    /// The pair hadn't been checked before
+    First,
+    /// The path is exactly the same as before
+    Unchanged,
+    /// The path is as long as before, but goes through different articles
+    Rerouted,
+    /// The length of the path changed, given in links
+    LengthChanged { before: usize, after: usize },
+}
+
+impl PathChange {
+    /// Returns the name the change is written to the watch log with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PathChange::First => "first",
+            PathChange::Unchanged => "unchanged",
+            PathChange::Rerouted => "rerouted",
+            PathChange::LengthChanged { .. } => "length_changed",
+        }
+    }
+
+    /// Returns true if the path differs from the previously recorded one
+    pub fn is_drift(&self) -> bool {
+        matches!(self, PathChange::Rerouted | PathChange::LengthChanged { .. })
+    }
+}
+
+/// A struct representing a single check of a watched pair, stored as one JSON object per line in the watch log
+#[derive(Clone, PartialEq, Debug)]
+pub struct WatchRecord {
+    pub origin: ArticleTitle,
+    pub goal: ArticleTitle,
+    pub path: Vec<ArticleTitle>,
+    pub checked_at: u64,
+}
+
+impl WatchRecord {
+    /// A constructor for WatchRecord, timestamped with the current time
+    ///
+    /// # Arguments
+    ///
+    /// * 'path' - The path found by the check, from the origin to the goal
+    ///
+    /// # Returns
+    ///
+    /// * Option<WatchRecord> - The new record, or None if the path was empty
+    pub fn new(path: Vec<ArticleTitle>) -> Option<WatchRecord> {
+        let origin = path.first()?.clone();
+        let goal = path.last()?.clone();
+        let checked_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs());
+        Some(WatchRecord { origin, goal, path, checked_at })
+    }
+
+    /// A function for reading a record out of a line of the watch log
+    ///
+    /// # Arguments
+    ///
+    /// * 'line' - A string slice with the line
+    ///
+    /// # Returns
+    ///
+    /// * Option<WatchRecord> - The record, or None if the line was malformed
+    fn from_line(line: &str) -> Option<WatchRecord> {
+        let entry = serde_json::from_str::<serde_json::Value>(line).ok()?;
+        let path = entry["path"].as_array()?.iter()
+            .map(|title| title.as_str().map(ArticleTitle::new))
+            .collect::<Option<Vec<ArticleTitle>>>()?;
+        Some(WatchRecord {
+            origin: ArticleTitle::new(entry["origin"].as_str()?),
+            goal: ArticleTitle::new(entry["goal"].as_str()?),
+            path,
+            checked_at: entry["checked_at"].as_u64()?,
+        })
+    }
+}
+
+/// A function for finding the latest recorded check of a pair from the watch log
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the watch log
+/// * 'origin' - A reference to the title of the origin article
+/// * 'goal' - A reference to the title of the goal article
+///
+/// # Returns
+///
+/// * Option<WatchRecord> - The latest record of the pair, or None if the pair hasn't been checked before
+pub fn last_record(file: &str, origin: &ArticleTitle, goal: &ArticleTitle) -> Option<WatchRecord> {
+    let contents = fs::read_to_string(file).ok()?;
+    contents.lines()
+        .rev()
+        .filter_map(WatchRecord::from_line)
+        .find(|record| &record.origin == origin && &record.goal == goal)
+}
+
+/// A function for appending a check into the watch log
+///
+/// # Arguments
+///
+/// * 'file' - A string slice with the path of the watch log
+/// * 'record' - A reference to the record of the check
+/// * 'change' - The PathChange of the check compared to the previous one
+///
+/// # Returns
+///
+/// * Result<(), Box<dyn Error>> - Result containing possible errors
+pub fn append_record(file: &str, record: &WatchRecord, change: PathChange) -> Result<(), Box<dyn Error>> {
+    let entry = json!({
+        "origin": record.origin.as_str(),
+        "goal": record.goal.as_str(),
+        "path": record.path.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>(),
+        "length": record.path.len() - 1,
+        "change": change.as_str(),
+        "checked_at": record.checked_at,
+    });
+    let mut file = OpenOptions::new().create(true).append(true).open(file)?;
+    writeln!(file, "{}", entry)?;
+    Ok(())
+}
+
+/// A function for comparing a newly found path with the previously recorded one
+///
+/// # Arguments
+///
+/// * 'previous' - An option containing the previous record of the pair, if there is one
+/// * 'path' - A slice of the titles on the newly found path
+///
+/// # Returns
+///
+/// * PathChange - How the path changed
+pub fn compare_paths(previous: Option<&WatchRecord>, path: &[ArticleTitle]) -> PathChange {
+    let previous = match previous {
+        Some(previous) => &previous.path,
+        None => return PathChange::First,
+    };
+
+    if previous.as_slice() == path {
+        PathChange::Unchanged
+    } else if previous.len() == path.len() {
+        PathChange::Rerouted
+    } else {
+        PathChange::LengthChanged { before: previous.len() - 1, after: path.len() - 1 }
+    }
+}
+
+/// A function for formatting the interval between checks in the largest unit it's a whole multiple of
+///
+/// # Arguments
+///
+/// * 'interval' - The Duration between checks
+///
+/// # Returns
+///
+/// * String - The formatted interval, such as 1d
+pub fn format_interval(interval: Duration) -> String {
+    let seconds = interval.as_secs();
+    let units = [("w", 7 * 24 * 60 * 60), ("d", 24 * 60 * 60), ("h", 60 * 60), ("m", 60)];
+    for (unit, unit_seconds) in units.iter() {
+        if seconds >= *unit_seconds && seconds % unit_seconds == 0 {
+            return format!("{}{}", seconds / unit_seconds, unit);
+        }
+    }
+    format!("{}s", seconds)
+}
@@ -1,238 +1,1409 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::io;
 
 use serde_json;
 use mediawiki;
 
-use super::user_interface;
+use super::api_errors;
+use super::api_warnings::{self, WarningKind};
+use super::article_title::ArticleTitle;
+use super::fuzzy_match;
+use super::html_links;
+use super::wiki_client::WikiClient;
 
-// https://stackoverflow.com/questions/65976432/how-to-remove-first-and-last-character-of-a-string-in-rust
-// This is required, because wikipedia API always surrounds the titles with quotes
+// TextExtracts returns the extracts of at most this many articles in a single response
+const EXTRACTS_PER_QUERY: usize = 20;
 
-/// A function for stripping the surrounding quotes from all the strings recieved from wikipedia
+/// A function for reading the title field of a page object recieved from wikipedia
 /// 
 /// # Arguments
 /// 
-/// * 'quoted' - The quoted string slice recieved from wikipedia API
+/// * 'page' - A reference to a serde_json::Value representing a page in the API response
 /// 
 /// # Returns
 /// 
-/// * &str - An unquoted string slice of the original (=first and last character removed)
-fn strip_quotes(quoted: &str) -> &str {
-    let mut chars = quoted.chars();
-    chars.next();
-    chars.next_back();
-    chars.as_str()
+/// * Option<ArticleTitle> - An option containing the normalized title, or None if the page had no title
+fn title_from_value(page: &serde_json::Value) -> Option<ArticleTitle> {
+    page["title"].as_str().map(ArticleTitle::new)
 }
 
-/// An async function that takes a string and validates it by searching wikipedia for it.
-/// 
-/// Returns the same string if it represents an article title verbatim, or queries user for replacement articles
-/// with similiar names and returns the article gotten this way if one is found. Otherwise returns None
+/// A function for asking the wiki to convert the titles of a query written in another variant of its language into
+/// the titles of the articles, such as traditional Chinese titles on a wiki titling its articles in simplified
+/// Chinese. Only done on wikis with variants, see WikiClient::converts_titles
+///
+/// # Arguments
+///
+/// * 'query_map' - A mutable reference to the HashMap with the query parameters
+/// * 'client' - A reference to the WikiClient the query is sent with
+fn convert_titles(query_map: &mut HashMap<String, String>, client: &WikiClient) {
+    if client.converts_titles() {
+        query_map.insert("converttitles".to_string(), "1".to_string());
+    }
+}
+
+/// A function for reading the titles the wiki converted between the variants of its language from a query response.
+/// The conversions are remembered by the client, so that the crawls can tell the converted titles apart
+///
+/// # Arguments
+///
+/// * 'response' - A reference to the query response
+/// * 'client' - A reference to the WikiClient the query was sent with
+///
+/// # Returns
+///
+/// * Vec<(ArticleTitle, ArticleTitle)> - The converted titles paired up with the titles they were converted into
+fn record_conversions(response: &serde_json::Value, client: &WikiClient) -> Vec<(ArticleTitle, ArticleTitle)> {
+    let conversions: Vec<(ArticleTitle, ArticleTitle)> = response["query"]["converted"].as_array()
+        .map_or_else(Vec::new, |converted| converted.iter()
+            .filter_map(|step| Some((ArticleTitle::new(step["from"].as_str()?),
+                                     ArticleTitle::new(step["to"].as_str()?))))
+            .collect());
+    if !conversions.is_empty() {
+        client.store_conversions(&conversions);
+    }
+    conversions
+}
+
+/// A struct representing a single search result recieved while validating an article name
+#[derive(Clone, PartialEq, Debug)]
+pub struct Suggestion {
+    pub title: ArticleTitle,
+    pub word_count: Option<u64>,
+    /// The redirect to the article that matched the search, if the article was found through one
+    pub redirect: Option<ArticleTitle>,
+}
+
+/// An async function that searches wikipedia for articles matching the given title. Doesn't interact with the user
+/// in any way, so it is usable from the library side as well
 /// 
 /// # Arguments
 /// 
-/// * 'article' - A string slice of the article name
-/// * 'api' - A reference to a logged in mediawiki::api::Api instance
+/// * 'article' - A reference to the title of the article
+/// * 'limit' - The maximum amount of suggestions to return
+/// * 'namespace' - The id of the namespace to search in
+/// * 'client' - A reference to a WikiClient with a logged in api session
 /// 
 /// # Returns
 /// 
-/// * Result<Option<String>, mediawiki::media_wiki_error::MediaWikiError> - A result with a string option inside
-///     containing a valid article or None if no article found
-pub async fn validate_article(article: &str, api: &mediawiki::api::Api) 
-    -> Result<Option<String>, mediawiki::media_wiki_error::MediaWikiError> {
+/// * Result<Vec<Suggestion>, mediawiki::media_wiki_error::MediaWikiError> - A result with the found articles in
+///   order of relevance. The Vec is empty if nothing matched the title
+pub async fn search_titles(article: &ArticleTitle, limit: u8, namespace: i64, client: &WikiClient) 
+    -> Result<Vec<Suggestion>, mediawiki::media_wiki_error::MediaWikiError> {
 
-    let query_map = api.params_into(&[
+    let limit = limit.to_string();
+    let namespace = namespace.to_string();
+    let query_map = client.params_into(&[
         ("action", "query"),
         ("format", "json"),
         ("list", "search"),
-        ("srsearch", article),
-        ("srnamespace", "0"),
-        ("srlimit", "5"),
+        ("srsearch", article.as_str()),
+        ("srnamespace", &namespace),
+        ("srlimit", &limit),
+        ("srprop", "wordcount|redirecttitle"),
     ]);
 
-    let result = api.get_query_api_json(&query_map).await?;
-
-    // Super simple private function to remove doubled code below
-    fn local_exit(article: &str) -> Result<Option<String>, mediawiki::media_wiki_error::MediaWikiError> {
-        println!("Input: '{}' didn't match any articles. Cancelling operation...\n", article);
-        return Ok(None)
+    let result = client.get(&query_map).await?;
+    if let Some(error) = api_errors::parse_error(&result) {
+        return Err(error.into());
     }
 
     // Parse result
-    let found_articles = match result["query"].as_object() {
-        Some(object) => match object.get("search") {
-            Some(query) => query,
-            None => return local_exit(article),
-        },
-        None => return local_exit(article),
-    };
-
-    let articles_array = match found_articles.as_array() {
+    let articles_array = match result["query"]["search"].as_array() {
         Some(array) => array,
-        None => {
-            eprintln!("Error while unwrapping query results during article name validation!");
-            return Ok(None);
-        },
+        None => return Ok(vec!()),
     };
-        
-        
-    let found_articles: Vec<String> = articles_array  
+
+    let suggestions: Vec<Suggestion> = articles_array
         .iter()
-        .map(|article| {
-            let quoted = article["title"].to_string();
-            strip_quotes(&quoted).to_string()
-        }).collect();
-
-    match found_articles.get(0) {
-        Some(best_result) => {
-            if best_result == article {
-                return Ok(Some(article.to_string()));
-            }
-        },
-        None => {
-            println!("Didn't find any articles with name '{}', terminating. Operation", article);
-            return Ok(None);
-        },
+        .filter_map(|article| {
+            title_from_value(article).map(|title| Suggestion {
+                title,
+                word_count: article["wordcount"].as_u64(),
+                redirect: article["redirecttitle"].as_str().map(ArticleTitle::new),
+            })
+        })
+        .collect();
+
+    if let Some(cache) = client.cache() {
+        let redirects: Vec<(ArticleTitle, ArticleTitle)> = suggestions.iter()
+            .filter_map(|suggestion| suggestion.redirect.clone().map(|redirect| (redirect, suggestion.title.clone())))
+            .collect();
+        cache.store_redirects(&redirects);
     }
 
-    
+    Ok(suggestions)
+}
 
-    let mut prompt = String::new();
-    prompt.push_str("\nDidn't find an article matching exact string '");
-    prompt.push_str(article);
-    prompt.push_str("', did you mean one of these articles:\n");
-    
-    let mut iterator: u8 = 0;
-    for article_name in found_articles.iter() {
-        iterator += 1;
-        prompt.push_str(&iterator.to_string());
-        prompt.push_str(": ");
-        prompt.push_str(article_name);
-        prompt.push_str("\n");
-    }
-
-    prompt.push_str("0: None of the above.\nPlease input a number representing your intent: ");
-
-    loop {
-        match user_interface::get_user_input(&prompt).await {
-            Some(string) => match string.parse::<u8>() {
-                Ok(0) => {
-                    println!("Didn't find requested article.");
-                    break;
+/// An async function that fetches article titles starting with the given prefix using the opensearch API. Meant
+/// for autocompleting titles while the user is typing them
+/// 
+/// # Arguments
+/// 
+/// * 'prefix' - A string slice with the beginning of the title
+/// * 'limit' - The maximum amount of titles to return
+/// * 'namespace' - The id of the namespace to search in
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> - A result with the matching titles
+pub async fn opensearch(prefix: &str, limit: u8, namespace: i64, client: &WikiClient) 
+    -> Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> {
+
+    let limit = limit.to_string();
+    let namespace = namespace.to_string();
+    let query_map = client.params_into(&[
+        ("action", "opensearch"),
+        ("format", "json"),
+        ("search", prefix),
+        ("namespace", &namespace),
+        ("limit", &limit),
+        ("redirects", "resolve"),
+    ]);
+
+    let result = client.get(&query_map).await?;
+
+    // The response is an array of the search string, the titles, their descriptions and their urls
+    let titles = match result[1].as_array() {
+        Some(array) => array.iter().filter_map(|title| title.as_str()).map(ArticleTitle::new).collect(),
+        None => vec!(),
+    };
+
+    Ok(titles)
+}
+
+/// An async function that looks up the exact title of an article without searching, following redirects. Used for
+/// validating article names on wikis with search disabled. Redirects already resolved into the cache are followed
+/// without calling the API, and newly resolved ones are stored into it
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Option<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> - A result with the title of the
+///   article, or None if it doesn't exist
+pub async fn lookup_title(article: &ArticleTitle, client: &WikiClient)
+    -> Result<Option<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> {
+
+    if let Some(target) = client.cache().and_then(|cache| cache.get_redirect(article)) {
+        return Ok(Some(target));
+    }
+
+    let mut query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("titles", article.as_str()),
+        ("redirects", "1"),
+    ]);
+    convert_titles(&mut query_map, client);
+
+    let result = client.get(&query_map).await?;
+    if let Some(error) = api_errors::parse_error(&result) {
+        return Err(error.into());
+    }
+    record_conversions(&result, client);
+
+    if let (Some(cache), Some(redirects)) = (client.cache(), result["query"]["redirects"].as_array()) {
+        let redirects: Vec<(ArticleTitle, ArticleTitle)> = redirects.iter()
+            .filter_map(|redirect| Some((ArticleTitle::new(redirect["from"].as_str()?),
+                                         ArticleTitle::new(redirect["to"].as_str()?))))
+            .collect();
+        cache.store_redirects(&redirects);
+    }
+
+    let pages = match result["query"]["pages"].as_object() {
+        Some(pages) => pages,
+        None => return Ok(None),
+    };
+    Ok(pages.values()
+        .find(|page| page.get("missing").is_none() && page.get("invalid").is_none())
+        .and_then(title_from_value))
+}
+
+/// An enum representing whether a title leads to an article, as answered by check_titles
+#[derive(Clone, PartialEq, Debug)]
+pub enum TitleStatus {
+    /// The title is the title of an existing article, possibly after the wiki normalized it
+    Exists(ArticleTitle),
+    /// The title redirects to the given existing article
+    Redirect(ArticleTitle),
+    /// The title redirects to the given article, which doesn't exist
+    BrokenRedirect(ArticleTitle),
+    /// There is no article with the title
+    Missing,
+    /// The title can't be the title of an article, such as one with characters not allowed in titles or one leading
+    /// to another wiki
+    Invalid,
+}
+
+impl TitleStatus {
+    /// Returns the name the status is reported with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TitleStatus::Exists(_) => "exists",
+            TitleStatus::Redirect(_) => "redirect",
+            TitleStatus::BrokenRedirect(_) => "broken_redirect",
+            TitleStatus::Missing => "missing",
+            TitleStatus::Invalid => "invalid",
+        }
+    }
+
+    /// Returns the article the title leads to, or None if it doesn't lead to one
+    pub fn target(&self) -> Option<&ArticleTitle> {
+        match self {
+            TitleStatus::Exists(target) | TitleStatus::Redirect(target) | TitleStatus::BrokenRedirect(target) => {
+                Some(target)
+            },
+            TitleStatus::Missing | TitleStatus::Invalid => None,
+        }
+    }
+}
+
+/// An async function for checking whether titles lead to articles, as many titles at a time as the batch size of
+/// the client allows. The titles are normalized and their redirects followed by the wiki like lookup_title does, and
+/// the found redirects are stored into the cache
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles to check
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<TitleStatus>, mediawiki::media_wiki_error::MediaWikiError> - A result with the status of each
+///   title, in the same order as the titles
+pub async fn check_titles(articles: &[ArticleTitle], client: &WikiClient)
+    -> Result<Vec<TitleStatus>, mediawiki::media_wiki_error::MediaWikiError> {
+    let mut statuses = vec!();
+    for chunk in articles.chunks(client.batch_size().max(1)) {
+        // A title with a | would be split into two titles by the query, and isn't a valid title anyway
+        let (sent, unsent): (Vec<&ArticleTitle>, Vec<&ArticleTitle>) = chunk.iter()
+            .partition(|article| !article.is_empty() && !article.as_str().contains('|'));
+        let mut found: HashMap<&ArticleTitle, TitleStatus> = unsent.into_iter()
+            .map(|article| (article, TitleStatus::Invalid))
+            .collect();
+        if !sent.is_empty() {
+            let titles = sent.iter().map(|article| article.as_str()).collect::<Vec<&str>>().join("|");
+            let mut query_map = client.params_into(&[
+                ("action", "query"),
+                ("format", "json"),
+                ("titles", &titles),
+                ("redirects", "1"),
+            ]);
+            convert_titles(&mut query_map, client);
+            let result = client.get(&query_map).await?;
+            if let Some(error) = api_errors::parse_error(&result) {
+                return Err(error.into());
+            }
+            record_conversions(&result, client);
+            let steps = |key: &str| -> HashMap<String, String> {
+                result["query"][key].as_array().map_or_else(HashMap::new, |steps| steps.iter()
+                    .filter_map(|step| Some((step["from"].as_str()?.to_string(), step["to"].as_str()?.to_string())))
+                    .collect())
+            };
+            let (normalized, converted, redirects) = (steps("normalized"), steps("converted"), steps("redirects"));
+            if let Some(cache) = client.cache() {
+                let redirects: Vec<(ArticleTitle, ArticleTitle)> = redirects.iter()
+                    .map(|(from, to)| (ArticleTitle::new(from), ArticleTitle::new(to)))
+                    .collect();
+                cache.store_redirects(&redirects);
+            }
+            let pages: HashMap<String, &serde_json::Value> = result["query"]["pages"].as_object()
+                .map_or_else(HashMap::new, |pages| pages.values()
+                    .filter_map(|page| Some((page["title"].as_str()?.to_string(), page)))
+                    .collect());
+            for article in sent {
+                let mut title = article.as_str().to_string();
+                if let Some(to) = normalized.get(&title) {
+                    title = to.clone();
                 }
-                Ok(num) => {
-                    if num > iterator {
-                        println!("Invalid input.");
-                        continue
-                    }
-                    
-                    match found_articles.get(usize::from(num-1)) {
-                        Some(string) => return Ok(Some(string.to_string())),
-                        None => {
-                            println!("Something went wrong while fetching string.")
-                        }
+                if let Some(to) = converted.get(&title) {
+                    title = to.clone();
+                }
+                // The wiki follows chains of redirects, listing every step, but stops at a loop
+                let mut redirected = false;
+                for _ in 0..redirects.len() {
+                    match redirects.get(&title) {
+                        Some(to) => {
+                            title = to.clone();
+                            redirected = true;
+                        },
+                        None => break,
                     }
-                },
-                Err(_) => {
-                    println!("Please give a whole number between 0 and {}", iterator);
                 }
+                let status = match pages.get(&title) {
+                    Some(page) if page.get("invalid").is_some() => TitleStatus::Invalid,
+                    Some(page) if page.get("missing").is_some() && redirected => {
+                        TitleStatus::BrokenRedirect(ArticleTitle::new(&title))
+                    },
+                    Some(page) if page.get("missing").is_some() => TitleStatus::Missing,
+                    Some(_) if redirected => TitleStatus::Redirect(ArticleTitle::new(&title)),
+                    Some(_) => TitleStatus::Exists(ArticleTitle::new(&title)),
+                    // Titles leading to other wikis are listed apart from the pages
+                    None => TitleStatus::Invalid,
+                };
+                found.insert(article, status);
             }
-            None => {
-                println!("Something went wrong while reading input!");
-            }
+        }
+        statuses.extend(chunk.iter().map(|article| found.get(article).cloned().unwrap_or(TitleStatus::Invalid)));
+    }
+    Ok(statuses)
+}
+
+/// A function for finding the suggestion that matches the searched title exactly, if there is one. A suggestion
+/// found through a redirect with exactly the searched title counts as a match
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the searched title
+/// * 'suggestions' - A slice of the suggestions recieved from search_titles
+/// 
+/// # Returns
+/// 
+/// * Option<&Suggestion> - An option containing the exactly matching suggestion, or None if there wasn't one
+pub fn find_exact<'a>(article: &ArticleTitle, suggestions: &'a [Suggestion]) -> Option<&'a Suggestion> {
+    suggestions.iter().find(|suggestion| &suggestion.title == article)
+        .or_else(|| suggestions.iter().find(|suggestion| suggestion.redirect.as_ref() == Some(article)))
+}
+
+/// A function for finding the suggestion the searched title most likely is a typo of. A suggestion counts as a
+/// match if it differs from the title only by case, diacritics or a small edit distance, and no other suggestion
+/// is equally close
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the searched title
+/// * 'suggestions' - A slice of the suggestions recieved from search_titles
+/// 
+/// # Returns
+/// 
+/// * Option<&Suggestion> - An option containing the unambiguously closest suggestion, or None if there wasn't one
+pub fn find_close_match<'a>(article: &ArticleTitle, suggestions: &'a [Suggestion]) -> Option<&'a Suggestion> {
+    let mut best: Option<(usize, &Suggestion)> = None;
+    let mut is_ambiguous = false;
+
+    for suggestion in suggestions {
+        let distance = match fuzzy_match::close_match_distance(article.as_str(), suggestion.title.as_str()) {
+            Some(distance) => distance,
+            None => continue,
         };
-        println!("Please try again.\n");
+        match best {
+            Some((best_distance, _)) if distance > best_distance => (),
+            Some((best_distance, _)) if distance == best_distance => is_ambiguous = true,
+            _ => {
+                best = Some((distance, suggestion));
+                is_ambiguous = false;
+            },
+        }
+    }
+
+    if is_ambiguous {
+        return None;
     }
+    best.map(|(_, suggestion)| suggestion)
+}
 
-    println!("Cancelling operation...");
-    Ok(None)
+/// An enum representing which way the links of an article are followed
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LinkDirection {
+    /// Links from the article to other articles
+    Outgoing,
+    /// Links to the article from other articles (backlinks)
+    Incoming,
 }
 
-/// An sync func that fetches all the links from a given Vec of strings
+impl LinkDirection {
+    /// Returns the name the direction is stored with in the cache and reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkDirection::Outgoing => "outgoing",
+            LinkDirection::Incoming => "incoming",
+        }
+    }
+
+    /// Returns the query parameters and the result key used for fetching links in this direction
+    fn query_params(&self) -> (&'static [(&'static str, &'static str)], &'static str) {
+        match self {
+            LinkDirection::Outgoing => (&[
+                ("prop", "links"),
+                ("pllimit", "max"),
+                ("plnamespace", "0"),
+            ], "links"),
+            LinkDirection::Incoming => (&[
+                ("prop", "linkshere"),
+                ("lhlimit", "max"),
+                ("lhnamespace", "0"),
+                ("lhprop", "title"),
+            ], "linkshere"),
+        }
+    }
+}
+
+/// An async func that fetches all the links from a given Vec of strings, in the given direction. Links that are
+/// being prefetched are waited for instead of fetched again
 /// 
 /// # Arguments
 /// 
-/// * 'articles' - A reference to a Vec of Strings containing the articles of which links' should be queried
-/// * 'api' - A reference to a logged in mediawiki::api::Api instance
+/// * 'articles' - A slice of the titles of the articles of which links' should be queried
+/// * 'direction' - The LinkDirection telling whether the links or backlinks of the articles are fetched
+/// * 'client' - A reference to a WikiClient with a logged in api session
 /// 
 /// # Returns
 /// 
-/// * Result<HashMap<String, Vec<String>>, Box<dyn Error>> - A result containing a HashMap of String Vec<String> 
-///     pairs with the articles paired up with their links
-pub async fn get_links(articles: &Vec<String>, api: &mediawiki::api::Api) 
-    -> Result<HashMap<String, Vec<String>>, Box<dyn Error>> {
+/// * Result<HashMap<ArticleTitle, Vec<ArticleTitle>>, Box<dyn Error>> - A result containing a HashMap of
+///   ArticleTitle Vec<ArticleTitle> pairs with the articles paired up with their links
+pub async fn get_links(articles: &[ArticleTitle], direction: LinkDirection, client: &WikiClient) 
+    -> Result<HashMap<ArticleTitle, Vec<ArticleTitle>>, Box<dyn Error>> {
+
+    let mut result_map: HashMap<ArticleTitle, Vec<ArticleTitle>> = HashMap::new();
+    let mut remaining: Vec<ArticleTitle> = vec!();
+    for article in articles.iter() {
+        let prefetched = match client.take_prefetch(article, direction) {
+            Some(prefetch) => prefetch.await.ok().flatten(),
+            None => None,
+        };
+        match prefetched {
+            Some(links) => {
+                result_map.insert(article.clone(), links);
+            },
+            None => remaining.push(article.clone()),
+        }
+    }
 
-    let articles_string = articles.join("|");
-    let mut result_map: HashMap<String, Vec<String>> = HashMap::new();
+    if !remaining.is_empty() {
+        result_map.extend(load_links(&remaining, direction, client).await?);
+    }
+    Ok(result_map)
+}
+
+/// An async function for finding out which way each link on a path found by an undirected crawl goes. A hop is
+/// outgoing if the article links to the next one, and incoming if only the next article links back to it
+/// 
+/// # Arguments
+/// 
+/// * 'path' - A slice of the titles on the path from origin to goal
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<LinkDirection>, Box<dyn Error>> - The direction of each hop along the path, in order
+pub async fn hop_directions(path: &[ArticleTitle], client: &WikiClient)
+    -> Result<Vec<LinkDirection>, Box<dyn Error>> {
+    if path.len() < 2 {
+        return Ok(vec!());
+    }
+    let links = get_links(&path[..path.len() - 1], LinkDirection::Outgoing, client).await?;
+    Ok(path.windows(2).map(|hop| match links.get(&hop[0]) {
+        Some(links) if links.contains(&hop[1]) => LinkDirection::Outgoing,
+        _ => LinkDirection::Incoming,
+    }).collect())
+}
 
-    let result = fetch_links_from_api(&articles_string, api).await?;
+/// A function for starting to fetch the links of an article in the background. The next get_links call for the
+/// article picks up the result, so the fetch can be started before the links are actually needed
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'direction' - The LinkDirection of the links to fetch
+/// * 'client' - A reference to a WikiClient with a logged in api session
+pub fn prefetch_links(article: &ArticleTitle, direction: LinkDirection, client: &WikiClient) {
+    let task_client = client.clone();
+    let task_article = article.clone();
+    let prefetch = tokio::spawn(async move {
+        let mut links = load_links(std::slice::from_ref(&task_article), direction, &task_client).await.ok()?;
+        links.remove(&task_article)
+    });
+    client.store_prefetch(article.clone(), direction, prefetch);
+}
+
+/// An async func that loads the links of the given articles. Links found from the cache of the client aren't
+/// fetched again, and the fetched ones are added to the cache. If the client fetches the links as of a past time,
+/// they are read from the revisions of the articles one article at a time, and if it reads the links from the HTML
+/// of the articles, they are read from the HTML one article at a time
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles of which links' should be queried
+/// * 'direction' - The LinkDirection telling whether the links or backlinks of the articles are fetched
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, Vec<ArticleTitle>>, Box<dyn Error>> - A result containing a HashMap of
+///   ArticleTitle Vec<ArticleTitle> pairs with the articles paired up with their links
+async fn load_links(articles: &[ArticleTitle], direction: LinkDirection, client: &WikiClient) 
+    -> Result<HashMap<ArticleTitle, Vec<ArticleTitle>>, Box<dyn Error>> {
+
+    let mut result_map: HashMap<ArticleTitle, Vec<ArticleTitle>> = HashMap::new();
+    let mut uncached: Vec<&ArticleTitle> = vec!();
+    for article in articles.iter() {
+        match client.cache().and_then(|cache| cache.get_links(article, direction)) {
+            Some(links) => {
+                result_map.insert(article.clone(), links);
+            },
+            None => uncached.push(article),
+        }
+    }
+
+    let mut fetched_map: HashMap<ArticleTitle, Vec<ArticleTitle>> = HashMap::new();
+    let (_, links_key) = direction.query_params();
+    let mut converted = false;
 
     // Local error handling
     fn construct_error(articles: &str) -> Box<dyn Error> {
         let mut error_string = String::from("Error while fetching link data with the article collection '");
         error_string.push_str(articles);
         error_string.push_str("'\n");
-        Box::new(io::Error::new(io::ErrorKind::Other, error_string))
+        Box::new(io::Error::other(error_string))
     }
 
-    // Parse result
-    let found_pages_wrapped = match result["query"].as_object() {
-        Some(object) => match object.get("pages") {
-            Some(query) => query.as_object(),
-            None => return Err(construct_error(&articles_string)),
-        },
-        None => return Err(construct_error(&articles_string)),
-    };
+    let mut pending = uncached;
+    if let Some(timestamp) = client.as_of() {
+        for article in pending.drain(..) {
+            fetched_map.insert(article.clone(), fetch_links_as_of(article, direction, timestamp, client).await?);
+        }
+    }
+    while !pending.is_empty() {
+        if direction == LinkDirection::Outgoing && client.reads_html_links() {
+            for article in pending.drain(..) {
+                fetched_map.insert(article.clone(), html_links::fetch_links(article, client).await?);
+            }
+            break;
+        }
+        let batch_end = pending.len().min(client.batch_size());
+        let mut batch: Vec<&ArticleTitle> = pending.drain(..batch_end).collect();
 
-    let found_pages = match found_pages_wrapped {
-        Some(pages) => pages,
-        None => return Err(construct_error(&articles_string)),
-    };
+        let articles_string = batch.iter().map(|article| article.as_str()).collect::<Vec<&str>>().join("|");
+        let responses = fetch_links_from_api(&articles_string, direction, client).await?;
 
-    for (_, page) in found_pages.iter() {
-        let links_array = match page["links"].as_array() {
-            Some(array) => array,
-            None => continue,
-        };
-        let page_links: Vec<String> = links_array
-            .iter()
-            .map(|article| {
-                let quoted = article["title"].to_string();
-            strip_quotes(&quoted).to_string()
-            }).collect();
+        // Wikis can refuse to list the links to anonymous users or have the module disabled altogether, in which
+        // case the batch is read from the HTML of the articles if that's allowed
+        if let Some(code) = responses.iter().find_map(|response| response["error"]["code"].as_str()) {
+            if direction == LinkDirection::Outgoing
+                && client.fall_back_to_html(&format!("The API refused to list the links ({})", code)) {
+                pending.extend(batch);
+                continue;
+            }
+        }
 
-        let page_name = strip_quotes(&page["title"].to_string()).to_string();
+        // The API only handles the titles up to its limit and tells about the rest in a warning. Fetch the
+        // dropped titles again in smaller batches and use the limit for the future batches as well
+        if let Some(limit) = responses.iter().find_map(title_limit_from_warnings) {
+            if limit < batch.len() {
+                if client.limit_batch_size(limit) {
+                    client.report_error(format!("The API accepts only {} titles per query, lowering the batch size",
+                                                limit));
+                }
+                pending.extend(batch.drain(limit..));
+            }
+        } else if batch.len() > 1
+            && responses.last().is_some_and(|response| api_warnings::has_warning(response, WarningKind::Truncation)) {
+            // A truncated result without a continuation means links were lost, so fetch the whole batch again
+            // in halves
+            let limit = batch.len() / 2;
+            if client.limit_batch_size(limit) {
+                client.report_error(format!("The API truncated a result, lowering the batch size to {}", limit));
+            }
+            pending.extend(batch);
+            continue;
+        }
+
+        // Only the first response of a query tells which titles were converted from another variant
+        let conversions = responses.first().map_or_else(Vec::new, |response| record_conversions(response, client));
+        converted |= !conversions.is_empty();
+
+        // Parse results. Continuations can split the links of a single page between several responses
+        for result in responses.iter() {
+            let found_pages = match (result["query"]["pages"].as_object(), api_errors::parse_error(result)) {
+                (_, Some(error)) => return Err(Box::new(error)),
+                (Some(pages), None) => pages,
+                (None, None) => return Err(construct_error(&articles_string)),
+            };
+
+            for (_, page) in found_pages.iter() {
+                let page_name = match title_from_value(page) {
+                    Some(title) => title,
+                    None => continue,
+                };
+                let links: Vec<ArticleTitle> = page[links_key].as_array()
+                    .map_or_else(Vec::new, |links_array| links_array.iter().filter_map(title_from_value).collect());
 
-        result_map.insert(page_name, page_links);
+                // The links of a title converted from another variant are returned under the title it was asked for
+                let mut requested: Vec<ArticleTitle> = conversions.iter()
+                    .filter(|(_, to)| *to == page_name)
+                    .map(|(from, _)| from.clone())
+                    .collect();
+                if requested.is_empty() || batch.contains(&&page_name) {
+                    requested.push(page_name);
+                }
+                // Pages without links are kept as well, so that the cache remembers them as empty
+                for title in requested {
+                    fetched_map.entry(title).or_default().extend(links.iter().cloned());
+                }
+            }
+        }
     }
+
+    if let Some(cache) = client.cache() {
+        // The links of the converted titles are cached under the titles of their articles, so that the title is
+        // converted again when it's next fetched
+        match converted {
+            true => cache.store_links(&fetched_map.iter()
+                .map(|(title, links)| (client.converted_title(title).unwrap_or_else(|| title.clone()), links.clone()))
+                .collect(), direction),
+            false => cache.store_links(&fetched_map, direction),
+        }
+    }
+    result_map.extend(fetched_map);
     Ok(result_map)
 }
 
+/// A function for reading the title limit from a warning about too many titles in a query response
+/// 
+/// # Arguments
+/// 
+/// * 'response' - A reference to the query response
+/// 
+/// # Returns
+/// 
+/// * Option<usize> - The amount of titles the API accepts, or None if the response had no such warning
+fn title_limit_from_warnings(response: &serde_json::Value) -> Option<usize> {
+    // The warning reads 'Too many values supplied for parameter "titles". The limit is 50.'
+    api_warnings::parse_warnings(response).iter().find_map(|warning| {
+        let message = &warning.message;
+        let after_parameter = &message[message.find("parameter \"titles\"")?..];
+        let after_limit = &after_parameter[after_parameter.find("The limit is ")? + "The limit is ".len()..];
+        let digits: String = after_limit.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits.parse().ok()
+    })
+}
+
 /// An async func to be used with get_links to perform the actual wikipedia api query
 /// 
 /// # Arguments
 /// 
 /// * 'articles_string' - A string slice containing all the articles that should be queried separated by pipes
-/// * 'api' - A reference to a logged in instance of mediawiki::api::Api
+/// * 'direction' - The LinkDirection of the links to fetch
+/// * 'client' - A reference to a WikiClient with a logged in api session
 /// 
 /// # Returns
 /// 
-/// * Result<serde_json::Value, Box<dyn Error>> - A result containing a serde_json::Value that has the query result
-async fn fetch_links_from_api(articles_string: &str, api: &mediawiki::api::Api) 
-    -> Result<serde_json::Value, Box<dyn Error>> {
+/// * Result<Vec<serde_json::Value>, Box<dyn Error>> - A result containing the responses of the query and its
+///   continuations
+async fn fetch_links_from_api(articles_string: &str, direction: LinkDirection, client: &WikiClient) 
+    -> Result<Vec<serde_json::Value>, Box<dyn Error>> {
     
-    let query_map = api.params_into(&[
+    let (direction_params, _) = direction.query_params();
+    let mut query_map = client.params_into(&[
         ("action", "query"),
         ("format", "json"),
-        ("titles", &articles_string),
-        ("prop", "links"),
-        ("pllimit", "max"),
-        ("plnamespace", "0"),
+        ("titles", articles_string),
         ]);
+    query_map.extend(client.params_into(direction_params));
+    convert_titles(&mut query_map, client);
 
-    let results = api.get_query_api_json_all(&query_map).await?;
+    let results = client.get_all(&query_map).await?;
 
     Ok(results)
 }
+
+/// An async func for fetching the links of an article as they were at the given time, from the latest revision of the
+/// article before the time. The revision is parsed by the API, so the links coming from templates are included, but
+/// the templates are the current ones. Costs two requests, and only the links from the article can be fetched
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'direction' - The LinkDirection of the links to fetch, which must be outgoing
+/// * 'timestamp' - A string slice with the time as an API timestamp, such as 2015-06-01T00:00:00Z
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<ArticleTitle>, Box<dyn Error>> - A result with the links of the article, empty if the article
+///   didn't exist yet at the time or its revision has been hidden
+async fn fetch_links_as_of(article: &ArticleTitle, direction: LinkDirection, timestamp: &str, client: &WikiClient)
+    -> Result<Vec<ArticleTitle>, Box<dyn Error>> {
+    if direction == LinkDirection::Incoming {
+        return Err(Box::new(io::Error::new(io::ErrorKind::Unsupported,
+                                           "The links to an article can't be fetched as of a past time")));
+    }
+
+    let revision_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("titles", article.as_str()),
+        ("prop", "revisions"),
+        ("rvprop", "ids"),
+        ("rvlimit", "1"),
+        ("rvdir", "older"),
+        ("rvstart", timestamp),
+    ]);
+    let response = client.get(&revision_map).await?;
+    let revision = response["query"]["pages"].as_object()
+        .and_then(|pages| pages.values().next())
+        .and_then(|page| page["revisions"][0]["revid"].as_u64());
+    let revision = match revision {
+        Some(revision) => revision.to_string(),
+        None => return Ok(vec!()),
+    };
+
+    let parse_map = client.params_into(&[
+        ("action", "parse"),
+        ("format", "json"),
+        ("oldid", &revision),
+        ("prop", "links"),
+    ]);
+    let response = client.get(&parse_map).await?;
+    if let Some(code) = response["error"]["code"].as_str() {
+        client.report_error(format!("Couldn't parse the revision {} of '{}' ({}), treating it as having no links",
+                                    revision, article, code));
+        return Ok(vec!());
+    }
+    match response["parse"]["links"].as_array() {
+        Some(links) => Ok(links.iter()
+            .filter(|link| link["ns"].as_i64() == Some(0))
+            .filter_map(|link| link["*"].as_str())
+            .map(ArticleTitle::new)
+            .collect()),
+        None => Err(Box::new(io::Error::other(format!("Error while parsing the revision {} of '{}'", revision,
+                                                      article)))),
+    }
+}
+
+/// A struct representing the amount of links to and from an article
+#[derive(Clone, PartialEq, Debug)]
+pub struct Degree {
+    /// The amount of links from the article into the main namespace
+    pub outbound: usize,
+    /// The amount of main namespace articles linking to the article, None if the wiki couldn't tell
+    pub inbound: Option<usize>,
+    /// The most linked articles linking to the article, in descending order. Empty if the wiki can't sort search
+    /// results by incoming links
+    pub hubs: Vec<ArticleTitle>,
+}
+
+/// An async function for finding out the degree of an article. The outbound links are fetched like in a crawl,
+/// while the inbound links are counted with a single linksto search, which also returns the biggest hubs linking
+/// to the article. Wikis without CirrusSearch fall back to fetching every backlink
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'hub_count' - The maximum amount of hubs to return
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Degree, Box<dyn Error>> - A result containing the degree of the article
+pub async fn article_degree(article: &ArticleTitle, hub_count: u8, client: &WikiClient)
+    -> Result<Degree, Box<dyn Error>> {
+    let outbound = get_links(std::slice::from_ref(article), LinkDirection::Outgoing, client).await?
+        .remove(article)
+        .map_or(0, |links| links.len());
+
+    if client.capabilities().link_search {
+        let (inbound, hubs) = search_backlinks(article, hub_count, client).await?;
+        return Ok(Degree { outbound, inbound: Some(inbound), hubs });
+    }
+
+    let inbound = if client.capabilities().backlinks {
+        get_links(std::slice::from_ref(article), LinkDirection::Incoming, client).await?
+            .remove(article)
+            .map(|links| links.len())
+    } else {
+        None
+    };
+    Ok(Degree { outbound, inbound, hubs: vec!() })
+}
+
+/// An async function for counting the backlinks of an article with the linksto keyword of CirrusSearch
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'hub_count' - The amount of the most linked backlinks to return
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<(usize, Vec<ArticleTitle>), mediawiki::media_wiki_error::MediaWikiError> - A result with the amount
+///   of backlinks and the most linked of them
+async fn search_backlinks(article: &ArticleTitle, hub_count: u8, client: &WikiClient)
+    -> Result<(usize, Vec<ArticleTitle>), mediawiki::media_wiki_error::MediaWikiError> {
+    let search = format!("linksto:\"{}\"", article.as_str().replace('"', "\\\""));
+    let limit = hub_count.to_string();
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("list", "search"),
+        ("srsearch", &search),
+        ("srnamespace", "0"),
+        ("srlimit", &limit),
+        ("srsort", "incoming_links_desc"),
+        ("srinfo", "totalhits"),
+        ("srprop", ""),
+    ]);
+
+    let result = client.get(&query_map).await?;
+
+    let total = result["query"]["searchinfo"]["totalhits"].as_u64().unwrap_or(0) as usize;
+    let hubs = match result["query"]["search"].as_array() {
+        Some(array) => array.iter().filter_map(title_from_value).collect(),
+        None => vec!(),
+    };
+    Ok((total, hubs))
+}
+
+/// An async function for listing the articles linking to an article with list=backlinks. Articles linking through
+/// a redirect are included, while the redirects themselves are left out
+/// 
+/// # Arguments
+/// 
+/// * 'article' - A reference to the title of the article
+/// * 'limit' - The maximum amount of backlinks to list, or None to list every one of them
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> - A result with the titles of the
+///   linking articles in the order the API returned them
+pub async fn list_backlinks(article: &ArticleTitle, limit: Option<usize>, client: &WikiClient)
+    -> Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> {
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("list", "backlinks"),
+        ("bltitle", article.as_str()),
+        ("blnamespace", "0"),
+        ("bllimit", "max"),
+        ("blredirect", "1"),
+    ]);
+
+    let backlinks_of = |response: &serde_json::Value| -> Vec<ArticleTitle> {
+        let pages = match response["query"]["backlinks"].as_array() {
+            Some(pages) => pages,
+            None => return vec!(),
+        };
+        pages.iter().flat_map(|page| match page["redirlinks"].as_array() {
+            Some(redirect_links) => redirect_links.iter().filter_map(title_from_value).collect(),
+            None if page.get("redirect").is_some() => vec!(),
+            None => title_from_value(page).into_iter().collect(),
+        }).collect()
+    };
+
+    let mut listed = 0;
+    let responses = client.get_while(&query_map, |response| {
+        listed += backlinks_of(response).len();
+        limit.is_none_or(|limit| listed < limit)
+    }).await?;
+
+    // An article linking both directly and through a redirect is listed only once
+    let mut seen = HashSet::new();
+    let mut backlinks: Vec<ArticleTitle> = responses.iter()
+        .flat_map(backlinks_of)
+        .filter(|title| seen.insert(title.clone()))
+        .collect();
+    if let Some(limit) = limit {
+        backlinks.truncate(limit);
+    }
+    Ok(backlinks)
+}
+
+/// An async function for listing the articles in a category with list=categorymembers. Subcategories and files in
+/// the category are left out, and the articles in its subcategories aren't listed
+/// 
+/// # Arguments
+/// 
+/// * 'category' - A string slice with the title of the category, including the namespace prefix
+/// * 'limit' - The maximum amount of articles to list
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> - A result with the titles of the
+///   articles in the category in the order the API returned them
+pub async fn category_members(category: &str, limit: usize, client: &WikiClient)
+    -> Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> {
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("list", "categorymembers"),
+        ("cmtitle", category),
+        ("cmnamespace", "0"),
+        ("cmtype", "page"),
+        ("cmlimit", "max"),
+    ]);
+
+    let members_of = |response: &serde_json::Value| -> Vec<ArticleTitle> {
+        match response["query"]["categorymembers"].as_array() {
+            Some(members) => members.iter().filter_map(title_from_value).collect(),
+            None => vec!(),
+        }
+    };
+
+    let mut listed = 0;
+    let responses = client.get_while(&query_map, |response| {
+        listed += members_of(response).len();
+        listed < limit
+    }).await?;
+
+    let mut members: Vec<ArticleTitle> = responses.iter().flat_map(members_of).collect();
+    members.truncate(limit);
+    Ok(members)
+}
+
+/// An async function for listing the most viewed articles of the wiki, as counted by the PageViewInfo extension
+/// over the last day. Special pages and the pages of other namespaces are left out
+/// 
+/// # Arguments
+/// 
+/// * 'limit' - The maximum amount of articles to list
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> - A result with the titles of the
+///   articles, the most viewed first
+pub async fn most_viewed(limit: usize, client: &WikiClient)
+    -> Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> {
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("list", "mostviewed"),
+        ("pvimmetric", "pageviews"),
+        ("pvimlimit", "max"),
+    ]);
+
+    let articles_of = |response: &serde_json::Value| -> Vec<ArticleTitle> {
+        match response["query"]["mostviewed"].as_array() {
+            Some(pages) => pages.iter()
+                .filter(|page| page["ns"].as_i64() == Some(0))
+                .filter_map(title_from_value)
+                .collect(),
+            None => vec!(),
+        }
+    };
+
+    let mut listed = 0;
+    let responses = client.get_while(&query_map, |response| {
+        listed += articles_of(response).len();
+        listed < limit
+    }).await?;
+
+    let mut articles: Vec<ArticleTitle> = responses.iter().flat_map(articles_of).collect();
+    articles.truncate(limit);
+    Ok(articles)
+}
+
+/// A struct representing the extra information shown about an article on a found path
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Annotation {
+    /// The amount of views the article got during the last 30 days
+    pub monthly_views: Option<u64>,
+    /// The most common quality class given to the article by the wikiprojects assessing it, such as B or GA
+    pub quality: Option<String>,
+}
+
+/// An async function for fetching the pageviews and the quality classes of articles with a single query. Only the
+/// information the wiki has extensions for is fetched, see capabilities::detect
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, Annotation>, mediawiki::media_wiki_error::MediaWikiError> - A result with the
+///   annotations of the articles. Empty if the wiki supports neither pageviews nor assessments
+pub async fn annotate_titles(articles: &[ArticleTitle], client: &WikiClient)
+    -> Result<HashMap<ArticleTitle, Annotation>, mediawiki::media_wiki_error::MediaWikiError> {
+    let mut props = vec!();
+    if client.capabilities().pageviews {
+        props.push("pageviews");
+    }
+    if client.capabilities().assessments {
+        props.push("pageassessments");
+    }
+    if props.is_empty() || articles.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let titles = articles.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>().join("|");
+    let props = props.join("|");
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("titles", &titles),
+        ("prop", &props),
+        ("pvipdays", "30"),
+        ("palimit", "max"),
+    ]);
+
+    let responses = client.get_all(&query_map).await?;
+
+    // Continuations can split the assessments of a page between responses, so the classes are counted first
+    let mut annotations: HashMap<ArticleTitle, Annotation> = HashMap::new();
+    let mut classes: HashMap<ArticleTitle, HashMap<String, usize>> = HashMap::new();
+    for response in responses.iter() {
+        let pages = match response["query"]["pages"].as_object() {
+            Some(pages) => pages,
+            None => continue,
+        };
+        for page in pages.values() {
+            let title = match title_from_value(page) {
+                Some(title) => title,
+                None => continue,
+            };
+            if let Some(days) = page["pageviews"].as_object() {
+                let views = days.values().filter_map(serde_json::Value::as_u64).reduce(|sum, views| sum + views);
+                if views.is_some() {
+                    annotations.entry(title.clone()).or_default().monthly_views = views;
+                }
+            }
+            if let Some(projects) = page["pageassessments"].as_object() {
+                let counts = classes.entry(title.clone()).or_default();
+                for class in projects.values().filter_map(|project| project["class"].as_str()) {
+                    if !class.is_empty() {
+                        *counts.entry(class.to_string()).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    for (title, counts) in classes {
+        // Ties are broken alphabetically, so that the same assessments always give the same class
+        let quality = counts.into_iter()
+            .max_by(|(class, count), (other_class, other_count)| count.cmp(other_count).then(other_class.cmp(class)))
+            .map(|(class, _)| class);
+        annotations.entry(title).or_default().quality = quality;
+    }
+    Ok(annotations)
+}
+
+/// A struct representing the id and the address of an article
+#[derive(Clone, PartialEq, Debug)]
+pub struct PageInfo {
+    /// The page id of the article
+    pub id: u64,
+    /// The full url of the article
+    pub url: String,
+}
+
+/// An async function for fetching the page ids and the urls of articles, as many articles at a time as the batch
+/// size of the client allows
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, PageInfo>, mediawiki::media_wiki_error::MediaWikiError> - A result with the
+///   information of the existing articles
+pub async fn page_info(articles: &[ArticleTitle], client: &WikiClient)
+    -> Result<HashMap<ArticleTitle, PageInfo>, mediawiki::media_wiki_error::MediaWikiError> {
+    let mut info = HashMap::new();
+    for chunk in articles.chunks(client.batch_size()) {
+        let titles = chunk.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>().join("|");
+        let query_map = client.params_into(&[
+            ("action", "query"),
+            ("format", "json"),
+            ("titles", &titles),
+            ("prop", "info"),
+            ("inprop", "url"),
+        ]);
+
+        let result = client.get(&query_map).await?;
+        let pages = match result["query"]["pages"].as_object() {
+            Some(pages) => pages,
+            None => continue,
+        };
+        for page in pages.values() {
+            if let (Some(title), Some(id), Some(url)) = (title_from_value(page), page["pageid"].as_u64(),
+                                                         page["fullurl"].as_str()) {
+                info.insert(title, PageInfo { id, url: url.to_string() });
+            }
+        }
+    }
+    Ok(info)
+}
+
+/// An async function for fetching the sizes of the wikitext of articles in bytes with prop=info, which is a cheap
+/// way of estimating how many links the articles have without fetching them. Sends one query per batch size of
+/// titles
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, u64>, mediawiki::media_wiki_error::MediaWikiError> - A result with the sizes of
+///   the existing articles
+pub async fn page_lengths(articles: &[ArticleTitle], client: &WikiClient)
+    -> Result<HashMap<ArticleTitle, u64>, mediawiki::media_wiki_error::MediaWikiError> {
+    let mut lengths = HashMap::new();
+    for chunk in articles.chunks(client.batch_size().max(1)) {
+        let titles = chunk.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>().join("|");
+        let query_map = client.params_into(&[
+            ("action", "query"),
+            ("format", "json"),
+            ("titles", &titles),
+            ("prop", "info"),
+        ]);
+
+        let result = client.get(&query_map).await?;
+        let pages = match result["query"]["pages"].as_object() {
+            Some(pages) => pages,
+            None => continue,
+        };
+        for page in pages.values() {
+            if let (Some(title), Some(length)) = (title_from_value(page), page["length"].as_u64()) {
+                lengths.insert(title, length);
+            }
+        }
+    }
+    Ok(lengths)
+}
+
+/// An async function for fetching the first sentence of the introduction of articles as plain text with a single
+/// query. Needs the TextExtracts extension, see capabilities::detect
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, String>, mediawiki::media_wiki_error::MediaWikiError> - A result with the first
+///   sentences of the articles that had one. Empty if the wiki doesn't support extracts
+pub async fn first_sentences(articles: &[ArticleTitle], client: &WikiClient)
+    -> Result<HashMap<ArticleTitle, String>, mediawiki::media_wiki_error::MediaWikiError> {
+    if !client.capabilities().extracts || articles.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let titles = articles.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>().join("|");
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("titles", &titles),
+        ("prop", "extracts"),
+        ("exintro", "1"),
+        ("explaintext", "1"),
+        ("exsentences", "1"),
+        ("exlimit", "max"),
+    ]);
+
+    let result = client.get(&query_map).await?;
+
+    let pages = match result["query"]["pages"].as_object() {
+        Some(pages) => pages,
+        None => return Ok(HashMap::new()),
+    };
+    Ok(pages.values()
+        .filter_map(|page| {
+            let extract = page["extract"].as_str()?.trim();
+            if extract.is_empty() {
+                return None;
+            }
+            Some((title_from_value(page)?, extract.to_string()))
+        })
+        .collect())
+}
+
+/// An async function for fetching the introductions of articles as plain text, EXTRACTS_PER_QUERY articles at a
+/// time. Needs the TextExtracts extension, see capabilities::detect
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, String>, mediawiki::media_wiki_error::MediaWikiError> - A result with the
+///   introductions of the articles that had one. Empty if the wiki doesn't support extracts
+pub async fn introductions(articles: &[ArticleTitle], client: &WikiClient)
+    -> Result<HashMap<ArticleTitle, String>, mediawiki::media_wiki_error::MediaWikiError> {
+    let mut introductions = HashMap::new();
+    if !client.capabilities().extracts {
+        return Ok(introductions);
+    }
+
+    for chunk in articles.chunks(EXTRACTS_PER_QUERY) {
+        let titles = chunk.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>().join("|");
+        let query_map = client.params_into(&[
+            ("action", "query"),
+            ("format", "json"),
+            ("titles", &titles),
+            ("prop", "extracts"),
+            ("exintro", "1"),
+            ("explaintext", "1"),
+            ("exlimit", "max"),
+        ]);
+
+        // The extracts that don't fit in a response are given in its continuations
+        for response in client.get_all(&query_map).await?.iter() {
+            let pages = match response["query"]["pages"].as_object() {
+                Some(pages) => pages,
+                None => continue,
+            };
+            for page in pages.values() {
+                if let (Some(title), Some(extract)) = (title_from_value(page), page["extract"].as_str()) {
+                    introductions.insert(title, extract.to_string());
+                }
+            }
+        }
+    }
+    Ok(introductions)
+}
+
+/// An async function for fetching the time the articles were last edited at with a single query
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<HashMap<ArticleTitle, i64>, mediawiki::media_wiki_error::MediaWikiError> - A result with the time of the
+///   latest revision of each existing article as seconds since the unix epoch
+pub async fn last_edited(articles: &[ArticleTitle], client: &WikiClient)
+    -> Result<HashMap<ArticleTitle, i64>, mediawiki::media_wiki_error::MediaWikiError> {
+    if articles.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let titles = articles.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>().join("|");
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("titles", &titles),
+        ("prop", "revisions"),
+        ("rvprop", "timestamp"),
+    ]);
+
+    let result = client.get(&query_map).await?;
+
+    let pages = match result["query"]["pages"].as_object() {
+        Some(pages) => pages,
+        None => return Ok(HashMap::new()),
+    };
+    Ok(pages.values()
+        .filter_map(|page| {
+            let timestamp = parse_timestamp(page["revisions"][0]["timestamp"].as_str()?)?;
+            Some((title_from_value(page)?, timestamp))
+        })
+        .collect())
+}
+
+/// A function for parsing a timestamp returned by the API, such as 2021-03-04T05:06:07Z
+/// 
+/// # Arguments
+/// 
+/// * 'timestamp' - A string slice with the timestamp in UTC
+/// 
+/// # Returns
+/// 
+/// * Option<i64> - The timestamp as seconds since the unix epoch, or None if it was malformed
+pub fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    let date: Vec<i64> = date.split('-').map(|part| part.parse().ok()).collect::<Option<Vec<i64>>>()?;
+    let time: Vec<i64> = time.split(':').map(|part| part.parse().ok()).collect::<Option<Vec<i64>>>()?;
+    if date.len() != 3 || time.len() != 3 {
+        return None;
+    }
+
+    // Days since the epoch from the civil date, counting years from March so that leap days end the year
+    let (year, month, day) = (date[0], date[1], date[2]);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    Some(days * 86_400 + time[0] * 3_600 + time[1] * 60 + time[2])
+}
+
+/// A function for formatting a time as a timestamp like the ones returned by the API, the reverse of parse_timestamp
+/// 
+/// # Arguments
+/// 
+/// * 'seconds' - The time as seconds since the unix epoch
+/// 
+/// # Returns
+/// 
+/// * String - The timestamp in UTC, such as 2021-03-04T05:06:07Z
+pub fn format_timestamp(seconds: i64) -> String {
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // The civil date from the days since the epoch, with the years counted from March as in parse_timestamp
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, time / 3_600, time % 3_600 / 60, time % 60)
+}
+
+/// An async function for filtering out the articles that have no interlanguage link to the given language
+/// 
+/// # Arguments
+/// 
+/// * 'articles' - A slice of the titles of the articles
+/// * 'language' - A string slice with the language code, such as fi
+/// * 'client' - A reference to a WikiClient with a logged in api session
+/// 
+/// # Returns
+/// 
+/// * Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> - A result with the articles that have
+///   a version in the language, in the order they were given in
+pub async fn with_langlink(articles: &[ArticleTitle], language: &str, client: &WikiClient)
+    -> Result<Vec<ArticleTitle>, mediawiki::media_wiki_error::MediaWikiError> {
+    if articles.is_empty() {
+        return Ok(vec!());
+    }
+
+    let titles = articles.iter().map(ArticleTitle::as_str).collect::<Vec<&str>>().join("|");
+    let query_map = client.params_into(&[
+        ("action", "query"),
+        ("format", "json"),
+        ("titles", &titles),
+        ("prop", "langlinks"),
+        ("lllang", language),
+        ("lllimit", "max"),
+    ]);
+
+    let responses = client.get_all(&query_map).await?;
+
+    let mut translated: HashSet<ArticleTitle> = HashSet::new();
+    for response in responses.iter() {
+        let pages = match response["query"]["pages"].as_object() {
+            Some(pages) => pages,
+            None => continue,
+        };
+        translated.extend(pages.values()
+            .filter(|page| page["langlinks"].as_array().is_some_and(|langlinks| !langlinks.is_empty()))
+            .filter_map(title_from_value));
+    }
+    Ok(articles.iter().filter(|article| translated.contains(article)).cloned().collect())
+}
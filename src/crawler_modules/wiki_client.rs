@@ -0,0 +1,950 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use mediawiki;
+use regex::Regex;
+use serde_json;
+
+use super::account::AccountInfo;
+use super::api_errors;
+use super::api_warnings::{self, ApiWarning};
+use super::cache::WikiCache;
+use super::article_title::ArticleTitle;
+use super::capabilities::{WikiCapabilities, DEFAULT_MAX_TITLES};
+#[cfg(feature = "chaos")]
+use super::chaos::{self, Chaos, Failure};
+use super::crawler::ErrorReporter;
+use super::html_links;
+use super::rate_limiter::RateLimiter;
+use super::wiki_api::{self, LinkDirection};
+
+/// The handle of a background task fetching the links of an article, resolving to None if the fetch failed
+pub type Prefetch = tokio::task::JoinHandle<Option<Vec<ArticleTitle>>>;
+
+// Longer queries are sent as POST requests, as servers commonly reject URIs longer than this
+const MAX_GET_LENGTH: usize = 2000;
+// The error codes the API answers with when the account given with the assert parameter isn't logged in
+const ASSERT_FAILURES: [&str; 2] = ["assertuserfailed", "assertbotfailed"];
+// The error codes the API answers with when the client may no longer read the wiki at all, such as once the access
+// of the account has been revoked on a wiki that can't be read anonymously
+const FATAL_ERRORS: [&str; 3] = ["readapidenied", "mwoauth-invalid-authorization",
+                                 "mwoauth-invalid-authorization-invalid-user"];
+// The HTTP statuses the API answers with when the client isn't allowed to use it or it isn't there anymore
+const FATAL_STATUSES: [u16; 4] = [401, 403, 404, 410];
+// The HTTP statuses the servers answer with when they are overloaded or the client is sending too many requests
+const THROTTLE_STATUSES: [u16; 2] = [429, 503];
+// How many times in a row a throttled request is sent again before giving up on it
+const MAX_THROTTLE_RETRIES: u32 = 5;
+// How long to wait after the first throttled response that didn't say how long to wait, doubled for each retry
+const FALLBACK_COOLDOWN: Duration = Duration::from_secs(5);
+// The longest wait asked for in a Retry-After header that is honored, so that a broken or hostile server can't hold
+// the requests forever
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(600);
+// How long to wait before trying again after the wiki couldn't be reached, doubled for each retry up to the maximum
+const FIRST_RECONNECT_WAIT: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_WAIT: Duration = Duration::from_secs(60);
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+/// The credentials of the account the client is logged in with, kept for logging in again if the session expires
+struct Login {
+    username: String,
+    password: String,
+}
+
+/// A thin wrapper around mediawiki::api::Api that all wiki requests of the program go through. Clones share the
+/// same underlying session, request counter, warning counts, batch size, prefetches, cache and the date the links
+/// are fetched as of
+///
+/// Once logged in, every request asserts that the session is still logged in, so that an expired session is noticed
+/// instead of silently continuing without the rights of the account
+///
+/// When the server answers that it's overloaded or getting too many requests, every clone of the client holds its
+/// requests for as long as the server asked for in its Retry-After header, and then sends the request again. If the
+/// wiki can't be reached at all, the requests are held and tried again with a growing wait until the network returns
+///
+/// A rate limit set on a client applies to all of its clones together. Clones made with for_crawl take turns with
+/// each other when the requests have to wait for the limit, and report the errors they carry on from, such as a lost
+/// connection or a warning of the API, into the error channel of their crawl. Other clones print them
+///
+/// If the HTML fallback is allowed, the client can switch to reading the links of articles from their HTML through
+/// the REST API of the wiki once the Action API refuses to list them. The switch applies to every clone. A client
+/// with an anchor pattern always reads the links from the HTML, keeping only the links whose text matches
+///
+/// If the API answers that the client may not use it anymore or that it's gone, the error is fatal: it's kept for
+/// every clone, and every following request fails with it right away instead of being sent, so that the crawls can
+/// stop instead of retrying for nothing
+///
+/// When built with the chaos feature, failures can be injected into the requests of the client and its clones to
+/// exercise the handling above, see set_chaos
+#[derive(Clone)]
+pub struct WikiClient {
+    api: mediawiki::api::Api,
+    request_count: Arc<AtomicUsize>,
+    batch_size: Arc<AtomicUsize>,
+    warnings: Arc<Mutex<HashMap<ApiWarning, usize>>>,
+    prefetches: Arc<Mutex<HashMap<(LinkDirection, ArticleTitle), Prefetch>>>,
+    cache: Option<WikiCache>,
+    errors: ErrorReporter,
+    capabilities: WikiCapabilities,
+    account: AccountInfo,
+    login: Option<Arc<Login>>,
+    assertion: Arc<RwLock<Option<&'static str>>>,
+    relogin: Arc<tokio::sync::Mutex<()>>,
+    // Counts the times the session has been renewed, so that requests failing with the same session log in once
+    session: Arc<AtomicUsize>,
+    as_of: Option<String>,
+    anchor_pattern: Option<Arc<Regex>>,
+    rest_url: Option<String>,
+    html_links: Arc<AtomicBool>,
+    cooldown: Arc<Mutex<Option<Cooldown>>>,
+    offline: Arc<AtomicBool>,
+    degraded: Arc<AtomicBool>,
+    fatal_error: Arc<RwLock<Option<String>>>,
+    // The titles the wiki has converted from another variant of its language into the titles of the articles
+    conversions: Arc<RwLock<HashMap<ArticleTitle, ArticleTitle>>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    lane: usize,
+    lanes: Arc<AtomicUsize>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<Chaos>>,
+}
+
+/// The time the requests of a client are held until after a throttled response or a lost connection
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Cooldown {
+    /// The moment the requests may continue
+    pub until: Instant,
+    /// Why the requests are held
+    pub reason: CooldownReason,
+}
+
+/// An enum representing the reasons the requests of a client can be held for
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CooldownReason {
+    /// The server asked for the wait in a Retry-After header
+    Requested,
+    /// The server throttled the client without saying how long to wait, so the client guessed it
+    Throttled,
+    /// The wiki couldn't be reached, and the connection is tried again once the wait is over
+    Offline,
+}
+
+impl WikiClient {
+    /// A constructor for WikiClient
+    ///
+    /// # Arguments
+    ///
+    /// * 'api' - A mediawiki::api::Api instance, logged in if the session requires it
+    ///
+    /// # Returns
+    ///
+    /// * WikiClient - A new WikiClient wrapping the given api
+    pub fn new(api: mediawiki::api::Api) -> WikiClient {
+        WikiClient {
+            api,
+            request_count: Arc::new(AtomicUsize::new(0)),
+            batch_size: Arc::new(AtomicUsize::new(DEFAULT_MAX_TITLES)),
+            warnings: Arc::new(Mutex::new(HashMap::new())),
+            prefetches: Arc::new(Mutex::new(HashMap::new())),
+            cache: None,
+            errors: ErrorReporter::default(),
+            capabilities: WikiCapabilities::default(),
+            account: AccountInfo::default(),
+            login: None,
+            assertion: Arc::new(RwLock::new(None)),
+            relogin: Arc::new(tokio::sync::Mutex::new(())),
+            session: Arc::new(AtomicUsize::new(0)),
+            as_of: None,
+            anchor_pattern: None,
+            rest_url: None,
+            html_links: Arc::new(AtomicBool::new(false)),
+            cooldown: Arc::new(Mutex::new(None)),
+            offline: Arc::new(AtomicBool::new(false)),
+            degraded: Arc::new(AtomicBool::new(false)),
+            fatal_error: Arc::new(RwLock::new(None)),
+            conversions: Arc::new(RwLock::new(HashMap::new())),
+            rate_limiter: None,
+            lane: 0,
+            lanes: Arc::new(AtomicUsize::new(1)),
+            #[cfg(feature = "chaos")]
+            chaos: None,
+        }
+    }
+
+    /// Sets the credentials the wrapped api is logged in with, so that every following request asserts the login and
+    /// the client can log in again if the session expires. Bot accounts are asserted to still have the bot flag
+    ///
+    /// # Arguments
+    ///
+    /// * 'username' - A string slice with the name of the account
+    /// * 'password' - A string slice with the password of the account
+    pub fn set_login(&mut self, username: &str, password: &str) {
+        self.login = Some(Arc::new(Login { username: username.to_string(), password: password.to_string() }));
+        let assertion = if self.api.user().is_bot() { "bot" } else { "user" };
+        self.set_assertion(Some(assertion));
+    }
+
+    /// Returns the account type every request asserts, or None if the client is used anonymously
+    pub fn assertion(&self) -> Option<&'static str> {
+        self.assertion.read().map_or(None, |assertion| *assertion)
+    }
+
+    /// Sets the account type every request asserts, None for anonymous requests
+    fn set_assertion(&self, assertion: Option<&'static str>) {
+        match self.assertion.write() {
+            Ok(mut current) => *current = assertion,
+            Err(error) => self.report_error(format!("Error acquiring lock for login assertion:\n{:?}", error)),
+        }
+    }
+
+    /// Limits the rate of the requests of the client and all of its clones, including the ones already made
+    ///
+    /// # Arguments
+    ///
+    /// * 'per_second' - The amount of requests allowed per second on average, more than 0
+    pub fn set_rate_limit(&mut self, per_second: f64) {
+        // A second's worth of requests can be sent at once, so slow limits don't make the first requests wait
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(per_second, per_second as u32)));
+    }
+
+    /// Returns a clone of the client for a single crawl. The clone shares everything with the client, but takes
+    /// turns with the other crawls when the requests have to wait for the rate limit, and reports the errors of its
+    /// requests and its cache with the ErrorReporter of the crawl
+    ///
+    /// # Arguments
+    ///
+    /// * 'errors' - The ErrorReporter sending into the error channel of the crawl
+    ///
+    /// # Returns
+    ///
+    /// * WikiClient - The clone of the client for the crawl
+    pub fn for_crawl(&self, errors: ErrorReporter) -> WikiClient {
+        WikiClient {
+            lane: self.lanes.fetch_add(1, Ordering::Relaxed),
+            cache: self.cache.as_ref().map(|cache| cache.with_errors(errors.clone())),
+            errors,
+            ..self.clone()
+        }
+    }
+
+    /// Reports an error the client carries on from, into the error channel of the crawl if the client belongs to one
+    ///
+    /// # Arguments
+    ///
+    /// * 'message' - A String describing the error
+    pub fn report_error(&self, message: String) {
+        self.errors.report(message);
+    }
+
+    /// Returns true if the client runs anonymously because logging in failed
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    /// Marks the client and its clones as running anonymously because logging in failed. The batch size is lowered
+    /// to the limit of anonymous users
+    pub fn set_degraded(&self) {
+        self.degraded.store(true, Ordering::Relaxed);
+        self.set_assertion(None);
+        self.limit_batch_size(self.capabilities.max_titles);
+    }
+
+    /// Returns the fatal error the API answered with, if it has answered with one, after which no more requests are
+    /// sent
+    pub fn fatal_error(&self) -> Option<String> {
+        self.fatal_error.read().ok().and_then(|fatal_error| fatal_error.clone())
+    }
+
+    /// A function for marking the API as unusable for the client and its clones. Only the first fatal error is kept
+    ///
+    /// # Arguments
+    ///
+    /// * 'message' - A String describing the error
+    ///
+    /// # Returns
+    ///
+    /// * MediaWikiError - The error to fail the request with
+    fn set_fatal_error(&self, message: String) -> mediawiki::media_wiki_error::MediaWikiError {
+        match self.fatal_error.write() {
+            Ok(mut fatal_error) if fatal_error.is_none() => *fatal_error = Some(message.clone()),
+            Ok(_) => (),
+            Err(error) => self.report_error(format!("Error acquiring lock for the fatal API error:\n{:?}", error)),
+        }
+        message.into()
+    }
+
+    /// Returns the amount of titles to query links for in a single request
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.load(Ordering::Relaxed)
+    }
+
+    /// Sets the amount of titles to query links for in a single request
+    pub fn set_batch_size(&self, batch_size: usize) {
+        self.batch_size.store(batch_size.max(1), Ordering::Relaxed);
+    }
+
+    /// Lowers the batch size to the given limit if it's currently higher, returning true if it was lowered
+    pub fn limit_batch_size(&self, limit: usize) -> bool {
+        self.batch_size.fetch_min(limit.max(1), Ordering::Relaxed) > limit.max(1)
+    }
+
+    /// Sets the capabilities detected for the wiki, see capabilities::detect
+    pub fn set_capabilities(&mut self, capabilities: WikiCapabilities) {
+        self.capabilities = capabilities;
+    }
+
+    /// Returns a reference to the capabilities of the wiki, the defaults if they haven't been detected
+    pub fn capabilities(&self) -> &WikiCapabilities {
+        &self.capabilities
+    }
+
+    /// A function for turning a title given by the user into an ArticleTitle, capitalized by the rules of the wiki
+    ///
+    /// # Arguments
+    ///
+    /// * 'raw' - A string slice with the title as given by the user
+    ///
+    /// # Returns
+    ///
+    /// * ArticleTitle - The normalized title
+    pub fn title(&self, raw: &str) -> ArticleTitle {
+        ArticleTitle::with_case(raw, self.capabilities.title_case)
+    }
+
+    /// Returns true if the titles sent to the wiki should be converted into the variant of its language the articles
+    /// are titled in, which wikis with variants such as the scripts of Chinese or Serbian do
+    pub fn converts_titles(&self) -> bool {
+        self.capabilities.variants
+    }
+
+    /// Remembers the titles the wiki converted from another variant of its language, for the client and its clones
+    ///
+    /// # Arguments
+    ///
+    /// * 'conversions' - A slice of the converted titles paired up with the titles they were converted into
+    pub fn store_conversions(&self, conversions: &[(ArticleTitle, ArticleTitle)]) {
+        match self.conversions.write() {
+            Ok(mut known) => known.extend(conversions.iter().cloned()),
+            Err(error) => self.report_error(format!("Error acquiring lock for the converted titles:\n{:?}", error)),
+        }
+    }
+
+    /// Returns the title of the article the wiki converted the given title into, if it has converted the title
+    pub fn converted_title(&self, title: &ArticleTitle) -> Option<ArticleTitle> {
+        self.conversions.read().ok().and_then(|known| known.get(title).cloned())
+    }
+
+    /// Sets the rights and the rate limits of the logged in account, see account::detect
+    pub fn set_account(&mut self, account: AccountInfo) {
+        self.account = account;
+    }
+
+    /// Returns a reference to the rights and the rate limits of the account, the defaults if the client is used
+    /// anonymously
+    pub fn account(&self) -> &AccountInfo {
+        &self.account
+    }
+
+    /// Sets the persistent cache used for the data fetched through this client. Should be opened with the wiki id
+    /// of this client so that data from different wikis never gets mixed
+    pub fn set_cache(&mut self, cache: WikiCache) {
+        self.cache = Some(cache);
+    }
+
+    /// Returns a reference to the cache of this client, if it has one
+    pub fn cache(&self) -> Option<&WikiCache> {
+        self.cache.as_ref()
+    }
+
+    /// Sets the time the links are fetched as of, so that the links of each article are read from its latest
+    /// revision before the time instead of its current links. Should be set before the cache, see wiki_id
+    ///
+    /// # Arguments
+    ///
+    /// * 'timestamp' - A string with the time as an API timestamp, such as 2015-06-01T00:00:00Z
+    pub fn set_as_of(&mut self, timestamp: String) {
+        self.as_of = Some(timestamp);
+    }
+
+    /// Returns the time the links are fetched as of, or None if the current links are fetched
+    pub fn as_of(&self) -> Option<&str> {
+        self.as_of.as_deref()
+    }
+
+    /// Allows reading the links of articles from their HTML if the Action API refuses to list them, see
+    /// html_links::rest_url
+    ///
+    /// # Returns
+    ///
+    /// * bool - True if the REST API of the wiki could be located from the url of its Action API
+    pub fn allow_html_fallback(&mut self) -> bool {
+        self.rest_url = html_links::rest_url(self.api.api_url());
+        self.rest_url.is_some()
+    }
+
+    /// Makes the client and its clones follow only the links whose text matches the given pattern, which means reading
+    /// the links from the HTML of the articles at one request per article
+    ///
+    /// # Arguments
+    ///
+    /// * 'pattern' - An arc housing the Regex the text of a link has to match
+    ///
+    /// # Returns
+    ///
+    /// * bool - True if the REST API of the wiki could be located from the url of its Action API
+    pub fn set_anchor_pattern(&mut self, pattern: Arc<Regex>) -> bool {
+        if !self.allow_html_fallback() {
+            return false;
+        }
+        self.anchor_pattern = Some(pattern);
+        self.html_links.store(true, Ordering::Relaxed);
+        true
+    }
+
+    /// Returns the pattern the text of the followed links has to match, or None if every link is followed
+    pub fn anchor_pattern(&self) -> Option<&Regex> {
+        self.anchor_pattern.as_deref()
+    }
+
+    /// Returns true if the links are read from the HTML of the articles instead of the Action API
+    pub fn reads_html_links(&self) -> bool {
+        self.html_links.load(Ordering::Relaxed)
+    }
+
+    /// Switches the client and its clones to reading the links from the HTML of the articles, reporting the switch
+    /// the first time, see report_error
+    ///
+    /// # Arguments
+    ///
+    /// * 'reason' - A string slice telling why the Action API can't be used for the links
+    ///
+    /// # Returns
+    ///
+    /// * bool - True if the links are now read from the HTML, false if the HTML fallback isn't allowed
+    pub fn fall_back_to_html(&self, reason: &str) -> bool {
+        if self.rest_url.is_none() {
+            return false;
+        }
+        if !self.html_links.swap(true, Ordering::Relaxed) {
+            self.report_error(format!("{}, reading the links from the HTML of the articles instead", reason));
+        }
+        true
+    }
+
+    /// Returns an id identifying the wiki this client is connected to. The wiki id (database name) from the site
+    /// info is preferred, as it's the same for every url pointing to the wiki, with the server url as a fallback.
+    /// The links of the past are a different link graph, so the time they are fetched as of is added to the id, and
+    /// the same goes for the links filtered by the text of their links
+    pub fn wiki_id(&self) -> String {
+        let wiki_id = if let Ok(wiki_id) = self.api.get_site_info_string("general", "wikiid") {
+            wiki_id.to_string()
+        } else {
+            match self.api.get_site_info_string("general", "server") {
+                Ok(server) => server.to_string(),
+                Err(_) => self.api.api_url().to_string(),
+            }
+        };
+        let wiki_id = match &self.as_of {
+            Some(timestamp) => format!("{}@{}", wiki_id, timestamp),
+            None => wiki_id,
+        };
+        match &self.anchor_pattern {
+            Some(pattern) => format!("{}~{}", wiki_id, pattern.as_str()),
+            None => wiki_id,
+        }
+    }
+
+    /// Returns a reference to the wrapped mediawiki::api::Api instance
+    pub fn api(&self) -> &mediawiki::api::Api {
+        &self.api
+    }
+
+    /// Returns the amount of requests sent through this client and its clones so far
+    pub fn request_count(&self) -> usize {
+        self.request_count.load(Ordering::Relaxed)
+    }
+
+    /// Stores a background fetch of the links of an article, to be picked up by the next fetch of the same links
+    ///
+    /// # Arguments
+    ///
+    /// * 'article' - The title of the article whose links are fetched
+    /// * 'direction' - The LinkDirection of the fetched links
+    /// * 'prefetch' - The handle of the task fetching the links
+    pub fn store_prefetch(&self, article: ArticleTitle, direction: LinkDirection, prefetch: Prefetch) {
+        if let Ok(mut prefetches) = self.prefetches.lock() {
+            prefetches.insert((direction, article), prefetch);
+        }
+    }
+
+    /// Takes the background fetch of the links of an article, if one has been started
+    ///
+    /// # Arguments
+    ///
+    /// * 'article' - A reference to the title of the article
+    /// * 'direction' - The LinkDirection of the links
+    ///
+    /// # Returns
+    ///
+    /// * Option<Prefetch> - The handle of the task fetching the links, or None if there is no such task
+    pub fn take_prefetch(&self, article: &ArticleTitle, direction: LinkDirection) -> Option<Prefetch> {
+        match self.prefetches.lock() {
+            Ok(mut prefetches) => prefetches.remove(&(direction, article.clone())),
+            Err(_) => None,
+        }
+    }
+
+    /// Aborts the background fetches that weren't picked up, so that they don't keep making requests after the crawl
+    /// they were started for has ended
+    pub fn abort_prefetches(&self) {
+        if let Ok(mut prefetches) = self.prefetches.lock() {
+            for (_, prefetch) in prefetches.drain() {
+                prefetch.abort();
+            }
+        }
+    }
+
+    /// Returns the cooldown the requests are currently held for, or None if they can be sent right away
+    pub fn cooldown(&self) -> Option<Cooldown> {
+        match self.cooldown.lock() {
+            Ok(cooldown) => cooldown.filter(|cooldown| cooldown.until > Instant::now()),
+            Err(_) => None,
+        }
+    }
+
+    /// Holds the requests of the client and its clones for the given time, unless they are already held for longer
+    ///
+    /// # Arguments
+    ///
+    /// * 'wait' - The Duration to hold the requests for
+    /// * 'reason' - The CooldownReason the requests are held for
+    fn cool_down(&self, wait: Duration, reason: CooldownReason) {
+        let now = Instant::now();
+        let until = now.checked_add(wait).unwrap_or_else(|| now + MAX_RETRY_AFTER);
+        match self.cooldown.lock() {
+            Ok(mut cooldown) if cooldown.is_none_or(|current| current.until < until) => {
+                *cooldown = Some(Cooldown { until, reason });
+            },
+            Ok(_) => (),
+            Err(error) => self.report_error(format!("Error acquiring lock for request cooldown:\n{:?}", error)),
+        }
+    }
+
+    /// Sets failures to be injected into the requests of the client and its clones: timeouts and 429 responses when
+    /// the requests are sent, and malformed and truncated responses when they are read
+    #[cfg(feature = "chaos")]
+    pub fn set_chaos(&mut self, chaos: Chaos) {
+        self.chaos = Some(Arc::new(chaos));
+    }
+
+    /// Returns the failures injected into the requests, if there are any
+    #[cfg(feature = "chaos")]
+    pub fn chaos(&self) -> Option<&Chaos> {
+        self.chaos.as_deref()
+    }
+
+    /// Returns true while the wiki can't be reached and the requests are held until the connection returns
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    /// A function for marking the wiki as reachable or not, telling the user when the connection is lost or returns
+    ///
+    /// # Arguments
+    ///
+    /// * 'offline' - Whether the last request failed to reach the wiki
+    fn set_offline(&self, offline: bool) {
+        match (self.offline.swap(offline, Ordering::Relaxed), offline) {
+            (false, true) => {
+                self.report_error("Lost the connection to the wiki. Holding the requests until it returns..."
+                                  .to_string());
+            },
+            (true, false) => {
+                if let Ok(mut cooldown) = self.cooldown.lock() {
+                    if cooldown.is_some_and(|cooldown| cooldown.reason == CooldownReason::Offline) {
+                        *cooldown = None;
+                    }
+                }
+                self.report_error("The connection to the wiki returned, continuing.".to_string());
+            },
+            _ => (),
+        }
+    }
+
+    /// Returns how many times each warning has been returned by the API so far
+    pub fn warning_counts(&self) -> HashMap<ApiWarning, usize> {
+        match self.warnings.lock() {
+            Ok(warnings) => warnings.clone(),
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    /// A function for counting the warnings of a response. Each distinct warning is shown to the user the first
+    /// time it's returned, as repeating it for every request would drown the progress display
+    ///
+    /// # Arguments
+    ///
+    /// * 'response' - A reference to the API response
+    fn record_warnings(&self, response: &serde_json::Value) {
+        let warnings = api_warnings::parse_warnings(response);
+        if warnings.is_empty() {
+            return;
+        }
+
+        let mut counts = match self.warnings.lock() {
+            Ok(counts) => counts,
+            Err(error) => {
+                self.report_error(format!("Error acquiring lock for API warnings:\n{:?}", error));
+                return;
+            },
+        };
+        for warning in warnings {
+            let count = counts.entry(warning.clone()).or_insert(0);
+            if *count == 0 {
+                self.report_error(format!("API warning from '{}': {}", warning.module, warning.message));
+            }
+            *count += 1;
+        }
+    }
+
+    /// Turns a slice of str tuples into a HashMap of Strings, to be used in API calls
+    pub fn params_into(&self, params: &[(&str, &str)]) -> HashMap<String, String> {
+        self.api.params_into(params)
+    }
+
+    /// An async function for performing a single request against the API. The request is sent as GET, unless the
+    /// parameters are too long to fit in the URI, in which case it's sent as POST. If the API answers that the
+    /// session isn't logged in anymore, the client logs in again and repeats the request. If logging in fails, the
+    /// client drops to anonymous requests with the lower limits of an anonymous user
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the query parameters
+    ///
+    /// # Returns
+    ///
+    /// * Result<serde_json::Value, mediawiki::media_wiki_error::MediaWikiError> - The parsed response
+    pub async fn get(&self, params: &HashMap<String, String>)
+        -> Result<serde_json::Value, mediawiki::media_wiki_error::MediaWikiError> {
+        let session = self.session.load(Ordering::Acquire);
+        let response = self.send(params).await?;
+        if let Some(error) = api_errors::parse_error(&response).filter(|error| FATAL_ERRORS.contains(&&*error.code)) {
+            return Err(self.set_fatal_error(format!("The API denied reading the wiki. {}", error)));
+        }
+        let failed = response["error"]["code"].as_str().is_some_and(|code| ASSERT_FAILURES.contains(&code));
+        if !failed {
+            return Ok(response);
+        }
+
+        self.login_again(session).await;
+        self.send(params).await
+    }
+
+    /// An async function for sending a single request with the current login assertion, see send_request
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the query parameters
+    ///
+    /// # Returns
+    ///
+    /// * Result<serde_json::Value, mediawiki::media_wiki_error::MediaWikiError> - The parsed response
+    async fn send(&self, params: &HashMap<String, String>)
+        -> Result<serde_json::Value, mediawiki::media_wiki_error::MediaWikiError> {
+        let mut params = params.clone();
+        if let Some(assertion) = self.assertion() {
+            params.insert("assert".to_string(), assertion.to_string());
+        }
+        params.insert("format".to_string(), "json".to_string());
+        let method = if encoded_length(&params) > MAX_GET_LENGTH { "POST" } else { "GET" };
+
+        let response = self.send_request(|| self.api.get_api_request_builder(&params, method)).await?;
+        let status = response.status().as_u16();
+        if FATAL_STATUSES.contains(&status) {
+            return Err(self.set_fatal_error(format!("The API answered with the HTTP status {}", status)));
+        }
+        #[cfg(feature = "chaos")]
+        let injected = self.chaos().and_then(|chaos| chaos.draw(&[Failure::MalformedJson, Failure::TruncatedBatch]));
+        let text = response.text().await?;
+        #[cfg(feature = "chaos")]
+        let text = match injected {
+            Some(Failure::MalformedJson) => chaos::malform(&text).to_string(),
+            _ => text,
+        };
+        let response: serde_json::Value = serde_json::from_str(&text)?;
+        #[cfg(feature = "chaos")]
+        let response = match injected {
+            Some(Failure::TruncatedBatch) => chaos::truncate(response),
+            _ => response,
+        };
+        self.record_warnings(&response);
+        Ok(response)
+    }
+
+    /// An async function for fetching the HTML of an article from the REST API of the wiki, going through the same
+    /// cooldowns, rate limit and request count as the API requests
+    ///
+    /// # Arguments
+    ///
+    /// * 'article' - A reference to the title of the article
+    ///
+    /// # Returns
+    ///
+    /// * Result<Option<String>, mediawiki::media_wiki_error::MediaWikiError> - The HTML of the article, or None if
+    ///   the article doesn't exist
+    pub async fn get_html(&self, article: &ArticleTitle)
+        -> Result<Option<String>, mediawiki::media_wiki_error::MediaWikiError> {
+        let rest_url = match &self.rest_url {
+            Some(rest_url) => rest_url,
+            None => return Err("The HTML fallback isn't allowed".to_string().into()),
+        };
+        let url = format!("{}/page/{}/html", rest_url, html_links::encode_title(article));
+        let response = self.send_request(|| Ok(self.api.client().get(&url)
+            .header("User-Agent", self.api.user_agent_full()))).await?;
+        if response.status().as_u16() == 404 {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("The REST API answered with status {} for '{}'", response.status().as_u16(),
+                               article).into());
+        }
+        Ok(Some(response.text().await?))
+    }
+
+    /// An async function for sending a request built by the given function and counting it. If the server answers
+    /// that it's throttled, the requests of every clone are held for the time the server asked for, after which the
+    /// request is built and sent again. If the wiki can't be reached, the request is sent again once it can be. Once
+    /// the API has answered with a fatal error, the request fails with the error without being sent
+    ///
+    /// # Arguments
+    ///
+    /// * 'build' - A function building the request
+    ///
+    /// # Returns
+    ///
+    /// * Result<mediawiki::reqwest::Response, mediawiki::media_wiki_error::MediaWikiError> - The response, which
+    ///   wasn't throttled
+    async fn send_request<F>(&self, build: F)
+        -> Result<mediawiki::reqwest::Response, mediawiki::media_wiki_error::MediaWikiError>
+        where F: Fn() -> Result<mediawiki::reqwest::RequestBuilder, mediawiki::media_wiki_error::MediaWikiError> {
+        let mut retries = 0;
+        let mut reconnect_wait = FIRST_RECONNECT_WAIT;
+        loop {
+            while let Some(cooldown) = self.cooldown() {
+                tokio::time::sleep(cooldown.until.saturating_duration_since(Instant::now())).await;
+            }
+            if let Some(fatal_error) = self.fatal_error() {
+                return Err(fatal_error.into());
+            }
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire(self.lane, &self.errors).await;
+            }
+            #[cfg(feature = "chaos")]
+            match self.chaos().and_then(|chaos| chaos.draw(&[Failure::Timeout, Failure::Throttle])) {
+                Some(Failure::Timeout) => {
+                    self.request_count.fetch_add(1, Ordering::Relaxed);
+                    return Err("Injected failure: the request timed out".to_string().into());
+                },
+                Some(_) if retries < MAX_THROTTLE_RETRIES => {
+                    self.request_count.fetch_add(1, Ordering::Relaxed);
+                    self.cool_down(chaos::INJECTED_RETRY_AFTER, CooldownReason::Requested);
+                    retries += 1;
+                    continue;
+                },
+                _ => (),
+            }
+
+            let response = match build()?.send().await {
+                Ok(response) => response,
+                // Failing to connect or to resolve the address means the network is down rather than the request
+                // being wrong, so the request is kept and sent again instead of losing the links it was for
+                Err(error) if error.is_connect() => {
+                    self.set_offline(true);
+                    self.cool_down(reconnect_wait, CooldownReason::Offline);
+                    reconnect_wait = (reconnect_wait * 2).min(MAX_RECONNECT_WAIT);
+                    continue;
+                },
+                Err(error) => {
+                    self.request_count.fetch_add(1, Ordering::Relaxed);
+                    return Err(error.into());
+                },
+            };
+            // Only the requests that reached the wiki are counted, so that waiting out a lost connection doesn't use
+            // up the API call budget of a crawl
+            self.request_count.fetch_add(1, Ordering::Relaxed);
+            self.set_offline(false);
+            let status = response.status().as_u16();
+            if THROTTLE_STATUSES.contains(&status) {
+                if retries >= MAX_THROTTLE_RETRIES {
+                    return Err(format!("The server kept answering with status {} after {} retries", status,
+                                       retries).into());
+                }
+                let requested = response.headers().get("retry-after")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after);
+                let reason = if requested.is_some() { CooldownReason::Requested } else { CooldownReason::Throttled };
+                self.cool_down(requested.unwrap_or(FALLBACK_COOLDOWN * 2u32.pow(retries)), reason);
+                retries += 1;
+                continue;
+            }
+            return Ok(response);
+        }
+    }
+
+    /// An async function for logging in again with the stored credentials. The session cookies are shared by every
+    /// clone of the wrapped api, so logging in with a clone renews the session of all of them. Requests that failed
+    /// with the same session wait for each other here, and only the first of them logs in. If logging in fails, the
+    /// client drops to anonymous requests, see set_degraded
+    ///
+    /// # Arguments
+    ///
+    /// * 'session' - The count of renewed sessions read before the failed request was sent
+    async fn login_again(&self, session: usize) {
+        let _guard = self.relogin.lock().await;
+        // Another request renewed the session while this one was on its way or waiting for the lock
+        if self.session.load(Ordering::Acquire) != session {
+            return;
+        }
+        self.report_error("The login session has expired, logging in again...".to_string());
+        let result = match &self.login {
+            Some(login) => self.api.clone().login(login.username.as_str(), login.password.as_str()).await,
+            None => Err("The client has no credentials to log in with".to_string().into()),
+        };
+        if let Err(error) = result {
+            self.report_error(format!("Logging in again failed, continuing without logging in:\n{}", error));
+            self.set_degraded();
+        }
+        // Counted even if logging in failed, as the requests waiting for the lock are sent anonymously then
+        self.session.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// An async function for performing a query and following its continuations until the API has returned
+    /// everything. Every continuation is a request of its own and counted as such
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the query parameters
+    ///
+    /// # Returns
+    ///
+    /// * Result<Vec<serde_json::Value>, mediawiki::media_wiki_error::MediaWikiError> - The parsed responses in the
+    ///   order they were recieved
+    pub async fn get_all(&self, params: &HashMap<String, String>)
+        -> Result<Vec<serde_json::Value>, mediawiki::media_wiki_error::MediaWikiError> {
+        self.get_while(params, |_| true).await
+    }
+
+    /// An async function for performing a query and following its continuations for as long as the given function
+    /// asks for more, so that long lists can be cut short without fetching them whole
+    ///
+    /// # Arguments
+    ///
+    /// * 'params' - A reference to a HashMap with the query parameters
+    /// * 'more' - A function called with each response, returning false once no more responses are needed
+    ///
+    /// # Returns
+    ///
+    /// * Result<Vec<serde_json::Value>, mediawiki::media_wiki_error::MediaWikiError> - The parsed responses in the
+    ///   order they were recieved
+    pub async fn get_while<F>(&self, params: &HashMap<String, String>, mut more: F)
+        -> Result<Vec<serde_json::Value>, mediawiki::media_wiki_error::MediaWikiError>
+        where F: FnMut(&serde_json::Value) -> bool {
+        let mut responses = vec!();
+        let mut current_params = params.clone();
+
+        loop {
+            let mut response = self.get(&current_params).await?;
+            let continue_params = match response.as_object_mut().and_then(|object| object.remove("continue")) {
+                Some(serde_json::Value::Object(object)) if more(&response) => object,
+                _ => {
+                    responses.push(response);
+                    return Ok(responses);
+                },
+            };
+            responses.push(response);
+
+            current_params = params.clone();
+            for (key, value) in continue_params.iter() {
+                let value = match value.as_str() {
+                    Some(string) => string.to_string(),
+                    None => value.to_string(),
+                };
+                current_params.insert(key.to_string(), value);
+            }
+        }
+    }
+}
+
+/// A function for parsing the value of a Retry-After header, which is either an amount of seconds or an HTTP date
+/// such as Wed, 21 Oct 2015 07:28:00 GMT. Waits longer than MAX_RETRY_AFTER are cut down to it
+///
+/// # Arguments
+///
+/// * 'value' - A string slice with the value of the header
+///
+/// # Returns
+///
+/// * Option<Duration> - The time to wait, zero for dates in the past, or None if the value was malformed
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds).min(MAX_RETRY_AFTER));
+    }
+
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    // The year of an HTTP date always has four digits, which also keeps the date arithmetic from overflowing
+    if parts.len() != 6 || parts[5] != "GMT" || parts[3].len() != 4 {
+        return None;
+    }
+    let month = MONTHS.iter().position(|month| *month == parts[2])? + 1;
+    let at = wiki_api::parse_timestamp(&format!("{}-{:02}-{}T{}Z", parts[3], month, parts[1], parts[4]))?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs() as i64;
+    Some(Duration::from_secs((at - now).max(0) as u64).min(MAX_RETRY_AFTER))
+}
+
+/// A function for estimating the length of the query parameters once they are url encoded
+///
+/// # Arguments
+///
+/// * 'params' - A reference to a HashMap with the query parameters
+///
+/// # Returns
+///
+/// * usize - The length of the encoded parameters, overestimated rather than underestimated
+fn encoded_length(params: &HashMap<String, String>) -> usize {
+    let encoded = |string: &String| -> usize {
+        string.bytes().map(|byte| if byte.is_ascii_alphanumeric() { 1 } else { 3 }).sum()
+    };
+    params.iter().map(|(key, value)| encoded(key) + encoded(value) + 2).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn caps_long_waits() {
+        assert_eq!(parse_retry_after("18446744073709551615"), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("Fri, 31 Dec 2999 23:59:59 GMT"), Some(MAX_RETRY_AFTER));
+    }
+
+    #[test]
+    fn parses_dates_in_the_past_as_no_wait() {
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        for value in ["", "soon", "-5", "1.5", "Wed, 21 Foo 2015 07:28:00 GMT", "Wed, 21 Oct 2015 07:28:00 UTC",
+                      "Wed, 21 Oct 2015 07:28 GMT", "Wed, 21 Oct 99999999999999999 07:28:00 GMT"] {
+            assert_eq!(parse_retry_after(value), None, "{}", value);
+        }
+    }
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "fixture-server")]
+
+use std::process::{Command, Stdio};
+
+use eddie_crawler::crawler_modules::demo;
+
+#[test]
+fn demo_finds_the_shortest_path_and_exits_cleanly() {
+    // Without input the tour runs through without waiting, like the smoke check of a fresh build
+    let output = Command::new(env!("CARGO_BIN_EXE_eddie_crawler"))
+        .arg("demo")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}\n{}", stdout, String::from_utf8_lossy(&output.stderr));
+
+    // The demo wiki has several shortest paths between the targets of the redirects, so only the ends are fixed
+    let path: Vec<&str> = stdout.lines()
+        .find(|line| line.starts_with("Coffee -> "))
+        .map(|line| line.split(" -> ").collect())
+        .unwrap_or_default();
+    assert_eq!(path.len(), demo::SHORTEST_PATH_LINKS + 1, "{}", stdout);
+    assert_eq!(path.last(), Some(&"Moon"));
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "fixture-server")]
+
+use std::process::{Command, Stdio};
+
+use eddie_crawler::crawler_modules::user_interface::{EXIT_BUDGET_EXHAUSTED, EXIT_FOUND, EXIT_NO_PATH};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/paths.json");
+
+fn exit_code(args: &[&str]) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_eddie_crawler"))
+        .args(["--api-fixture", FIXTURE, "--no-cache"])
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .unwrap()
+        .status
+        .code()
+}
+
+#[test]
+fn exits_with_the_outcome_of_the_crawl() {
+    assert_eq!(exit_code(&["verify", "Origin", "Goal"]), Some(EXIT_FOUND));
+    // The shortest path of the fixture is three links long
+    assert_eq!(exit_code(&["verify", "Origin", "Goal", "--max-len", "1"]), Some(EXIT_NO_PATH));
+    assert_eq!(exit_code(&["--max-api-calls", "1", "compare", "Origin", "Goal"]), Some(EXIT_BUDGET_EXHAUSTED));
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "fixture-server")]
+
+use std::fs;
+use std::process;
+
+use serde_json::{self, json};
+
+use eddie_crawler::crawler_modules::article_title::ArticleTitle;
+use eddie_crawler::crawler_modules::capabilities;
+#[cfg(feature = "chaos")]
+use eddie_crawler::crawler_modules::chaos::{Chaos, Failure};
+use eddie_crawler::crawler_modules::crawler::{self, CrawlOptions, CrawlResult, Crawler};
+use eddie_crawler::crawler_modules::fixture_server;
+use eddie_crawler::crawler_modules::wiki_api::LinkDirection;
+use eddie_crawler::crawler_modules::wiki_client::WikiClient;
+
+// The graph the crawls run on. The shortest paths from Origin to Goal go through Beta and Epsilon, or through Gamma
+// and Eta, and a longer one through Alpha, Delta and Zeta
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/paths.json");
+
+/// Starts the fixture server with the graph of FIXTURE and the given settings added to it, and connects a client to
+/// the server like a session does
+async fn connect(name: &str, settings: serde_json::Value) -> WikiClient {
+    let mut graph: serde_json::Value = serde_json::from_str(&fs::read_to_string(FIXTURE).unwrap()).unwrap();
+    for (key, value) in settings.as_object().unwrap() {
+        graph[key] = value.clone();
+    }
+    // The tests run side by side, so each one serves its graph from a file of its own
+    let file = std::env::temp_dir().join(format!("eddie_crawler_{}_{}.json", process::id(), name));
+    fs::write(&file, graph.to_string()).unwrap();
+    let api_path = fixture_server::start(file.to_str().unwrap()).await;
+    fs::remove_file(&file).unwrap();
+
+    let mut client = WikiClient::new(mediawiki::api::Api::new(&api_path.unwrap()).await.unwrap());
+    client.set_capabilities(capabilities::detect(&client).await);
+    client.set_batch_size(client.capabilities().max_titles);
+    client
+}
+
+/// Crawls from Origin to Goal with the given options
+async fn crawl(client: &WikiClient, options: CrawlOptions) -> CrawlResult {
+    let crawler_arc = Crawler::new_arc(&ArticleTitle::new("Origin"), &ArticleTitle::new("Goal"),
+                                       LinkDirection::Outgoing, options);
+    crawler::start(crawler_arc, client).await
+}
+
+fn titles(titles: &[&str]) -> Vec<ArticleTitle> {
+    titles.iter().map(|title| ArticleTitle::new(title)).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn finds_the_shortest_path() {
+    let client = connect("shortest", json!({})).await;
+    let result = crawl(&client, CrawlOptions { deterministic: true, ..CrawlOptions::default() }).await;
+
+    assert_eq!(result.path(), Some(&titles(&["Origin", "Beta", "Epsilon", "Goal"])));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn follows_the_continuations_of_the_links() {
+    // Every query answers with a single link, so the rest of the links are only reached through continuations
+    let client = connect("continuation", json!({ "link_limit": 1 })).await;
+    let result = crawl(&client, CrawlOptions { deterministic: true, ..CrawlOptions::default() }).await;
+
+    assert_eq!(result.path(), Some(&titles(&["Origin", "Beta", "Epsilon", "Goal"])));
+    assert!(result.stats.total_api_calls() > 4);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn collects_only_the_shortest_paths() {
+    let client = connect("paths", json!({})).await;
+    let result = crawl(&client, CrawlOptions { max_paths: 3, ..CrawlOptions::default() }).await;
+
+    let mut paths: Vec<Vec<ArticleTitle>> = result.path().into_iter().chain(result.other_paths.iter())
+        .cloned()
+        .collect();
+    paths.sort();
+    assert_eq!(paths, vec!(titles(&["Origin", "Beta", "Epsilon", "Goal"]),
+                           titles(&["Origin", "Gamma", "Eta", "Goal"])));
+}
+
+#[cfg(feature = "chaos")]
+#[tokio::test(flavor = "multi_thread")]
+async fn finds_the_same_path_through_injected_failures() {
+    // Like --inject-failures 0.3 --chaos-seed 27. The crawl sends its requests one after another, so the seed makes
+    // the same requests fail on every run, one with a timeout and one with a 429 response
+    let mut client = connect("chaos", json!({})).await;
+    client.set_chaos(Chaos::new(0.3, Some(27)));
+    let result = crawl(&client, CrawlOptions { deterministic: true, ..CrawlOptions::default() }).await;
+
+    assert_eq!(result.path(), Some(&titles(&["Origin", "Beta", "Epsilon", "Goal"])));
+    let injected = client.chaos().unwrap().injected();
+    assert!(injected.contains(&(Failure::Timeout, 1)), "{:?}", injected);
+    assert!(injected.contains(&(Failure::Throttle, 1)), "{:?}", injected);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn collects_the_shortest_paths_when_a_deeper_batch_arrives_first() {
+    // Every hub leads to the goal through an article of its own, which also links one article deeper. The single
+    // worker thread runs the workers in turns, so the deeper batch of the first path found is sent while the worker
+    // of the hubs is still sending the rest of the articles on the depth of the paths
+    const HUBS: usize = 50;
+    let mut articles = serde_json::Map::new();
+    articles.insert("Origin".to_string(), json!((0..HUBS).map(|hub| format!("Hub {}", hub)).collect::<Vec<String>>()));
+    for hub in 0..HUBS {
+        articles.insert(format!("Hub {}", hub), json!([format!("Middle {}", hub)]));
+        articles.insert(format!("Middle {}", hub), json!(["Goal", format!("Tail {}", hub)]));
+    }
+    let client = connect("deeper_batch", json!({ "articles": articles })).await;
+    let result = crawl(&client, CrawlOptions { max_paths: HUBS, ..CrawlOptions::default() }).await;
+
+    let paths: Vec<&Vec<ArticleTitle>> = result.path().into_iter().chain(result.other_paths.iter()).collect();
+    assert_eq!(paths.len(), HUBS);
+    assert!(paths.iter().all(|path| path.len() == 4), "{:?}", paths);
+}